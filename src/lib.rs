@@ -1,15 +1,19 @@
 use std::ffi::OsStr;
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::collections::HashMap;
-use std::io::prelude::*;
 use std::{fs, io};
 use convert_case::{Boundary, Case, Casing};
 use egui::Ui;
+use serde::{Deserialize, Serialize};
 
 pub mod logic;
 // use logic::app::DFGraphicsHelper;
-use logic::error::{Result, DFGHError, wrap_import_buffer_error, wrap_import_file_error};
+use logic::error::{Result, DFGHError, group_skipped_files, wrap_import_buffer_error, wrap_import_file_error};
+use logic::vfs::{FileSystem, DiskFileSystem};
+use logic::export::{ExportFile, ExportPlan};
+use logic::catalog::RawCatalog;
+use logic::lint::LintConfig;
 
 pub const PADDING: f32 = 8.0;
 
@@ -49,8 +53,9 @@ macro_rules! index_err {
     };
 }
 
-macro_rules! graphics_file_export {
-    ($prefix:expr, $name:ident, $suffix:expr, $vector:ident, $path:ident) => {
+//path of a graphics file relative to the mod folder
+macro_rules! graphics_file_path {
+    ($prefix:expr, $name:ident, $suffix:expr) => {
         {
             let bare_name = $name.clone()
                 .replace(".txt", "")
@@ -61,14 +66,7 @@ macro_rules! graphics_file_export {
 
             let gf_name = format!("{0}{1}{2}.txt", $prefix, bare_name, $suffix);
 
-            let graphics_file = fs::File::create(
-                $path
-                .join("graphics")
-                .join(gf_name.clone()))?;
-            
-            let graphics_file_writer = io::LineWriter::new(graphics_file);
-
-            graphics_file_writer
+            PathBuf::from("graphics").join(gf_name)
         }
     };
 }
@@ -76,24 +74,138 @@ macro_rules! graphics_file_export {
 pub trait RAW {
     fn new() -> Self;
 
-    fn read(buffer: Vec<Vec<String>>, raw_buffer: Vec<String>, path: &PathBuf) -> (Self, Vec<DFGHError>) where Self: Sized;
+    fn read(buffer: Vec<Token>, raw_buffer: Vec<String>, path: &Path) -> (Self, Vec<DFGHError>) where Self: Sized;
 
     fn display(&self) -> String;
+
+    /// Read an object from raw text, such as a block pasted from another mod.
+    ///
+    /// Errors point at the line and column in the text as if it had been read from a file at `path`.
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let text = "[LAYER:BODY:DWARF:0:x]\n\t[CONDITION_NOT_DYED]\n";
+    ///
+    /// let (layer, errors) = Layer::read_text(text, "clipboard".as_ref());
+    ///
+    /// assert_eq!(("BODY", "DWARF"), (layer.name.as_str(), layer.tile_name.as_str()));
+    /// assert_eq!(Condition::NotDyed, layer.conditions[0]);
+    /// assert!(errors[0].to_string().starts_with("Failed to import line 1, column 1"));
+    /// ```
+    fn read_text(text: &str, path: &Path) -> (Self, Vec<DFGHError>) where Self: Sized {
+        let raw_lines: Vec<String> = text.split_inclusive('\n').map(|l| l.to_string()).collect();
+        let tokens = Token::tokenize(&raw_lines, path);
+
+        if tokens.is_empty() {
+            return (Self::new(), Vec::new());
+        }
+
+        let (object, errors) = Self::read(tokens.clone(), raw_lines.clone(), path);
+        let errors = errors
+            .iter()
+            .map(|e| wrap_import_file_error(raw_lines.clone(), &tokens, e, tokens.len(), path))
+            .collect();
+
+        (object, errors)
+    }
 }
 pub trait Menu {
     fn menu(&mut self, ui: &mut Ui, shared: &mut Shared);
 }
 
+/// A bracketed token from a raw file split into its arguments at ':'s, with where it was found.
+/// 
+/// Dereferences to the vector of arguments so it can be matched like a line vector.
 #[derive(Clone, Debug, Default, PartialEq)]
+pub struct Token {
+    pub args: Vec<String>,      //arguments between '[' and ']'
+    pub path: PathBuf,          //file the token was read from
+    pub line: usize,            //line index in the file (starting at 0)
+    pub columns: std::ops::Range<usize>, //character columns from the '[' up to and including the ']'
+}
+impl Token {
+    /// Read every bracketed token from a set of lines.
+    /// 
+    /// Text outside of brackets is ignored like DF does, and a token without a closing bracket is dropped.
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let lines = vec![
+    ///     "comment [LAYER:BODY:DWARF:0:0][CONDITION_CASTE:MALE] trailing\n".to_string(),
+    ///     "\t[LAYER_SET:DEFAULT]\n".to_string(),
+    ///     "[UNCLOSED:TOKEN\n".to_string(),
+    /// ];
+    /// let tokens = Token::tokenize(&lines, &std::path::PathBuf::from("graphics.txt"));
+    /// 
+    /// assert_eq!(3, tokens.len());
+    /// assert_eq!(vec!["CONDITION_CASTE", "MALE"], tokens[1].args);
+    /// assert_eq!((0, 30..52), (tokens[1].line, tokens[1].columns.clone()));
+    /// assert_eq!((1, 1..20), (tokens[2].line, tokens[2].columns.clone()));
+    /// assert_eq!("LAYER_SET", tokens[2][0]);
+    /// ```
+    pub fn tokenize(raw_lines: &[String], path: &Path) -> Vec<Token> {
+        raw_lines.iter()
+            .enumerate()
+            .flat_map(|(i_line, raw_line)| Self::read_line(raw_line, i_line, path))
+            .collect()
+    }
+
+    /// Read every bracketed token on a single line.
+    pub fn read_line(raw_line: &str, line: usize, path: &Path) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut open: Option<(usize, String)> = None;
+
+        for (column, c) in raw_line.chars().enumerate() {
+            match c {
+                '[' => {
+                    //a new bracket restarts the token like it does in game
+                    open = Some((column, String::new()));
+                },
+                ']' => {
+                    if let Some((start, contents)) = open.take() {
+                        tokens.push(Token {
+                            args: contents.split(':').map(|s| s.to_string()).collect(),
+                            path: path.to_path_buf(),
+                            line,
+                            columns: start..column+1,
+                        });
+                    }
+                },
+                _ => {
+                    if let Some((_, contents)) = open.as_mut() {
+                        contents.push(c);
+                    }
+                },
+            }
+        }
+
+        tokens
+    }
+}
+impl std::ops::Deref for Token {
+    type Target = Vec<String>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.args
+    }
+}
+impl std::ops::DerefMut for Token {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.args
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Graphics {
+    pub mod_info: Option<ModInfo>,
     pub tile_page_files: Vec<TilePageFile>,
     pub graphics_files: Vec<GraphicsFile>,
-    pub shared: Shared,
+    #[serde(skip)]
+    pub shared: Shared,     //rebuilt from the files with update_shared
 }
 impl Graphics {
     /// Generate a blank generic Graphics struct
     pub fn new() -> Graphics {
         Graphics {
+            mod_info: None,
             tile_page_files: Vec::new(),
             graphics_files: Vec::new(),
             shared: Shared::new(),
@@ -116,45 +228,20 @@ impl Graphics {
     /// assert!(Graphics::read_brackets(&empty_line).is_empty());
     /// ```
     pub fn read_brackets(raw_line: &String) -> Vec<String> {
-        // let mut split_line: Vec<&str> = 
-        
-        let line_vec: Vec<String>;
-        //split at first '[' for the start of a line that DF will attempt to read. Throw away any leading characters.
-        if let Some((_, open_brac_line)) = raw_line.split_once('[') {
-            //if the line also has a closing bracket, proceed with vectorization. Throw away trailing characters.
-            if let Some((valid_line, _)) = open_brac_line.split_once(']') {
-                //split line at ':'s and gather the parameters into a vector of strings.
-                line_vec = valid_line.split(':').map(|s| s.to_string()).collect();
-            //if the line has no closing bracket, treat it as a blank line.
-            } else {
-                line_vec = Vec::new();
-            }
-        //if the line has no opening '[', treat it as a blank line
-        } else {
-            line_vec = Vec::new();
-        }
-
-        //return the vector of parameters in a line with unbracketed lines treated as empty parameter vectors
-        line_vec
+        //only the first token on the line is returned, see Token::read_line for every token.
+        Token::read_line(raw_line, 0, &PathBuf::new())
+            .into_iter()
+            .next()
+            .map(|token| token.args)
+            .unwrap_or_default()
     }
 
     /// Loads a graphics directory into the program.
     /// 
     /// Identifies if the selected path contains or is inside a valid graphics directory, 
     /// then attempts to each graphics or tilepage file in the graphics directory.
-    /// 
-    /// ```
-    /// # use df_texture_helper::*;
-    /// let mut folder = std::path::PathBuf::from(std::path::Path::new("./debug_RAWs/Mod"));
-    ///
-    /// assert!(Graphics::import(&mut folder).2.is_empty());
-    /// ```
+    /// See [`Graphics::from_sources`] to load a mod that isn't on disk.
     pub fn import(folder: &mut PathBuf) -> (Graphics, PathBuf, Vec<DFGHError>) {
-        let mut tile_page_files = Vec::new();
-        let mut graphics_files = Vec::new();
-        let mut errors: Vec<DFGHError> = Vec::new();
-        let mut shared = Shared::new();
-
         if folder.read_dir().is_ok_and(|mut d| d.any(|r| r.is_ok_and(|f| f.path().ends_with("graphics")))) {
             //folder contains graphics subfolder, so can be assumed to be mod folder
         } else if folder.components().any(|c| c.as_os_str().eq("graphics")) {
@@ -167,372 +254,876 @@ impl Graphics {
             folder.push(temp_folder);
         } else {
             //no graphics directory found in mod folder => throw error.
-            errors.push(DFGHError::NoGraphicsDirectory(folder.clone()));
             return (
-                Graphics {tile_page_files, graphics_files, shared},
+                Graphics::new(),
                 folder.clone(),
-                errors
+                vec![DFGHError::NoGraphicsDirectory(folder.clone())]
             );
         }
 
+        let (graphics, errors) = Self::from_sources(&DiskFileSystem, folder);
+
+        (graphics, folder.clone(), errors)
+    }
+
+    /// Loads the graphics directory of a mod folder from any file system, such as named buffers held in memory.
+    /// 
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/graphics/tile_page_dwarf.txt",
+    ///     "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n\n[TILE_PAGE:DWARF]\n\t[FILE:images/dwarf.png]\n\t[TILE_DIM:32:32]\n\t[PAGE_DIM_PIXELS:64:32]\n"
+    /// );
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_dwarf.txt",
+    ///     "graphics_creatures_dwarf\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[DEFAULT:DWARF:1:0:AS_IS:DEFAULT]\n"
+    /// );
+    /// 
+    /// let (graphics, errors) = Graphics::from_sources(&files, &std::path::PathBuf::from("Mod"));
+    /// 
+    /// assert!(errors.is_empty());
+    /// assert_eq!([64, 32], graphics.tile_page_files[0].tile_pages[0].image_size);
+    /// assert_eq!(1, graphics.graphics_files.len());
+    /// ```
+    pub fn from_sources(file_system: &dyn FileSystem, folder: &Path) -> (Graphics, Vec<DFGHError>) {
+        let mut tile_page_files = Vec::new();
+        let mut graphics_files = Vec::new();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        let mut shared = Shared::new();
+
+        //read the mod's info.txt if it has one.
+        let info_path = folder.join("info.txt");
+        let mut mod_info = None;
+        if file_system.is_file(&info_path) {
+            match file_system.read(&info_path) {
+                Ok(bytes) => {
+                    //the encoding is kept on the raw file and shown in its menu, it isn't a problem
+                    let (text, encoding) = Encoding::decode(&bytes);
+
+                    let raw_lines = text
+                        .split_inclusive('\n')
+                        .map(|l| l.to_string())
+                        .collect::<Vec<String>>();
+                    let tokens = Token::tokenize(&raw_lines, &info_path);
+                    let last_token = tokens.len();
+
+                    let (mut info, info_errors) = ModInfo::read(tokens.clone(), raw_lines.clone(), &info_path);
+                    info.raw.encoding = encoding;
+                    errors.extend(info_errors.iter().map(|e| wrap_import_file_error(raw_lines.clone(), &tokens, e, last_token, &info_path)));
+                    mod_info = Some(info);
+                },
+                Err(e) => errors.push(DFGHError::SkippedFileError(info_path.clone(), e.to_string())),
+            }
+        }
+
+        //read the definitions in the mod's object raws that graphics refer to.
+        let (catalog, mut catalog_errors) = RawCatalog::from_sources(file_system, folder);
+        shared.catalog = catalog;
+        errors.append(&mut catalog_errors);
+
         //read graphics directory from mod folder.
-        match fs::read_dir(&folder.join("graphics")) {
-            Ok(paths) => {
+        match file_system.read_dir_entries(&folder.join("graphics")) {
+            Ok((paths, entry_errors)) => {
+                errors.extend(entry_errors.into_iter().map(|e| DFGHError::SkippedFileError(folder.join("graphics"), e.to_string())));
+
                 //read each tile page or creature graphics text file and import.
-                for path_result in paths {
-                    match path_result {
-                        Ok(path) => {
-                            let mut tpf_bool = false;
-                            let mut gf_bool = false;
+                for path in paths {
+                    let mut tpf_bool = false;
+                    let mut gf_bool = false;
+        
+                    if file_system.is_file(&path) {
+                        if path.file_name().is_some_and(|f| f.to_string_lossy().ends_with(".txt")) {
+                            match file_system.read(&path) {
+                                Ok(bytes) => {
+                                    let (text, encoding) = Encoding::decode(&bytes);
+
+                                    //keep line endings so unedited blocks can be written back unchanged.
+                                    let raw_lines = text
+                                        .split_inclusive('\n')
+                                        .map(|l| l.to_string())
+                                        .collect::<Vec<String>>();
                 
-                            if path.path().is_file() {
-                                if path.file_name().into_string().unwrap_or(String::new()).ends_with(".txt") {
-                                    match fs::File::open(path.path()) {
-                                        Ok(f) => {
-                                            let raw_lines = io::BufReader::new(f)
-                                                .lines()
-                                                .map(|l| l.expect("All lines in a text file should be valid"))
-                                                .collect::<Vec<String>>();
-                        
-                                            let lines = raw_lines.clone().iter()
-                                                .map(|l| Self::read_brackets(l))
-                                                .collect::<Vec<Vec<String>>>();
-                        
-                                            //identify file type
-                                            for raw_line in raw_lines.clone().iter() {
-                                                let line_vec = Self::read_brackets(&raw_line);
-                                                let len = line_vec.len();
-                        
-                                                if len >=2 {
-                                                    match line_vec[0].as_str() {
-                                                        "OBJECT" => {
-                                                            match line_vec[1].as_str() {
-                                                                "TILE_PAGE" => {
-                                                                    tpf_bool = true;
-                                                                    break
-                                                                },
-                                                                "GRAPHICS" => {
-                                                                    gf_bool = true;
-                                                                    break
-                                                                },
-                                                                _ => break
-                                                            }
+                                    let tokens = Token::tokenize(&raw_lines, &path);
+                
+                                    //identify file type
+                                    for line_vec in tokens.iter() {
+                                        let len = line_vec.len();
+                
+                                        if len >=2 {
+                                            match line_vec[0].as_str() {
+                                                "OBJECT" => {
+                                                    match line_vec[1].as_str() {
+                                                        "TILE_PAGE" => {
+                                                            tpf_bool = true;
+                                                            break
                                                         },
-                                                        _ => {}
+                                                        "GRAPHICS" => {
+                                                            gf_bool = true;
+                                                            break
+                                                        },
+                                                        _ => break
                                                     }
-                                                }
+                                                },
+                                                _ => {}
                                             }
-                                        
-                                            if tpf_bool {
-                                                let (tile_page_file, mut tpf_errors) = TilePageFile::read(lines, raw_lines, &path.path());
-                                                tile_page_files.push(tile_page_file);
-                                                errors.append(&mut tpf_errors);
-                                            } else if gf_bool {
-                                                let (graphics_file, mut gf_errors) = GraphicsFile::read(lines, raw_lines, &path.path());
-                                                graphics_files.push(graphics_file);
-                                                errors.append(&mut gf_errors);
-                                            }
-                                        },
-                                        Err(e) => {
-                                            errors.push(DFGHError::from(e));
-                                            return (
-                                                Graphics {tile_page_files, graphics_files, shared},
-                                                folder.clone(),
-                                                errors
-                                            );
-                                        },
+                                        }
                                     }
-                                }
+                                
+                                    if tpf_bool {
+                                        let (mut tile_page_file, mut tpf_errors) = TilePageFile::read(tokens, raw_lines, &path);
+                                        tile_page_file.raw.encoding = encoding;
+                                        tile_page_file.read_image_sizes(file_system, &path);
+                                        tile_page_files.push(tile_page_file);
+                                        errors.append(&mut tpf_errors);
+                                    } else if gf_bool {
+                                        let (mut graphics_file, mut gf_errors) = GraphicsFile::read(tokens, raw_lines, &path);
+                                        if let Some(raw) = graphics_file.raw_mut() {
+                                            raw.encoding = encoding;
+                                        }
+                                        graphics_file.read_palette_images(file_system, &path);
+                                        graphics_files.push(graphics_file);
+                                        errors.append(&mut gf_errors);
+                                    }
+                                },
+                                Err(e) => {
+                                    //skip the file and keep importing the rest of the mod.
+                                    errors.push(DFGHError::SkippedFileError(path.clone(), e.to_string()));
+                                },
                             }
-        
-                        },
-                        Err(e) => {
-                            errors.push(DFGHError::from(e));
-                            return (
-                                Graphics {tile_page_files, graphics_files, shared},
-                                folder.clone(),
-                                errors
-                            );
-                        },
-                    } 
+                        }
+                    }
                 }
             },
             Err(e) => {
-                errors.push(DFGHError::from(e));
-                return (
-                    Graphics {tile_page_files, graphics_files, shared},
-                    folder.clone(),
-                    errors
-                );
+                errors.push(DFGHError::SkippedFileError(folder.join("graphics"), e.to_string()));
+                return (Graphics {mod_info, tile_page_files, graphics_files, shared}, group_skipped_files(errors));
             },
         }
 
-        shared.update(&tile_page_files, &mut graphics_files, &folder);
+        shared.update(&tile_page_files, &mut graphics_files, &folder, file_system);
 
-        (
-            Graphics { tile_page_files, graphics_files, shared },
-            folder.clone(),
-            errors
-        )
+        (Graphics { mod_info, tile_page_files, graphics_files, shared }, group_skipped_files(errors))
     }
 
     pub fn update_shared(&mut self, folder: &PathBuf) {
-        self.shared.update(&self.tile_page_files, &mut self.graphics_files, folder);
+        self.shared.update(&self.tile_page_files, &mut self.graphics_files, folder, &DiskFileSystem);
     }
 
-    pub fn export(&self, path: &PathBuf) -> Result<()> {
-        fs::DirBuilder::new()
-            .recursive(true)
-            .create(path.join("graphics").join("images"))?;
+    /// Fall back on the vanilla definitions and tile pages of a reference library for objects the mod
+    /// doesn't define. The vanilla tile pages only count when checking tile references, the editor
+    /// still only offers the mod's own.
+    pub fn use_library_catalog(&mut self, library: &ReferenceLibrary) {
+        self.shared.catalog.merge(&library.graphics.shared.catalog);
+        self.shared.library_tile_page_info = library.graphics.shared.tile_page_info.clone();
+    }
 
-        for tile_page_file in self.tile_page_files.iter() {
-            tile_page_file.export(&path)?;
+    /// Conditions that refer to items that aren't defined in the item raws.
+    ///
+    /// Nothing is reported until some item definitions have been read.
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/objects/item_helm.txt", "item_helm\n\n[OBJECT:ITEM]\n\n[ITEM_HELM:ITEM_HELM_HELM]\n");
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_dwarf.txt",
+    ///     "graphics_creatures_dwarf\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[LAYER_SET:DEFAULT]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:HELM:DWARF:0:0]\n\t\t\t[CONDITION_ITEM_WORN:BY_CATEGORY:HEAD:HELM:ITEM_HELM_HELM:ITEM_HELM_HLEM]\n"
+    /// );
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.item_errors();
+    ///
+    /// assert_eq!(1, errors.len());
+    /// assert!(errors[0].to_string().contains("ITEM_HELM_HLEM"));
+    /// ```
+    pub fn item_errors(&self) -> Vec<DFGHError> {
+        if self.shared.catalog.items.is_empty() {
+            return Vec::new();
         }
 
-        for graphics_file in self.graphics_files.iter() {
-            graphics_file.export(&path)?;
+        self.graphics_files
+            .iter()
+            .flat_map(|gf| gf.item_errors(&self.shared.catalog))
+            .collect()
+    }
+
+    /// Creature and statue graphics, and caste conditions, for creatures or castes that aren't in the creature raws.
+    ///
+    /// Nothing is reported until some creature definitions have been read.
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/objects/creature_ant.txt", "creature_ant\n\n[OBJECT:CREATURE]\n\n[CREATURE:GIANT_ANT]\n\t[CASTE:QUEEN]\n\t[CASTE:DRONE]\n");
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_ant.txt",
+    ///     "graphics_creatures_ant\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_CASTE_GRAPHICS:GIANT_ANT:QUEEN]\n\t[DEFAULT:ANT:0:0:AS_IS:DEFAULT]\n\n[CREATURE_CASTE_GRAPHICS:GIANT_ANT:KING]\n\t[DEFAULT:ANT:1:0:AS_IS:DEFAULT]\n\n[CREATURE_GRAPHICS:GIANT_BEE]\n\t[DEFAULT:ANT:2:0:AS_IS:DEFAULT]\n"
+    /// );
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.creature_errors();
+    ///
+    /// assert_eq!(2, errors.len());
+    /// assert!(errors[0].to_string().contains("KING"));
+    /// assert!(errors[1].to_string().contains("GIANT_BEE"));
+    /// ```
+    pub fn creature_errors(&self) -> Vec<DFGHError> {
+        if self.shared.catalog.creatures.is_empty() {
+            return Vec::new();
         }
 
-        Ok(())
+        self.graphics_files
+            .iter()
+            .flat_map(|gf| gf.creature_errors(&self.shared.catalog))
+            .collect()
     }
-}
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct TilePageFile {
-    pub name: String,     //file name of tile_set_file_name.txt
-    pub tile_pages: Vec<TilePage>, //set of tiles defined in this file
-}
-impl RAW for TilePageFile {
-    fn new() -> Self {
-        TilePageFile {
-            name: String::from("(new)"),
-            tile_pages: vec![TilePage::new()],
-        }
+    /// Body parts and tissues selected by conditions that the creature's body, body detail plan and
+    /// tissue raws don't give it.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/objects/body_mod.txt", "body_mod\n\n[OBJECT:BODY]\n\n[BODY:HEAD]\n\t[BP:HD:head:STP][HEAD][CATEGORY:HEAD]\n");
+    /// files.insert("Mod/objects/creature_mod.txt", "creature_mod\n\n[OBJECT:CREATURE]\n\n[CREATURE:GOBLIN]\n\t[BODY:HEAD]\n\t[USE_TISSUE_TEMPLATE:HAIR:HAIR_TEMPLATE]\n");
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_goblin.txt",
+    ///     "graphics_creatures_goblin\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:GOBLIN]\n\t[LAYER_SET:DEFAULT]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:HAIR:GOBLIN:0:0]\n\t\t\t[CONDITION_TISSUE_LAYER:BY_CATEGORY:HAED:HAIR]\n\t\t[LAYER:BEARD:GOBLIN:1:0]\n\t\t\t[CONDITION_TISSUE_LAYER:BY_CATEGORY:HEAD:BEARD]\n"
+    /// );
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.body_errors();
+    ///
+    /// assert_eq!(2, errors.len());
+    /// assert!(errors[0].to_string().contains("BY_CATEGORY:HAED"));
+    /// assert!(errors[1].to_string().contains("tissue BEARD"));
+    /// ```
+    pub fn body_errors(&self) -> Vec<DFGHError> {
+        self.graphics_files
+            .iter()
+            .flat_map(|gf| gf.body_errors(&self.shared.catalog))
+            .collect()
     }
 
-    fn read(buffer: Vec<Vec<String>>, raw_buffer: Vec<String>, path: &PathBuf) -> (Self, Vec<DFGHError>) {
-        let mut block_buffer = Vec::with_capacity(100);
-        let mut tile_pages = Vec::new();
-        let mut errors: Vec<DFGHError> = Vec::new();
+    /// Colors and shapings of conditions that the descriptor and creature raws don't define.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/objects/descriptor_color_mod.txt", "descriptor_color_mod\n\n[OBJECT:DESCRIPTOR_COLOR]\n\n[COLOR:AMBER]\n\t[RGB:255:191:0]\n");
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_goblin.txt",
+    ///     "graphics_creatures_goblin\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:GOBLIN]\n\t[LAYER_SET:DEFAULT]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:HAIR:GOBLIN:0:0]\n\t\t\t[TISSUE_MAY_HAVE_COLOR:AMBER:AMBRE]\n"
+    /// );
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.descriptor_errors();
+    ///
+    /// assert_eq!(1, errors.len());
+    /// assert!(errors[0].to_string().contains("color AMBRE"));
+    /// ```
+    pub fn descriptor_errors(&self) -> Vec<DFGHError> {
+        self.graphics_files
+            .iter()
+            .flat_map(|gf| gf.descriptor_errors(&self.shared.catalog))
+            .collect()
+    }
 
-        //tile page file name must match file name.
-        let name = path
-            .file_name().get_or_insert(&OsStr::new("no_name"))
-            .to_str().get_or_insert("no_name")
-            .replace(".txt", "").trim().to_string();
-        
-        //create vector (buffer) of all lines between relevant headers and import each buffer.
-        for (i_line, line_vec) in buffer.iter().enumerate() {
-            let len = line_vec.len();
-            if len >=1 {
-                match line_vec[0].as_str() {
-                    "TILE_PAGE" => {
-                        if block_buffer.len() > 0 {
-                            let (tp_temp, temp) = TilePage::read(block_buffer.clone(), Vec::new(), path);
-                            let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, i_line, path)).collect();
-                            errors.append(&mut es_temp);
-                            if tp_temp.ne(&TilePage::new()) {
-                                tile_pages.push(tp_temp);
-                            }
-                            block_buffer.clear();
-                        }
-                    },
-                    _ => {}
-                }
-            }
-            block_buffer.push(line_vec.clone());
-        }
-        let last_line = buffer.len();
-        if block_buffer.len() > 0 {
-            let (tp_temp, temp) = TilePage::read(block_buffer.clone(), Vec::new(), path);
-            let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, last_line, path)).collect();
-            errors.append(&mut es_temp);
-            if tp_temp.ne(&TilePage::new()) {
-                tile_pages.push(tp_temp);
-            }
-        }
-        (TilePageFile {name, tile_pages}, errors)
+    /// Syndrome classes and materials of conditions that the syndrome, interaction and inorganic raws
+    /// don't define.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::error::DFGHError;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/objects/inorganic_mod.txt", "inorganic_mod\n\n[OBJECT:INORGANIC]\n\n[INORGANIC:MITHRIL]\n\t[IS_METAL]\n\n[INORGANIC:CHALK]\n\t[IS_STONE]\n");
+    /// files.insert("Mod/objects/interaction_mod.txt", "interaction_mod\n\n[OBJECT:INTERACTION]\n\n[INTERACTION:BITE]\n\t[SYN_CLASS:VAMPIRE_BITE]\n");
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_goblin.txt",
+    ///     "graphics_creatures_goblin\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:GOBLIN]\n\t[LAYER_SET:DEFAULT]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:HELM:GOBLIN:0:0]\n\t\t\t[CONDITION_MATERIAL_TYPE:METAL:MITHRIL]\n\t\t[LAYER:HELM:GOBLIN:1:0]\n\t\t\t[CONDITION_MATERIAL_TYPE:METAL:CHALK]\n\t\t[LAYER:BITTEN:GOBLIN:2:0]\n\t\t\t[CONDITION_SYN_CLASS:VAMPIRE_BIT]\n"
+    /// );
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.material_errors();
+    ///
+    /// assert_eq!(2, errors.len());
+    /// assert!(matches!(&errors[0], DFGHError::UnknownMaterial(_, kind, name) if kind == "metal" && name == "METAL:CHALK"));
+    /// assert!(matches!(&errors[1], DFGHError::UnknownMaterial(_, kind, name) if kind == "syndrome class" && name == "VAMPIRE_BIT"));
+    /// ```
+    pub fn material_errors(&self) -> Vec<DFGHError> {
+        self.graphics_files
+            .iter()
+            .flat_map(|gf| gf.material_errors(&self.shared.catalog))
+            .collect()
     }
 
-    fn display(&self) -> String {
-        let mut out = format!(
-            "tile_page_{}\n\n[OBJECT:TILE_PAGE]\n\n",
-            self.name.clone()
-            .with_boundaries(&[Boundary::Space])
-            .to_case(Case::Snake)
-            .replace("tile_page_", "")
-            .replace(".txt", "")
-        );
-    
-        for tile_page in self.tile_pages.iter() {
-            out.push_str(&tile_page.display());
-        }
+    /// Appearance modifier ranges that the body detail plan and creature raws make dead or impossible.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/objects/body_mod.txt", "body_mod\n\n[OBJECT:BODY]\n\n[BODY:EYES]\n\t[BP:REYE:right eye:STP][CATEGORY:EYE][SIGHT]\n\t[BP:NOSE:nose:STP][CATEGORY:NOSE]\n");
+    /// files.insert(
+    ///     "Mod/objects/creature_mod.txt",
+    ///     "creature_mod\n\n[OBJECT:CREATURE]\n\n[CREATURE:GOBLIN]\n\t[BODY:EYES]\n\t[SET_BP_GROUP:BY_CATEGORY:EYE]\n\t\t[BP_APPEARANCE_MODIFIER:CLOSE_SET:0:70:90:100:110:130:200]\n"
+    /// );
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_goblin.txt",
+    ///     "graphics_creatures_goblin\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:GOBLIN]\n\t[LAYER_SET:DEFAULT]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:EYES:GOBLIN:0:0]\n\t\t\t[CONDITION_BP:BY_TOKEN:REYE]\n\t\t\t\t[BP_APPEARANCE_MODIFIER_RANGE:CLOSE_SET:150:300]\n\t\t[LAYER:WIDE_EYES:GOBLIN:1:0]\n\t\t\t[CONDITION_BP:BY_TOKEN:REYE]\n\t\t\t\t[BP_APPEARANCE_MODIFIER_RANGE:CLOSE_SET:250:300]\n\t\t[LAYER:NOSE:GOBLIN:2:0]\n\t\t\t[CONDITION_BP:BY_CATEGORY:NOSE]\n\t\t\t\t[BP_APPEARANCE_MODIFIER_RANGE:CLOSE_SET:0:100]\n"
+    /// );
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.appearance_errors();
+    ///
+    /// assert_eq!(2, errors.len());
+    /// assert!(errors[0].to_string().contains("between 250 and 300"));
+    /// assert!(errors[1].to_string().contains("BY_CATEGORY:NOSE"));
+    /// ```
+    pub fn appearance_errors(&self) -> Vec<DFGHError> {
+        self.graphics_files
+            .iter()
+            .flat_map(|gf| gf.appearance_errors(&self.shared.catalog))
+            .collect()
+    }
 
-        out
+    /// Tile coordinates and LARGE_IMAGE rectangles outside the tile grid of their page.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/graphics/tile_page_dwarf.txt",
+    ///     "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n\n[TILE_PAGE:DWARF]\n\t[FILE:images/dwarf.png]\n\t[TILE_DIM:32:32]\n\t[PAGE_DIM_PIXELS:128:64]\n"
+    /// );
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_dwarf.txt",
+    ///     "graphics_creatures_dwarf\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[LAYER_SET:DEFAULT]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:BODY:DWARF:3:1]\n\t\t[LAYER:CAPE:DWARF:LARGE_IMAGE:2:0:4:1]\n"
+    /// );
+    ///
+    /// let (mut graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.tile_bounds_errors();
+    ///
+    /// assert_eq!(1, errors.len());
+    /// assert!(errors[0].to_string().contains("layer CAPE uses tile 2:0 to 4:1"));
+    /// assert!(errors[0].to_string().contains("0:0 to 3:1"));
+    ///
+    /// //clamping the cape moves it back onto the page and leaves the body where it is
+    /// graphics.clamp_tile(0, 1);
+    /// assert!(graphics.tile_bounds_errors().is_empty());
+    /// ```
+    pub fn tile_bounds_errors(&self) -> Vec<DFGHError> {
+        self.graphics_files
+            .iter()
+            .flat_map(|gf| gf.tile_bounds_errors(&self.shared))
+            .map(|(_, e)| e)
+            .collect()
     }
-}
-impl TilePageFile {
-    fn export(&self, path: &PathBuf) -> Result<()> {
-        let mut tpf_name = format!("{}.txt", self.name.clone())
-            .with_boundaries(&[Boundary::Space])
-            .to_case(Case::Snake);
 
-        if !tpf_name.starts_with("tile_page_") {
-            tpf_name = format!("tile_page_{}", tpf_name);
+    /// Move one tile of a graphics file back onto its tile page. Tiles are counted the same way
+    /// as by the tile bounds check.
+    pub fn clamp_tile(&mut self, i_file: usize, i_tile: usize) {
+        if let Some(graphics_file) = self.graphics_files.get_mut(i_file) {
+            graphics_file.clamp_tile(i_tile, &self.shared);
         }
-        
-        let tile_page_file = fs::File::create(
-            path
-            .join("graphics")
-            .join(tpf_name))?;
-        
-        let mut tile_page_writer = io::LineWriter::new(tile_page_file);
-        
-        tile_page_writer.write_all(self.display().as_bytes())?;
-        
-        tile_page_writer.flush()?;
-
-        Ok(())
     }
-}
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct TilePage {
-    pub name: String,
-    pub file_name: PathBuf,
-    pub image_size: [u32; 2],
-    pub tile_size: [u32; 2],
-}
-impl RAW for TilePage {
-    fn new() -> Self {
-        TilePage {
-            name: "(new)".to_string(),
-            file_name: PathBuf::new(),
-            image_size: [0, 0],
-            tile_size: [32, 32],
-        }
+    /// Tile names that no tile page is called, with the closest tile page name as a suggestion.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/graphics/tile_page_dwarf.txt",
+    ///     "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n\n[TILE_PAGE:DWARF_HAIR]\n\t[FILE:images/dwarf_hair.png]\n\t[TILE_DIM:32:32]\n\t[PAGE_DIM_PIXELS:128:64]\n"
+    /// );
+    /// files.insert(
+    ///     "Mod/graphics/graphics_creatures_dwarf.txt",
+    ///     "graphics_creatures_dwarf\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[LAYER_SET:DEFAULT]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:HAIR:DWARF_HIAR:0:0]\n"
+    /// );
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.tile_name_errors();
+    ///
+    /// assert_eq!(1, errors.len());
+    /// assert!(errors[0].to_string().contains("DWARF_HIAR"));
+    /// assert!(errors[0].to_string().contains("Did you mean DWARF_HAIR?"));
+    /// ```
+    pub fn tile_name_errors(&self) -> Vec<DFGHError> {
+        let tile_page_names: Vec<&String> = self.shared.tile_page_info
+            .keys()
+            .chain(self.shared.library_tile_page_info.keys())
+            .collect();
+
+        self.graphics_files
+            .iter()
+            .flat_map(|gf| gf.tiles())
+            .filter(|(_, tile_name, ..)| !tile_name.is_empty())
+            .filter(|(_, tile_name, ..)| self.shared.checked_tile_page(tile_name).is_none())
+            .map(|(location, tile_name, ..)| {
+                let suggestion = closest_name(tile_name, &tile_page_names)
+                    .map(|name| format!(" Did you mean {}?", name))
+                    .unwrap_or_default();
+                DFGHError::UnknownTilePage(location, tile_name.clone(), suggestion)
+            })
+            .collect()
     }
 
-    fn read(buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, path: &PathBuf) -> (Self, Vec<DFGHError>) {
-        let mut tile_page = TilePage::new();
-        let mut errors: Vec<DFGHError> = Vec::new();
-        let buffer_len = buffer.len();
+    /// Tile pages of the mod that no graphics use.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/graphics/tile_page_dwarf.txt",
+    ///     "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n\n[TILE_PAGE:DWARF]\n\t[FILE:images/dwarf.png]\n\t[TILE_DIM:32:32]\n\t[PAGE_DIM_PIXELS:128:64]\n\n[TILE_PAGE:DWARF_OLD]\n\t[FILE:images/dwarf_old.png]\n\t[TILE_DIM:32:32]\n\t[PAGE_DIM_PIXELS:128:64]\n"
+    /// );
+    /// files.insert("Mod/graphics/graphics_creatures_dwarf.txt", "graphics_creatures_dwarf\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[DEFAULT:DWARF:0:0:AS_IS]\n");
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.unused_tile_pages();
+    ///
+    /// assert_eq!(1, errors.len());
+    /// assert!(errors[0].to_string().contains("DWARF_OLD"));
+    /// ```
+    pub fn unused_tile_pages(&self) -> Vec<DFGHError> {
+        let tiles: Vec<_> = self.graphics_files.iter().flat_map(|gf| gf.tiles()).collect();
+
+        self.tile_page_files
+            .iter()
+            .flat_map(|tpf| tpf.tile_pages.iter())
+            .filter(|tp| !tiles.iter().any(|(_, tile_name, ..)| tile_name.eq_ignore_ascii_case(&tp.name)))
+            .map(|tp| DFGHError::UnusedTilePage(tp.name.clone()))
+            .collect()
+    }
 
-        for (i_line, line_vec) in buffer.iter().enumerate() {
-            let len = line_vec.len();
-            if len >= 2 {
-                match line_vec[0].as_str() {
-                    "TILE_PAGE" => {
-                        tile_page.name = line_vec[1].clone();
-                    },
-                    "FILE" => {
-                        tile_page.file_name = line_vec[1].clone().split(&['\\','/']).collect::<PathBuf>();
-                    },
-                    "TILE_DIM" => {
-                        if len >= 3 {
-                            tile_page.tile_size =
-                                [buffer_err_wrap!(line_vec[1].parse(), i_line, buffer_len, 1..=1, 0, errors),
-                                buffer_err_wrap!(line_vec[2].parse(), i_line, buffer_len, 2..=2, 0, errors)];
-                        } else {
-                            index_err!(i_line, buffer_len, len, 3, errors);
-                        }
-                    },
-                    "PAGE_DIM_PIXELS" => {
-                        // if the image file name is already read attempt to correct the image size based on it.
-                        if path.parent().is_some_and(|p| p.join(&tile_page.file_name).exists()) {
-                            let image_path = path
-                                .parent().expect("This file should have a parent graphics directory if we are reading from it.")
-                                .join(&tile_page.file_name)
-                                .with_extension("png");
-                            
-                            if let Ok(image_dimensions) = image::image_dimensions(image_path) {
-                                tile_page.image_size = [image_dimensions.0 as _, image_dimensions.1 as _];
-                                continue; //skip parsing if reading image works
-                            }
-                        }
+    /// Images in graphics/images that no tile page or palette of the mod uses.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/graphics/tile_page_dwarf.txt",
+    ///     "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n\n[TILE_PAGE:DWARF]\n\t[FILE:images/dwarf.png]\n\t[TILE_DIM:32:32]\n\t[PAGE_DIM_PIXELS:128:64]\n"
+    /// );
+    /// files.insert("Mod/graphics/images/dwarf.png", Vec::new());
+    /// files.insert("Mod/graphics/images/old/dwarf_old.png", Vec::new());
+    ///
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let errors = graphics.orphan_images();
+    ///
+    /// assert_eq!(1, errors.len());
+    /// assert!(errors[0].to_string().contains("dwarf_old.png"));
+    /// ```
+    pub fn orphan_images(&self) -> Vec<DFGHError> {
+        //compared as lowercase paths with / separators, DF doesn't care about either
+        let normalize = |path: &PathBuf| path.with_extension("png").to_string_lossy().replace('\\', "/").to_lowercase();
+
+        let mut used: Vec<String> = self.tile_page_files
+            .iter()
+            .flat_map(|tpf| tpf.tile_pages.iter())
+            .map(|tp| normalize(&tp.file_name))
+            .collect();
+        for graphics_file in &self.graphics_files {
+            if let GraphicsFile::CreatureFile(_, creatures, _) = graphics_file {
+                used.extend(creatures
+                    .iter()
+                    .flat_map(|c| c.layer_sets.iter())
+                    .flat_map(|ls| ls.palettes.iter())
+                    .map(|p| normalize(&p.file_name)));
+            }
+        }
 
-                        if len >= 3 {
-                            tile_page.image_size =
-                                [buffer_err_wrap!(line_vec[1].parse(), i_line, buffer_len, 1..=1, 0, errors),
-                                buffer_err_wrap!(line_vec[2].parse(), i_line, buffer_len, 2..=2, 0, errors)];
-                        } else {
-                            index_err!(i_line, buffer_len, len, 3, errors);
-                        }
+        self.shared.images
+            .iter()
+            .filter(|image| !used.contains(&normalize(image)))
+            .map(|image| DFGHError::OrphanImage(image.clone()))
+            .collect()
+    }
+
+    /// Work out what exporting to a mod folder would write, without touching the disk.
+    ///
+    /// Imported files are written back to the file they came from as long as they haven't been renamed.
+    /// With `normalize_names` every file is instead named from its object name in the game's
+    /// `tile_page_*`/`graphics_creatures_*` snake case form.
+    /// Two objects that would be written to the same file are an error.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// # use std::path::PathBuf;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/graphics/graphics_creatures_MyMod.txt", "graphics_creatures_MyMod\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n");
+    /// let (mut graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    ///
+    /// let plan = graphics.export_plan("missing".as_ref(), false).unwrap();
+    /// assert_eq!(PathBuf::from("graphics/graphics_creatures_MyMod.txt"), plan.files[0].path);
+    /// let plan = graphics.export_plan("missing".as_ref(), true).unwrap();
+    /// assert_eq!(PathBuf::from("graphics/graphics_creatures_mymod.txt"), plan.files[0].path);
+    ///
+    /// graphics.graphics_files.push(graphics.graphics_files[0].clone());
+    /// assert!(graphics.export_plan("missing".as_ref(), false).is_err());
+    /// if let GraphicsFile::CreatureFile(name, ..) = &mut graphics.graphics_files[1] {
+    ///     *name = "graphics_creatures_copy".to_string();
+    /// }
+    /// assert!(graphics.export_plan("missing".as_ref(), false).is_ok());
+    /// ```
+    pub fn export_plan(&self, path: &Path, normalize_names: bool) -> Result<ExportPlan> {
+        let mut files = Vec::new();
+        let mut owners = Vec::new();
+
+        if let Some(mod_info) = &self.mod_info {
+            files.push(mod_info.export()?);
+            owners.push("info.txt".to_string());
+        }
+
+        for tile_page_file in self.tile_page_files.iter() {
+            files.push(tile_page_file.export(normalize_names)?);
+            owners.push(format!("Tile page file \"{}\"", tile_page_file.name));
+        }
+
+        for graphics_file in self.graphics_files.iter() {
+            if let Some(file) = graphics_file.export(normalize_names)? {
+                files.push(file);
+                owners.push(format!("Graphics file \"{}\"", graphics_file.name()));
+            }
+        }
+
+        //file names are case insensitive on windows
+        for (i_file, file) in files.iter().enumerate() {
+            let lowercase = file.path.to_string_lossy().to_lowercase();
+            if let Some(i_other) = files[..i_file].iter().position(|f| f.path.to_string_lossy().to_lowercase() == lowercase) {
+                return Err(DFGHError::ExportCollision(file.path.clone(), owners[i_other].clone(), owners[i_file].clone()));
+            }
+        }
+
+        Ok(ExportPlan::new(&DiskFileSystem, path, files))
+    }
+
+    pub fn export(&self, path: &Path) -> Result<()> {
+        self.export_plan(path, false)?.write(false)?;
+
+        Ok(())
+    }
+}
+
+/// Work in progress saved as a `.dfgh` project file.
+/// 
+/// Stores the whole model, including editor only state that `display()` can't express, so unfinished
+/// graphics can be saved and reopened exactly as they were.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub path: PathBuf,      //mod folder the graphics are exported to
+    pub graphics: Graphics,
+    #[serde(default)]
+    pub lint_config: LintConfig,
+}
+impl Project {
+    pub const EXTENSION: &'static str = "dfgh";
+
+    pub fn new(path: PathBuf, graphics: Graphics) -> Self {
+        Project { path, graphics, lint_config: LintConfig::default() }
+    }
+
+    /// Save the project to a `.dfgh` file.
+    /// 
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let file = std::env::temp_dir().join("project_doc_test.dfgh");
+    /// let mut graphics = Graphics::new();
+    /// graphics.tile_page_files.push(TilePageFile::new());
+    /// 
+    /// Project::new("Mod".into(), graphics.clone()).save(&file).unwrap();
+    /// let project = Project::load(&file).unwrap();
+    /// 
+    /// assert_eq!(graphics.tile_page_files, project.graphics.tile_page_files);
+    /// # std::fs::remove_file(file).unwrap();
+    /// ```
+    pub fn save(&self, file: &Path) -> Result<()> {
+        let project_writer = io::BufWriter::new(fs::File::create(file.with_extension(Self::EXTENSION))?);
+        serde_json::to_writer_pretty(project_writer, self)?;
+
+        Ok(())
+    }
+
+    /// Load a project from a `.dfgh` file and rebuild the shared data from the mod folder.
+    pub fn load(file: &Path) -> Result<Project> {
+        let project_reader = io::BufReader::new(fs::File::open(file)?);
+        let mut project: Project = serde_json::from_reader(project_reader)?;
+
+        let path = project.path.clone();
+        project.graphics.update_shared(&path);
+
+        Ok(project)
+    }
+}
+
+/// Vanilla graphics of a Dwarf Fortress installation, kept read-only to browse, preview and copy from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReferenceLibrary {
+    pub path: PathBuf,          //folder holding the vanilla mods, usually data/vanilla
+    pub mods: Vec<String>,      //names of the vanilla mod folders that had graphics
+    pub graphics: Graphics,     //graphics of every vanilla mod together
+}
+impl ReferenceLibrary {
+    /// Load the vanilla mods of a DF installation. Accepts the installation folder or its `data/vanilla` folder.
+    pub fn load(df_folder: &Path) -> (ReferenceLibrary, Vec<DFGHError>) {
+        Self::from_sources(&DiskFileSystem, df_folder)
+    }
+
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "DF/data/vanilla/vanilla_creatures_graphics/graphics/tile_page_dwarf.txt",
+    ///     "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n\n[TILE_PAGE:DWARF]\n\t[FILE:images/dwarf.png]\n\t[TILE_DIM:32:32]\n\t[PAGE_DIM_PIXELS:64:32]\n"
+    /// );
+    /// files.insert(
+    ///     "DF/data/vanilla/vanilla_creatures_graphics/graphics/graphics_creatures_dwarf.txt",
+    ///     "graphics_creatures_dwarf\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[DEFAULT:DWARF:1:0:AS_IS:DEFAULT]\n"
+    /// );
+    /// files.insert("DF/data/vanilla/vanilla_text/objects/text.txt", "");
+    ///
+    /// let (library, errors) = ReferenceLibrary::from_sources(&files, "DF".as_ref());
+    ///
+    /// assert!(errors.is_empty());
+    /// assert_eq!(vec!["vanilla_creatures_graphics".to_string()], library.mods);
+    /// assert_eq!(1, library.graphics.tile_page_files.len());
+    /// assert_eq!(1, library.graphics.graphics_files.len());
+    /// ```
+    pub fn from_sources(file_system: &dyn FileSystem, df_folder: &Path) -> (ReferenceLibrary, Vec<DFGHError>) {
+        let vanilla_folder = df_folder.join("data").join("vanilla");
+        let path = if file_system.is_dir(&vanilla_folder) {
+            vanilla_folder
+        } else {
+            df_folder.to_path_buf()
+        };
+
+        let mut library = ReferenceLibrary { path: path.clone(), ..Default::default() };
+        let mut errors = Vec::new();
+
+        let mut mod_folders = match file_system.read_dir(&path) {
+            Ok(paths) => paths,
+            Err(e) => {
+                errors.push(DFGHError::SkippedFileError(path, e.to_string()));
+                return (library, errors);
+            },
+        };
+        mod_folders.sort();
+
+        //object raws are spread over vanilla mods that mostly have no graphics
+        let mut catalog = RawCatalog::new();
+        for mod_folder in mod_folders.iter() {
+            let (mod_catalog, mut catalog_errors) = RawCatalog::from_sources(file_system, mod_folder);
+            catalog.merge(&mod_catalog);
+            errors.append(&mut catalog_errors);
+        }
+        catalog.vanilla = true;
+
+        mod_folders.retain(|folder| file_system.is_dir(&folder.join("graphics")));
+
+        if mod_folders.is_empty() {
+            errors.push(DFGHError::NoGraphicsDirectory(path));
+            return (library, group_skipped_files(errors));
+        }
+
+        for mod_folder in mod_folders {
+            let (mut graphics, mut mod_errors) = Graphics::from_sources(file_system, &mod_folder);
+
+            library.mods.push(mod_folder.file_name().unwrap_or_default().to_string_lossy().to_string());
+            library.graphics.tile_page_files.append(&mut graphics.tile_page_files);
+            library.graphics.graphics_files.append(&mut graphics.graphics_files);
+            library.graphics.shared.merge(graphics.shared);
+            errors.append(&mut mod_errors);
+        }
+        library.graphics.shared.catalog = catalog;
+
+        (library, group_skipped_files(errors))
+    }
+}
+
+/// The mod's info.txt metadata.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModInfo {
+    pub id: String,
+    pub numeric_version: u32,
+    pub displayed_version: String,
+    pub earliest_compatible_numeric_version: u32,
+    pub earliest_compatible_displayed_version: String,
+    pub author: String,
+    pub name: String,
+    pub description: String,
+    pub requires_ids: Vec<String>,
+    pub requires_ids_before_me: Vec<String>,
+    pub requires_ids_after_me: Vec<String>,
+    pub conflicts_with_ids: Vec<String>,
+    pub other_tokens: Vec<Vec<String>>, //tokens the editor doesn't handle (steam info, etc.) kept in order
+    pub raw: RawFile,                   //original text of the imported file
+}
+impl RAW for ModInfo {
+    fn new() -> Self {
+        ModInfo {
+            id: "new_mod".to_string(),
+            numeric_version: 1,
+            displayed_version: "1".to_string(),
+            earliest_compatible_numeric_version: 1,
+            earliest_compatible_displayed_version: "1".to_string(),
+            name: "(new)".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn read(buffer: Vec<Token>, raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut mod_info = ModInfo::default();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        let buffer_len = buffer.len();
+
+        for (i_line, line_vec) in buffer.iter().enumerate() {
+            let len = line_vec.len();
+            if len >= 2 {
+                let value = line_vec[1..].join(":"); //names and descriptions may contain ':'s
+                match line_vec[0].as_str() {
+                    "ID" => mod_info.id = value,
+                    "NUMERIC_VERSION" => {
+                        mod_info.numeric_version = buffer_err_wrap!(line_vec[1].parse(), i_line, buffer_len, 1..=1, 0, errors);
                     },
-                    "OBJECT"
-                    | "" => {}//do nothing for expected useless lines
-                    _ => {
-                        errors.push(DFGHError::ImportBufferError(i_line, buffer_len, 0..=(len-1), DFGHError::ImportUnknownError.to_string()));
+                    "DISPLAYED_VERSION" => mod_info.displayed_version = value,
+                    "EARLIEST_COMPATIBLE_NUMERIC_VERSION" => {
+                        mod_info.earliest_compatible_numeric_version = buffer_err_wrap!(line_vec[1].parse(), i_line, buffer_len, 1..=1, 0, errors);
                     },
+                    "EARLIEST_COMPATIBLE_DISPLAYED_VERSION" => mod_info.earliest_compatible_displayed_version = value,
+                    "AUTHOR" => mod_info.author = value,
+                    "NAME" => mod_info.name = value,
+                    "DESCRIPTION" => mod_info.description = value,
+                    "REQUIRES_ID" => mod_info.requires_ids.push(value),
+                    "REQUIRES_ID_BEFORE_ME" => mod_info.requires_ids_before_me.push(value),
+                    "REQUIRES_ID_AFTER_ME" => mod_info.requires_ids_after_me.push(value),
+                    "CONFLICTS_WITH_ID" => mod_info.conflicts_with_ids.push(value),
+                    _ => mod_info.other_tokens.push(line_vec.args.clone()),
                 }
+            } else {
+                mod_info.other_tokens.push(line_vec.args.clone());
             }
         }
-        (tile_page, errors)
+
+        mod_info.raw.push_block(&raw_buffer, 0..raw_buffer.len(), Some(mod_info.display()));
+
+        (mod_info, errors)
     }
 
     fn display(&self) -> String {
-        format!(
-            "[TILE_PAGE:{}]\n\t[FILE:{}]\n\t[TILE_DIM:{}:{}]\n\t[PAGE_DIM_PIXELS:{}:{}]\n\n",
-            self.name.with_boundaries(&[Boundary::Space]).to_case(Case::UpperSnake),
-            self.file_name.as_os_str().to_string_lossy().replace("\\", "/"),
-            self.tile_size[0],
-            self.tile_size[1],
-            self.image_size[0],
-            self.image_size[1]
-        )
+        let mut out = format!(
+            "[ID:{}]\n[NUMERIC_VERSION:{}]\n[DISPLAYED_VERSION:{}]\n[EARLIEST_COMPATIBLE_NUMERIC_VERSION:{}]\n[EARLIEST_COMPATIBLE_DISPLAYED_VERSION:{}]\n[AUTHOR:{}]\n[NAME:{}]\n[DESCRIPTION:{}]\n",
+            self.id,
+            self.numeric_version,
+            self.displayed_version,
+            self.earliest_compatible_numeric_version,
+            self.earliest_compatible_displayed_version,
+            self.author,
+            self.name,
+            self.description,
+        );
+
+        for (token, ids) in [
+            ("REQUIRES_ID", &self.requires_ids),
+            ("REQUIRES_ID_BEFORE_ME", &self.requires_ids_before_me),
+            ("REQUIRES_ID_AFTER_ME", &self.requires_ids_after_me),
+            ("CONFLICTS_WITH_ID", &self.conflicts_with_ids),
+        ] {
+            for id in ids {
+                out.push_str(&format!("[{}:{}]\n", token, id));
+            }
+        }
+
+        for token in self.other_tokens.iter() {
+            out.push_str(&format!("[{}]\n", token.join(":")));
+        }
+
+        out
     }
 }
-impl Menu for TilePage {
+impl Menu for ModInfo {
     fn menu(&mut self, ui: &mut Ui, _shared: &mut Shared) {
+        self.raw.encoding_label(ui);
         ui.separator();
-        ui.label("TilePage token");
-        ui.text_edit_singleline(&mut self.name);
-        ui.add_space(PADDING);
-
-        ui.label("Image file path:");
-        ui.horizontal(|ui| {
-            ui.label("graphics/");
+        egui::Grid::new("mod_info_grid").num_columns(2).show(ui, |ui| {
+            ui.label("ID:");
+            ui.text_edit_singleline(&mut self.id);
+            ui.end_row();
 
-            let mut file_name = self.file_name.clone().as_mut_os_string().to_string_lossy().replace("\\", "/");
-            ui.text_edit_singleline(&mut file_name);
+            ui.label("Name:");
+            ui.text_edit_singleline(&mut self.name);
+            ui.end_row();
 
-            self.file_name.clear();
-            self.file_name.push(file_name.split(&['\\','/']).collect::<PathBuf>());
+            ui.label("Author:");
+            ui.text_edit_singleline(&mut self.author);
+            ui.end_row();
 
-            if ui.button("⏷").clicked() {
-                if let Some(path) = rfd::FileDialog::new()
-                    .set_title(&self.name)
-                    .add_filter("png", &["png"])
-                    .pick_file() {
-                    if path.file_name().is_some() {
-                        let mut internal_path = path.components()
-                            .rev()
-                            .map(|c| c.as_os_str())
-                            .take_while(|c| !c.eq_ignore_ascii_case("graphics"))
-                            .collect::<Vec<_>>();
-                        internal_path.reverse();
-                        self.file_name = internal_path.iter()
-                            .map(|&p| p.to_string_lossy().to_string())
-                            .collect::<PathBuf>();
-                    }
+            ui.label("Numeric version:");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.numeric_version));
+                if ui.small_button("Bump").clicked() {
+                    self.numeric_version += 1;
                 }
-            }
-        });
-        ui.add_space(PADDING);
+            });
+            ui.end_row();
 
-        ui.label("Image size (pixels):");
-        ui.horizontal(|ui| {
-            ui.label(format!("Width: {}", self.image_size[0]));
-            ui.label(format!("Height: {}", self.image_size[1]));
-        });
-        ui.add_space(PADDING);
+            ui.label("Displayed version:");
+            ui.text_edit_singleline(&mut self.displayed_version);
+            ui.end_row();
 
-        ui.label("TilePage size (pixels):");
-        ui.horizontal(|ui| {
-            ui.add(egui::Slider::new(&mut self.tile_size[0], 0..=64).prefix("Width: "));
-            ui.add(egui::Slider::new(&mut self.tile_size[1], 0..=96).prefix("Height: "));
+            ui.label("Earliest compatible numeric version:");
+            ui.add(egui::DragValue::new(&mut self.earliest_compatible_numeric_version));
+            ui.end_row();
+
+            ui.label("Earliest compatible displayed version:");
+            ui.text_edit_singleline(&mut self.earliest_compatible_displayed_version);
+            ui.end_row();
         });
 
+        ui.add_space(PADDING);
+        ui.label("Description:");
+        ui.text_edit_multiline(&mut self.description);
+        //DF reads the description as a single token
+        self.description = self.description.replace(['\n', '\r'], " ");
+
+        for (label, ids) in [
+            ("Requires mods:", &mut self.requires_ids),
+            ("Requires mods loaded before this one:", &mut self.requires_ids_before_me),
+            ("Requires mods loaded after this one:", &mut self.requires_ids_after_me),
+            ("Conflicts with mods:", &mut self.conflicts_with_ids),
+        ] {
+            ui.add_space(PADDING);
+            ui.horizontal(|ui| {
+                ui.label(label);
+                if ui.small_button("+").clicked() {
+                    ids.push(String::new());
+                }
+            });
+            let mut remove = None;
+            for (i_id, id) in ids.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(id);
+                    if ui.small_button("-").clicked() {
+                        remove = Some(i_id);
+                    }
+                });
+            }
+            if let Some(i_id) = remove {
+                ids.remove(i_id);
+            }
+        }
+
         ui.add_space(PADDING);
         ui.label("Preview:");
         egui::ScrollArea::horizontal().show(ui, |ui| {
@@ -540,537 +1131,1903 @@ impl Menu for TilePage {
         });
     }
 }
+impl ModInfo {
+    /// Text written on export. An unedited info.txt keeps its original text, and an edited one keeps every line
+    /// it can: changed tokens are rewritten in place, removed ones are taken out of their line, and added ones go
+    /// after the last token of the same kind.
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/info.txt",
+    ///     "[ID:my_mod]\r\n[STEAM_TITLE:My Mod] shown on the workshop\r\n[NAME:My Mod] ;the name\r\n[REQUIRES_ID:a]\r\n[REQUIRES_ID:b]\r\n"
+    /// );
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let mut mod_info = graphics.mod_info.unwrap();
+    ///
+    /// mod_info.name = "Better Mod".to_string();
+    /// mod_info.requires_ids = vec!["c".to_string(), "b".to_string()];
+    /// assert_eq!(
+    ///     "[ID:my_mod]\r\n[STEAM_TITLE:My Mod] shown on the workshop\r\n[NAME:Better Mod] ;the name\r\n[REQUIRES_ID:c]\r\n[REQUIRES_ID:b]\r\n\
+    ///     [NUMERIC_VERSION:0]\r\n[DISPLAYED_VERSION:]\r\n[EARLIEST_COMPATIBLE_NUMERIC_VERSION:0]\r\n\
+    ///     [EARLIEST_COMPATIBLE_DISPLAYED_VERSION:]\r\n[AUTHOR:]\r\n[DESCRIPTION:]\r\n",
+    ///     mod_info.display_raw()
+    /// );
+    /// ```
+    pub fn display_raw(&self) -> String {
+        let display = self.display();
+        let original = match self.raw.blocks.first() {
+            Some(block) if !block.lines.is_empty() => block,
+            _ => return display,
+        };
+        if original.display == display {
+            return original.lines.concat();
+        }
+
+        let ending = if original.lines.first().is_some_and(|line| line.ends_with("\r\n")) { "\r\n" } else { "\n" };
+        let path = Path::new("info.txt");
+        let new_tokens: Vec<Vec<String>> = display
+            .lines()
+            .flat_map(|line| Token::read_line(line, 0, path))
+            .map(|token| token.args)
+            .collect();
+        let old_tokens = Token::tokenize(&original.lines, path);
+
+        //unchanged tokens keep their place first, then changed ones take the place of one of the same kind
+        let mut used = vec![false; new_tokens.len()];
+        let mut pairs: Vec<Option<usize>> = old_tokens.iter()
+            .map(|old| {
+                let pair = (0..new_tokens.len()).find(|i| !used[*i] && new_tokens[*i] == old.args);
+                if let Some(i) = pair {
+                    used[i] = true;
+                }
+                pair
+            })
+            .collect();
+        for (old, pair) in old_tokens.iter().zip(pairs.iter_mut()) {
+            if pair.is_none() {
+                *pair = (0..new_tokens.len()).find(|i| !used[*i] && new_tokens[*i][0] == old[0]);
+                if let Some(i) = *pair {
+                    used[i] = true;
+                }
+            }
+        }
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub enum GraphicsFile {
-    #[default]
-    DefaultFile,
-    CreatureFile(String, Vec<Creature>),
-    StatueCreatureFile(String, Vec<Statue>),
-    PlantFile(String, Vec<Plant>),
-    TileGraphicsFile(String, Vec<TileGraphic>),
+        //rewrite tokens from the end of each line so the columns of the ones before stay right
+        let mut lines = original.lines.clone();
+        for (old, pair) in old_tokens.iter().zip(pairs.iter()).rev() {
+            let chars: Vec<char> = lines[old.line].chars().collect();
+            let token = pair.map(|i| format!("[{}]", new_tokens[i].join(":"))).unwrap_or_default();
+            lines[old.line] = format!("{}{}{}",
+                chars[..old.columns.start].iter().collect::<String>(),
+                token,
+                chars[old.columns.end..].iter().collect::<String>()
+            );
+        }
+
+        //added tokens go after the last token of the same kind, or after the last token
+        let mut added: Vec<Vec<String>> = vec![Vec::new(); lines.len()];
+        for (i_new, new_token) in new_tokens.iter().enumerate().filter(|(i, _)| !used[*i]) {
+            let anchor = old_tokens.iter()
+                .rev()
+                .find(|old| old[0] == new_token[0])
+                .or(old_tokens.last())
+                .map_or(lines.len() - 1, |old| old.line);
+            added[anchor].push(format!("[{}]", new_tokens[i_new].join(":")));
+        }
+
+        let mut out = String::new();
+        for (i_line, line) in lines.iter().enumerate() {
+            //a line left empty by a removed token goes with it
+            let emptied = line.trim().is_empty() && old_tokens.iter().any(|old| old.line == i_line);
+            if !emptied {
+                out.push_str(line);
+            }
+            for token in &added[i_line] {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push_str(ending);
+                }
+                out.push_str(token);
+                out.push_str(ending);
+            }
+        }
+
+        out
+    }
+
+    fn export(&self) -> Result<ExportFile> {
+        let info_path = PathBuf::from("info.txt");
+        let bytes = self.raw.encode(&self.display_raw(), &info_path)?;
+
+        Ok(ExportFile { path: info_path, bytes })
+    }
 }
-impl RAW for GraphicsFile {
-    fn new() -> Self {
+
+/// Original text of an imported raw file, split into its header and top level blocks.
+///
+/// Each part keeps the `display()` text it was parsed into, so anything that hasn't been edited since import
+/// (comments, blank lines, unknown tokens, line endings) is written back exactly as it was read.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RawFile {
+    pub header: RawBlock,       //file name line, [OBJECT:..] and any text before the first block
+    pub blocks: Vec<RawBlock>,  //one entry per imported block with its trailing comments and blank lines
+    pub encoding: Encoding,     //text encoding the file was read in and will be written back in
+    #[serde(default)]
+    pub file_name: String,      //name of the file it was imported from, empty if it wasn't imported
+}
+impl RawFile {
+    pub fn new() -> Self {
         Self::default()
     }
 
-    fn read(buffer: Vec<Vec<String>>, raw_buffer: Vec<String>, path: &PathBuf) -> (Self, Vec<DFGHError>) {
-        let mut block_buffer = Vec::with_capacity(100);
-        let mut graphics_file = GraphicsFile::default();
-        let mut errors: Vec<DFGHError> = Vec::new();
+    /// Attach the lines of a block to the file. Blocks that were not imported (`display` is `None`) are kept
+    /// as trivia of the previous block so no text is lost.
+    fn push_block(&mut self, raw_buffer: &[String], lines: std::ops::Range<usize>, display: Option<String>) {
+        let raw_lines = raw_buffer.get(lines).unwrap_or_default().to_vec();
 
-        //name must match file name.
-        let file_name = path
-            .file_name().get_or_insert(&OsStr::new("no_name"))
-            .to_str().get_or_insert("no_name")
-            .replace(".txt", "").trim().to_string();
-        
-        //create vector (buffer) of all lines between relevant headers and import each buffer.
-        for (i_line, line_vec) in buffer.iter().enumerate() {
-            let len = line_vec.len();
+        if let Some(display) = display {
+            self.blocks.push(RawBlock::read(raw_lines, display));
+        } else if let Some(last_block) = self.blocks.last_mut() {
+            last_block.node.last_mut().lines.extend(raw_lines.clone());
+            last_block.lines.extend(raw_lines);
+        } else {
+            self.header.lines.extend(raw_lines);
+        }
+    }
 
-            match graphics_file {
-                GraphicsFile::DefaultFile => {//identify graphics file type
-                    if len >=2 {
-                        match line_vec[0].as_str() {
-                            "CREATURE_CASTE_GRAPHICS" |
-                            "CREATURE_GRAPHICS" => {
-                                graphics_file = GraphicsFile::CreatureFile(file_name.clone(), Vec::new());
-                            },
-                            "STATUE_CREATURE_CASTE_GRAPHICS" |
-                            "STATUE_CREATURE_GRAPHICS" => {
-                                graphics_file = GraphicsFile::StatueCreatureFile(file_name.clone(), Vec::new());
-                            },
-                            "PLANT_GRAPHICS" => {
-                                graphics_file = GraphicsFile::PlantFile(file_name.clone(), Vec::new());
-                            },
-                            "TILE_GRAPHICS" |
-                            "HELM_GRAPHICS" |
-                            "ARMOR_GRAPHICS" |
-                            "PANTS_GRAPHICS" |
-                            "GLOVES_GRAPHICS" |
-                            "SHOES_GRAPHICS" |
-                            "AMMO_GRAPHICS" |
-                            "SIEGEAMMO_GRAPHICS" |
-                            "WEAPON_GRAPHICS" |
-                            "SHIELD_GRAPHICS" |
-                            "TRAPCOMP_GRAPHICS" |
-                            "BOULDER_GRAPHICS" |
-                            "ROUGH_GEM_GRAPHICS" |
-                            "BARS_GRAPHICS" |
-                            "FOOD_GRAPHICS" |
-                            "TOY_GRAPHICS" |
-                            "TOOL_GRAPHICS" |
-                            "ADD_TOOL_GRAPHICS" |
-                            "FOOD_CONTAINER_GRAPHICS" => {
-                                graphics_file = GraphicsFile::TileGraphicsFile(file_name.clone(), Vec::new());
-                            },
-                            _ => {continue}
-                        }
-                        //if this doesn't get skipped then we have a graphics file type and need to start buffering.
-                        block_buffer.push(line_vec.clone());
-                    }
-                    continue;
-                },
-                GraphicsFile::CreatureFile(_, ref mut creatures) => {
-                    if len >=1 {
-                        match line_vec[0].as_str() {
-                            "CREATURE_CASTE_GRAPHICS" |
-                            "CREATURE_GRAPHICS" => {
-                                if block_buffer.len() > 0 {
-                                    let (c_temp, temp) = Creature::read(block_buffer.clone(), Vec::new(), path);
-                                    let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, i_line, path)).collect();
-                                    errors.append(&mut es_temp);
-                                    if c_temp.ne(&Creature::new()) {
-                                        creatures.push(c_temp);
-                                    }
-                                    block_buffer.clear();
-                                }
-                            },
-                            _ => {}
-                        }
-                    }
-                },
-                GraphicsFile::StatueCreatureFile(_, ref mut statues) => {
-                    if len >=1 {
-                        match line_vec[0].as_str() {
-                            "STATUE_CREATURE_CASTE_GRAPHICS" |
-                            "STATUE_CREATURE_GRAPHICS" => {
-                                if block_buffer.len() > 0 {
-                                    let (s_temp, temp) = Statue::read(block_buffer.clone(), Vec::new(), path);
-                                    let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, i_line, path)).collect();
-                                    errors.append(&mut es_temp);
-                                    if s_temp.ne(&Statue::new()) {
-                                        statues.push(s_temp);
-                                    }
-                                    block_buffer.clear();
-                                }
-                            },
-                            _ => {}
-                        }
+    /// Attach a block this tool doesn't model. It is always written back as it was read.
+    fn push_passthrough(&mut self, raw_buffer: &[String], lines: std::ops::Range<usize>, block: UnknownBlock) {
+        let raw_lines = raw_buffer.get(lines).unwrap_or_default().to_vec();
+
+        self.blocks.push(RawBlock { lines: raw_lines, display: block.display(), passthrough: Some(block), ..Default::default() });
+    }
+
+    /// Build the text of a file from the current `display()` of its header and blocks.
+    ///
+    /// Any header or block whose display is unchanged since import is replaced with its original text.
+    /// An edited block is matched to the original block with the same header line, and only the nodes
+    /// (layer sets, layer groups, layers and conditions) that changed are regenerated. Passthrough blocks
+    /// follow the block they came after, or stay in front of the block that came after them if that one was removed.
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let mut raw_file = RawFile::new();
+    /// raw_file.header = RawBlock { lines: vec!["header\r\n".to_string()], display: "header\n".to_string(), ..Default::default() };
+    /// raw_file.blocks.push(RawBlock { lines: vec!["[A] comment\r\n".to_string()], display: "[A]\n".to_string(), ..Default::default() });
+    ///
+    /// let unedited = raw_file.render("header\n".to_string(), vec!["[A]\n".to_string()]);
+    /// assert_eq!("header\r\n[A] comment\r\n", unedited);
+    ///
+    /// let edited = raw_file.render("header\n".to_string(), vec!["[B]\n".to_string(), "[A]\n".to_string()]);
+    /// assert_eq!("header\r\n[B]\n[A] comment\r\n", edited);
+    /// ```
+    /// A passthrough block keeps its place when the block before it is removed:
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let mut raw_file = RawFile::new();
+    /// for (line, passthrough) in [("[A]\n", false), ("[KEPT]\n", true), ("[B]\n", false)] {
+    ///     raw_file.blocks.push(RawBlock {
+    ///         lines: vec![line.to_string()],
+    ///         display: line.to_string(),
+    ///         passthrough: passthrough.then(UnknownBlock::default),
+    ///         ..Default::default()
+    ///     });
+    /// }
+    ///
+    /// let edited = raw_file.render(String::new(), vec!["[C]\n".to_string(), "[B]\n".to_string()]);
+    /// assert_eq!("[C]\n[KEPT]\n[B]\n", edited);
+    /// ```
+    /// Editing one layer keeps the comments and formatting of the rest of the block:
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let original = vec![
+    ///     "[CREATURE_GRAPHICS:DWARF]\r\n",
+    ///     "    [LAYER_SET:DEFAULT] ;adults\r\n",
+    ///     "        [LAYER:BODY:DWARF_BODY:0:0]\r\n",
+    ///     "            [CONDITION_CASTE:MALE] ;only the men\r\n",
+    ///     "        [LAYER:HAIR:DWARF_HAIR:1:0] ;beards too\r\n",
+    /// ];
+    /// let display = "[CREATURE_GRAPHICS:DWARF]\n\t[LAYER_SET:DEFAULT]\n\t\t\t[LAYER:BODY:DWARF_BODY:0:0]\n\
+    ///     \t\t\t\t[CONDITION_CASTE:MALE]\n\t\t\t[LAYER:HAIR:DWARF_HAIR:1:0]\n";
+    /// let mut raw_file = RawFile::new();
+    /// raw_file.blocks.push(RawBlock::read(original.iter().map(|line| line.to_string()).collect(), display.to_string()));
+    ///
+    /// let edited = raw_file.render(String::new(), vec![display.replace("DWARF_HAIR:1:0", "DWARF_HAIR:2:0")]);
+    /// assert!(edited.contains("[CONDITION_CASTE:MALE] ;only the men\r\n"));
+    /// assert_eq!(original.concat().replace("DWARF_HAIR:1:0", "DWARF_HAIR:2:0"), edited);
+    /// ```
+    pub fn render(&self, header: String, blocks: Vec<String>) -> String {
+        let mut out = String::new();
+        let mut used = vec![false; self.blocks.len()];
+        let mut matched = vec![false; self.blocks.len()];
+
+        if !self.header.lines.is_empty() && self.header.display == header {
+            out.push_str(&self.header.lines.concat());
+        } else {
+            out.push_str(&header);
+        }
+        self.push_passthrough_lines(0, &mut out, &mut used);
+
+        //unedited blocks are matched first so an edited block can't take the original of one that wasn't edited
+        let mut originals: Vec<Option<usize>> = blocks.iter()
+            .map(|block| {
+                let original = self.blocks
+                    .iter()
+                    .enumerate()
+                    .position(|(i, raw_block)| !matched[i] && raw_block.passthrough.is_none() && raw_block.display == *block);
+                if let Some(i) = original {
+                    matched[i] = true;
+                }
+                original
+            })
+            .collect();
+        let nodes: Vec<RawNode> = blocks.iter().map(|block| RawNode::split_display(block)).collect();
+        for (original, node) in originals.iter_mut().zip(nodes.iter()) {
+            if original.is_none() {
+                *original = self.blocks
+                    .iter()
+                    .enumerate()
+                    .position(|(i, raw_block)| !matched[i] && raw_block.passthrough.is_none()
+                        && !raw_block.node.key.is_empty() && RawNode::same(&raw_block.node.display, &node.lines.concat()));
+                if let Some(i) = *original {
+                    matched[i] = true;
+                }
+            }
+        }
+        //a renamed block keeps the original in the same place, if it's the same kind of block
+        let read: Vec<usize> = (0..self.blocks.len()).filter(|i| self.blocks[*i].passthrough.is_none()).collect();
+        for (i_block, (original, node)) in originals.iter_mut().zip(nodes.iter()).enumerate() {
+            if original.is_none() {
+                *original = read.get(i_block)
+                    .copied()
+                    .filter(|i| !matched[*i] && !node.key.is_empty() && self.blocks[*i].node.key == node.key);
+                if let Some(i) = *original {
+                    matched[i] = true;
+                }
+            }
+        }
+
+        for ((block, node), original) in blocks.iter().zip(nodes.iter()).zip(originals) {
+            if let Some(i) = original {
+                //passthrough blocks that followed a removed or replaced block stay in front of the next kept one
+                for (i_before, raw_block) in self.blocks.iter().enumerate().take(i) {
+                    let follows_unmatched = self.blocks[..i_before]
+                        .iter()
+                        .rposition(|b| b.passthrough.is_none())
+                        .is_some_and(|i_prev| !matched[i_prev]);
+                    if raw_block.passthrough.is_some() && !used[i_before] && follows_unmatched {
+                        used[i_before] = true;
+                        push_generated(&mut out, &raw_block.lines.concat(), "\n");
                     }
+                }
+                if self.blocks[i].display == *block {
+                    out.push_str(&self.blocks[i].lines.concat());
+                } else {
+                    let ending = if self.blocks[i].lines.first().is_some_and(|line| line.ends_with("\r\n")) { "\r\n" } else { "\n" };
+                    self.blocks[i].node.render(node, ending, &mut out);
+                }
+                self.push_passthrough_lines(i + 1, &mut out, &mut used);
+            } else {
+                push_generated(&mut out, block, "\n");
+            }
+        }
+
+        //passthrough blocks after the last kept block
+        for (i, raw_block) in self.blocks.iter().enumerate() {
+            if raw_block.passthrough.is_some() && !used[i] {
+                push_generated(&mut out, &raw_block.lines.concat(), "\n");
+            }
+        }
+
+        out
+    }
+
+    //write the run of passthrough blocks starting at `from`
+    fn push_passthrough_lines(&self, from: usize, out: &mut String, used: &mut [bool]) {
+        for (i, raw_block) in self.blocks.iter().enumerate().skip(from) {
+            if raw_block.passthrough.is_none() {
+                break;
+            }
+            if !used[i] {
+                used[i] = true;
+                push_generated(out, &raw_block.lines.concat(), "\n");
+            }
+        }
+    }
+
+    /// Whether an object name is still the one read from the file name, so it can be exported back to that file.
+    pub fn names_file(&self, name: &str) -> bool {
+        !self.file_name.is_empty() && self.file_name.replace(".txt", "").trim() == name
+    }
+
+    /// Note in a file menu that the file isn't UTF-8, and so will be saved back in its own encoding.
+    pub fn encoding_label(&self, ui: &mut Ui) {
+        if self.encoding.ne(&Encoding::Utf8) {
+            ui.label(format!("Read as {0}, it will be saved back as {0}.", self.encoding.name()));
+        }
+    }
+
+    /// Encode the text of a file in the encoding it was imported with.
+    pub fn encode(&self, text: &str, path: &Path) -> Result<Vec<u8>> {
+        self.encoding
+            .encode(text)
+            .map_err(|unencodable| DFGHError::EncodeError(path.to_path_buf(), self.encoding.name(), unencodable))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RawBlock {
+    pub lines: Vec<String>, //original lines including line endings
+    pub display: String,    //display() of the block as it was imported
+    #[serde(default)]
+    pub passthrough: Option<UnknownBlock>, //block this tool doesn't model, written back unchanged
+    #[serde(default)]
+    pub node: RawNode,      //the same lines split into nodes, so an edited block keeps the parts that weren't edited
+}
+impl RawBlock {
+    /// Keep the original lines of a block along with the `display()` it was read into.
+    pub fn read(lines: Vec<String>, display: String) -> Self {
+        let node = RawNode::split(&lines).pair(RawNode::split_display(&display));
+
+        RawBlock { lines, display, passthrough: None, node }
+    }
+}
+
+/// Part of an imported block: the block itself, a layer set, layer group, layer or a single condition.
+///
+/// A node holds its own line (and the comments or blank lines after it) up to its first child, so when a
+/// node is edited its children and siblings can still be written back as they were read.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RawNode {
+    pub key: String,            //first token of the node's line, empty for a block without tokens
+    pub lines: Vec<String>,     //original lines before the first child, including line endings
+    pub display: String,        //display() text of the same part of the node as it was imported
+    pub children: Vec<RawNode>,
+    #[serde(default)]
+    pub unread: bool,           //tokens this tool doesn't read, written back after the node before them
+}
+impl RawNode {
+    //nesting depth of a node by its first token, anything that isn't a layer or a group of layers is a leaf
+    fn depth(key: &str) -> usize {
+        match key {
+            "LAYER_SET" => 1,
+            "LAYER_GROUP" |
+            "END_LAYER_GROUP" |
+            "LS_PALETTE" |
+            "LS_PALETTE_FILE" |
+            "LS_PALETTE_DEFAULT" => 2,
+            "LAYER" => 3,
+            _ => 4,
+        }
+    }
+
+    /// Split the lines of a block into nodes, one for each token. Text without a token belongs to the node before it.
+    fn split(lines: &[String]) -> RawNode {
+        let mut open: Vec<(usize, RawNode)> = vec![(0, RawNode::default())];
+
+        for (key, text) in lines.iter().flat_map(|line| Self::split_line(line)) {
+            match key {
+                Some(key) if open.len() > 1 || !open[0].1.key.is_empty() => {
+                    let depth = Self::depth(&key);
+                    Self::close(&mut open, depth);
+                    open.push((depth, RawNode { key, lines: vec![text], ..Default::default() }));
                 },
-                GraphicsFile::PlantFile(_, ref mut plants) => {
-                    if len >=1 {
-                        match line_vec[0].as_str() {
-                            "PLANT_GRAPHICS" => {
-                                if block_buffer.len() > 0 {
-                                    let (p_temp, temp) = Plant::read(block_buffer.clone(), Vec::new(), path);
-                                    let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, i_line, path)).collect();
-                                    errors.append(&mut es_temp);
-                                    if p_temp.ne(&Plant::new()) {
-                                        plants.push(p_temp);
-                                    }
-                                    block_buffer.clear();
-                                }
-                            },
-                            _ => {}
-                        }
-                    }
+                Some(key) => {
+                    //the block header
+                    open[0].1.key = key;
+                    open[0].1.lines.push(text);
                 },
-                GraphicsFile::TileGraphicsFile(_, ref mut tile_graphics) => {
-                    if len >=1 {
-                        match line_vec[0].as_str() {
-                            "TILE_GRAPHICS" |
-                            "HELM_GRAPHICS" |
-                            "ARMOR_GRAPHICS" |
-                            "PANTS_GRAPHICS" |
-                            "GLOVES_GRAPHICS" |
-                            "SHOES_GRAPHICS" |
-                            "AMMO_GRAPHICS" |
-                            "SIEGEAMMO_GRAPHICS" |
-                            "WEAPON_GRAPHICS" |
-                            "SHIELD_GRAPHICS" |
-                            "TRAPCOMP_GRAPHICS" |
-                            "BOULDER_GRAPHICS" |
-                            "ROUGH_GEM_GRAPHICS" |
-                            "BARS_GRAPHICS" |
-                            "FOOD_GRAPHICS" |
-                            "TOY_GRAPHICS" |
-                            "TOOL_GRAPHICS" |
-                            "ADD_TOOL_GRAPHICS" |
-                            "FOOD_CONTAINER_GRAPHICS" => {
-                                if block_buffer.len() > 0 {
-                                    let (tg_temp, temp) = TileGraphic::read(block_buffer.clone(), Vec::new(), path);
-                                    let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, i_line, path)).collect();
-                                    errors.append(&mut es_temp);
-                                    if tg_temp.ne(&TileGraphic::new()) {
-                                        tile_graphics.push(tg_temp);
-                                    }
-                                    block_buffer.clear();
-                                }
-                            },
-                            _ => {}
-                        }
+                None => {
+                    if let Some((_, node)) = open.last_mut() {
+                        node.lines.push(text);
                     }
                 },
             }
-            block_buffer.push(line_vec.clone());
         }
+        Self::close(&mut open, 1);
 
-        let last_line = buffer.len();
-        if block_buffer.len() > 0 {//flush buffer
-            match graphics_file {
-                GraphicsFile::DefaultFile => {},
-                GraphicsFile::CreatureFile(_, ref mut creatures) => {
-                    let (c_temp, temp) = Creature::read(block_buffer.clone(), Vec::new(), path);
-                    let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, last_line, path)).collect();
-                    errors.append(&mut es_temp);
-                    if c_temp.ne(&Creature::new()) {
-                        creatures.push(c_temp);
-                    }
-                },
-                GraphicsFile::StatueCreatureFile(_, ref mut statues) => {
-                    let (s_temp, temp) = Statue::read(block_buffer.clone(), Vec::new(), path);
-                    let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, last_line, path)).collect();
-                    errors.append(&mut es_temp);
-                    if s_temp.ne(&Statue::new()) {
-                        statues.push(s_temp);
-                    }
+        open.pop().map(|(_, node)| node).unwrap_or_default()
+    }
+
+    //split a line before each token after the first, so tokens sharing a line are still separate nodes
+    fn split_line(line: &str) -> Vec<(Option<String>, String)> {
+        let tokens = Token::read_line(line, 0, Path::new(""));
+        if tokens.is_empty() {
+            return vec![(None, line.to_string())];
+        }
+
+        let mut starts: Vec<usize> = tokens.iter().skip(1).map(|token| Self::byte_index(line, token.columns.start)).collect();
+        starts.insert(0, 0);
+        starts.push(line.len());
+
+        tokens.iter()
+            .zip(starts.windows(2))
+            .map(|(token, bounds)| (Some(token[0].clone()), line[bounds[0]..bounds[1]].to_string()))
+            .collect()
+    }
+
+    //token columns count characters, not bytes
+    fn byte_index(line: &str, column: usize) -> usize {
+        line.char_indices().nth(column).map_or(line.len(), |(i, _)| i)
+    }
+
+    fn split_display(display: &str) -> RawNode {
+        let lines: Vec<String> = display.split_inclusive('\n').map(String::from).collect();
+        Self::split(&lines)
+    }
+
+    //move every open node at `depth` or deeper into its parent
+    fn close(open: &mut Vec<(usize, RawNode)>, depth: usize) {
+        while open.len() > 1 && open.last().is_some_and(|(d, _)| *d >= depth) {
+            if let Some((_, node)) = open.pop() {
+                if let Some((_, parent)) = open.last_mut() {
+                    parent.children.push(node);
+                }
+            }
+        }
+    }
+
+    /// Join the original lines of a node with the display it was read into.
+    ///
+    /// Children are matched in order by their key. Original children missing from the display (tokens this
+    /// tool doesn't read) are kept as unread nodes. If a layer, group or layer set in the display has no
+    /// original to match, the node isn't split and is only ever written back whole.
+    fn pair(self, display: RawNode) -> RawNode {
+        let all_lines = self.all_lines();
+        let mut children: Vec<RawNode> = Vec::new();
+        let mut raw_children = self.children;
+
+        for display_child in display.children.iter().cloned() {
+            match raw_children.iter().position(|raw_child| raw_child.key == display_child.key) {
+                Some(i_raw) => {
+                    let mut skipped: Vec<RawNode> = raw_children.drain(..=i_raw).collect();
+                    let raw_child = skipped.pop().unwrap_or_default();
+                    children.extend(skipped.into_iter().map(RawNode::into_unread));
+                    children.push(raw_child.pair(display_child));
                 },
-                GraphicsFile::PlantFile(_, ref mut plants) => {
-                    let (p_temp, temp) = Plant::read(block_buffer.clone(), Vec::new(), path);
-                    let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, last_line, path)).collect();
-                    errors.append(&mut es_temp);
-                    if p_temp.ne(&Plant::new()) {
-                        plants.push(p_temp);
-                    }
+                //a condition that is only written differently, its original is kept as unread
+                None if Self::depth(&display_child.key) > 3 => {
+                    children.push(RawNode { key: display_child.key.clone(), ..Default::default() }.pair(display_child));
                 },
-                GraphicsFile::TileGraphicsFile(_, ref mut tile_graphics) => {
-                    let (tg_temp, temp) = TileGraphic::read(block_buffer.clone(), Vec::new(), path);
-                    let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), e, last_line, path)).collect();
-                    errors.append(&mut es_temp);
-                    if tg_temp.ne(&TileGraphic::new()) {
-                        tile_graphics.push(tg_temp);
-                    }
+                None => {
+                    return RawNode { key: self.key, lines: all_lines, display: display.text(), ..Default::default() };
                 },
             }
         }
-        (graphics_file, errors)
+        children.extend(raw_children.into_iter().map(RawNode::into_unread));
+
+        RawNode { key: self.key, lines: self.lines, display: display.lines.concat(), children, unread: false }
     }
 
-    fn display(&self) -> String {
-        let mut out;
-        
-        match self {
-            GraphicsFile::DefaultFile => out = "".to_string(),
-            GraphicsFile::CreatureFile(file_name, creatures) => {
-                out = format!("graphics_creatures_{}\n\n[OBJECT:GRAPHICS]\n\n",
-                    file_name
-                    .with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
-                    .to_case(Case::Snake)
-                    .replace("graphics_", "")
-                    .replace("creatures_", "")
-                    .replace(".txt", "")
-                );
+    fn into_unread(self) -> RawNode {
+        RawNode { key: self.key.clone(), lines: self.all_lines(), unread: true, ..Default::default() }
+    }
 
-                for creature in creatures {
-                    out.push_str(&creature.display());
-                }
-            },
-            GraphicsFile::StatueCreatureFile(file_name, statues) => {
-                out = format!("graphics_creatures_{}_statue\n\n[OBJECT:GRAPHICS]\n\n",
-                    file_name
-                    .with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
-                    .to_case(Case::Snake)
-                    .replace("graphics_", "")
-                    .replace("creatures_", "")
-                    .replace("_statue", "")
-                    .replace(".txt", "")
-                );
+    //original lines of the node and all its children
+    fn all_lines(&self) -> Vec<String> {
+        let mut lines = self.lines.clone();
+        for child in &self.children {
+            lines.extend(child.all_lines());
+        }
+        lines
+    }
 
-                for statue in statues {
-                    out.push_str(&statue.display());
-                }
-            },
-            GraphicsFile::PlantFile(file_name, plants) => {
-                out = format!("graphics_{}\n\n[OBJECT:GRAPHICS]\n\n",
-                    file_name
-                    .with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
-                    .to_case(Case::Snake)
-                    .replace("graphics_", "")
-                    .replace(".txt", "")
-                );
+    //text of the node and all its children
+    fn text(&self) -> String {
+        self.all_lines().concat()
+    }
 
-                for plant in plants {
-                    out.push_str(&plant.display());
-                }
-            },
-            GraphicsFile::TileGraphicsFile(file_name, tile_graphics) => {
-                out = format!("graphics_{}\n\n[OBJECT:GRAPHICS]\n\n",
-                    file_name
-                    .with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
-                    .to_case(Case::Snake)
-                    .replace("graphics_", "")
-                    .replace(".txt", "")
-                );
+    //display of the node and all its children as it was imported
+    fn imported_display(&self) -> String {
+        let mut display = self.display.clone();
+        for child in &self.children {
+            display.push_str(&child.imported_display());
+        }
+        display
+    }
 
-                for tile_graphic in tile_graphics {
-                    out.push_str(&tile_graphic.display());
+    /// The node read last, where lines after the block belong.
+    fn last_mut(&mut self) -> &mut RawNode {
+        if self.children.is_empty() {
+            self
+        } else {
+            let last = self.children.len() - 1;
+            self.children[last].last_mut()
+        }
+    }
+
+    /// Write a node split from the current display, using the original text of every part that is unchanged.
+    ///
+    /// Generated lines use the line ending of the original block.
+    fn render(&self, current: &RawNode, ending: &str, out: &mut String) {
+        if Self::same(&self.imported_display(), &current.text()) {
+            out.push_str(&self.text());
+            return;
+        }
+
+        let generated = current.lines
+            .iter()
+            .find(|line| !Token::read_line(line, 0, Path::new("")).is_empty())
+            .map(|line| line.trim().to_string());
+        let original = self.lines
+            .iter()
+            .enumerate()
+            .find_map(|(i, line)| Token::read_line(line, 0, Path::new("")).first().map(|token| (i, token.columns.clone())));
+
+        if Self::same(&self.display, &current.lines.concat()) {
+            out.push_str(&self.lines.concat());
+        } else if let (Some(generated), Some((i_line, columns))) = (generated, original) {
+            //only the token changed, not the indentation or comments around it
+            let line = &self.lines[i_line];
+            out.push_str(&self.lines[..i_line].concat());
+            out.push_str(&line[..Self::byte_index(line, columns.start)]);
+            out.push_str(&generated);
+            out.push_str(&line[Self::byte_index(line, columns.end)..]);
+            out.push_str(&self.lines[i_line + 1..].concat());
+        } else {
+            push_generated(out, &current.lines.concat().replace('\n', ending), ending);
+        }
+
+        let read: Vec<usize> = (0..self.children.len()).filter(|i| !self.children[*i].unread).collect();
+        let mut used = vec![false; self.children.len()];
+        //unedited children are matched first so an edited one can't take their original
+        let unedited: Vec<Option<usize>> = current.children
+            .iter()
+            .map(|child| {
+                let i = read.iter().copied().find(|i| !used[*i] && Self::same(&self.children[*i].imported_display(), &child.text()));
+                if let Some(i) = i {
+                    used[i] = true;
                 }
-            },
+                i
+            })
+            .collect();
+
+        self.push_unread(0, out, &mut used);
+        for (i_current, (child, unedited)) in current.children.iter().zip(unedited).enumerate() {
+            //otherwise the child in the same place, if it's the same kind of node
+            let edited = read.get(i_current)
+                .copied()
+                .filter(|i| !used[*i] && self.children[*i].key == child.key);
+
+            if let Some(i) = unedited {
+                out.push_str(&self.children[i].text());
+                self.push_unread(i + 1, out, &mut used);
+            } else if let Some(i) = edited {
+                used[i] = true;
+                self.children[i].render(child, ending, out);
+                self.push_unread(i + 1, out, &mut used);
+            } else {
+                //blank lines display() puts between nodes are already in the original
+                let text: String = child.all_lines().iter().filter(|line| !line.trim().is_empty()).map(|line| line.replace('\n', ending)).collect();
+                push_generated(out, &text, ending);
+            }
         }
 
-        out
+        //unread children that lost the child they followed
+        for (i, child) in self.children.iter().enumerate() {
+            if child.unread && !used[i] {
+                push_generated(out, &child.text(), ending);
+            }
+        }
+    }
+
+    //whether two display texts are the same apart from blank lines, which display() puts between nodes
+    fn same(imported: &str, current: &str) -> bool {
+        let imported = imported.lines().filter(|line| !line.trim().is_empty());
+        let current = current.lines().filter(|line| !line.trim().is_empty());
+        imported.eq(current)
+    }
+
+    //write the run of unread children starting at `from`
+    fn push_unread(&self, from: usize, out: &mut String, used: &mut [bool]) {
+        for (i, child) in self.children.iter().enumerate().skip(from).take_while(|(_, child)| child.unread) {
+            used[i] = true;
+            out.push_str(&child.text());
+        }
     }
 }
-impl GraphicsFile {
-    fn name(&self) -> String {
+
+//append generated text, starting a new line if the original text before it didn't end in one
+fn push_generated(out: &mut String, text: &str, ending: &str) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push_str(ending);
+    }
+    out.push_str(text);
+}
+
+/// Text encoding of a raw file.
+/// 
+/// DF raws are traditionally CP437, but most editors save UTF-8 or Latin-1, so all three are read and written.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    Cp437,
+    Latin1,
+}
+impl Encoding {
+    pub fn name(&self) -> String {
         match self {
-            GraphicsFile::DefaultFile => "(new)".to_string(),
-            GraphicsFile::CreatureFile(name, _) => name.clone().replace("graphics_", ""),
-            GraphicsFile::StatueCreatureFile(name, _) => name.clone().replace("graphics_", ""),
-            GraphicsFile::PlantFile(name, _) => name.clone().replace("graphics_", ""),
-            GraphicsFile::TileGraphicsFile(name, _) => name.clone().replace("graphics_", ""),
+            Encoding::Utf8 => "UTF-8".to_string(),
+            Encoding::Cp437 => "CP437".to_string(),
+            Encoding::Latin1 => "Latin-1".to_string(),
         }
     }
 
-    fn export(&self, path: &PathBuf) -> Result<()> {
-        match self {
-            GraphicsFile::DefaultFile => return Ok(()),
-            GraphicsFile::CreatureFile(name, _) => {
-                let mut graphics_file_writer = graphics_file_export!("graphics_creatures_", name, "", creatures, path);
-                
-                graphics_file_writer.write_all(self.display().as_bytes())?;
-                
-                graphics_file_writer.flush()?;
+    /// Decode the bytes of a raw file, trying UTF-8 first and falling back to CP437 or Latin-1.
+    ///
+    /// Text that isn't UTF-8 can't be told apart for certain, so each byte above 0x7F votes for the
+    /// encoding that reads it as a Latin letter: 0x81 is ü in CP437 but a control character in Latin-1,
+    /// 0xE9 is é in Latin-1 but Θ in CP437. Latin-1 wins ties, as that is what most editors save.
+    /// Both encodings write back exactly the bytes they read, so a wrong guess only shows the wrong
+    /// characters in the editor, it never changes the file.
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let (text, encoding) = Encoding::decode(&[b'[', 0x81, b']']);
+    /// assert_eq!(("[ü]", Encoding::Cp437), (text.as_str(), encoding.clone()));
+    /// assert_eq!(vec![b'[', 0x81, b']'], encoding.encode(&text).unwrap());
+    ///
+    /// let (text, encoding) = Encoding::decode(&[b'[', 0xE9, b']']);
+    /// assert_eq!(("[é]", Encoding::Latin1), (text.as_str(), encoding));
+    ///
+    /// //a CP437 box drawing line doesn't outvote the accented letters of a Latin-1 file
+    /// let (text, encoding) = Encoding::decode(&[0xC4, 0xC4, b'\n', b'c', 0xE1, b'r', 0xE9]);
+    /// assert_eq!(("ÄÄ\ncáré", Encoding::Latin1), (text.as_str(), encoding));
+    ///
+    /// assert!(Encoding::Cp437.encode("🐉").is_err());
+    /// ```
+    pub fn decode(bytes: &[u8]) -> (String, Encoding) {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            return (text.to_string(), Encoding::Utf8);
+        }
 
-                return Ok(())
-            },
-            GraphicsFile::StatueCreatureFile(name, _) => {
-                let mut graphics_file_writer = graphics_file_export!("graphics_creatures_", name, "_statue", statues, path);
-                
-                graphics_file_writer.write_all(self.display().as_bytes())?;
-                
-                graphics_file_writer.flush()?;
+        //latin letters, leaving out the greek and math symbols in the top of CP437
+        let is_letter = |c: char| c.is_alphabetic() && (c as u32) < 0x250;
+        let (mut cp437_votes, mut latin1_votes) = (0, 0);
+        for &b in bytes.iter().filter(|&&b| b >= 0x80) {
+            cp437_votes += is_letter(CP437_HIGH[(b - 0x80) as usize]) as usize;
+            latin1_votes += is_letter(b as char) as usize;
+        }
 
-                return Ok(())
-            },
-            GraphicsFile::PlantFile(_name, _) => {
-                // let mut graphics_file_writer = graphics_file_export!("graphics_", name, "", plants, path);
-                
-                // graphics_file_writer.write_all(self.display().as_bytes())?;
-                
-                // graphics_file_writer.flush()?;
+        if cp437_votes > latin1_votes {
+            let text = bytes.iter()
+                .map(|&b| if b < 0x80 { b as char } else { CP437_HIGH[(b - 0x80) as usize] })
+                .collect();
+            (text, Encoding::Cp437)
+        } else {
+            let text = bytes.iter().map(|&b| b as char).collect();
+            (text, Encoding::Latin1)
+        }
+    }
 
-                return Ok(())
-                //todo plant import/export
-            },
-            GraphicsFile::TileGraphicsFile(_name, _) => {
-                // let mut graphics_file_writer = graphics_file_export!("graphics_", name, "", tile_graphics, path);
-                
-                // graphics_file_writer.write_all(self.display().as_bytes())?;
-                
-                // graphics_file_writer.flush()?;
+    /// Encode text to be written back to a raw file.
+    /// 
+    /// Returns the characters that can't be represented if the text doesn't fit the encoding.
+    pub fn encode(&self, text: &str) -> std::result::Result<Vec<u8>, String> {
+        match self {
+            Encoding::Utf8 => Ok(text.as_bytes().to_vec()),
+            Encoding::Cp437 | Encoding::Latin1 => {
+                let mut bytes = Vec::with_capacity(text.len());
+                let mut unencodable = String::new();
+
+                for c in text.chars() {
+                    let byte = match self {
+                        Encoding::Cp437 if !c.is_ascii() => {
+                            CP437_HIGH.iter().position(|&h| h == c).map(|i| i as u8 + 0x80)
+                        },
+                        _ => u8::try_from(c as u32).ok(),
+                    };
+
+                    match byte {
+                        Some(byte) => bytes.push(byte),
+                        None => {
+                            if !unencodable.contains(c) {
+                                unencodable.push(c);
+                            }
+                        },
+                    }
+                }
 
-                return Ok(())
-                //todo tile graphics import/export
+                if unencodable.is_empty() {
+                    Ok(bytes)
+                } else {
+                    Err(unencodable)
+                }
             },
         }
     }
 }
 
-#[derive(Clone, Default, Debug, PartialEq)]
-pub struct Creature {
-    pub name: String,
-    pub caste: Option<Caste>,
-    pub simple_layers: Vec<SimpleLayer>,
-    pub layer_sets: Vec<LayerSet>,
-    pub creature_shared: [CreatureShared; 2],
-    pub active_layers: Option<Vec<(String, [[usize;2];2])>>,
+//characters for bytes 0x80-0xFF in code page 437
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TilePageFile {
+    pub name: String,     //file name of tile_set_file_name.txt
+    pub tile_pages: Vec<TilePage>, //set of tiles defined in this file
+    pub raw: RawFile,     //original text of the imported file
 }
-impl RAW for Creature {
-    fn new() -> Creature {
-        Creature {
+impl RAW for TilePageFile {
+    fn new() -> Self {
+        TilePageFile {
             name: String::from("(new)"),
-            caste: None,
-            simple_layers: Vec::new(),
-            layer_sets: Vec::new(),
-            creature_shared: [CreatureShared::new(), CreatureShared::new()],
-            active_layers: None,
+            tile_pages: vec![TilePage::new()],
+            raw: RawFile::new(),
         }
     }
 
-    fn read(buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, path: &PathBuf) -> (Self, Vec<DFGHError>) {
-        let mut creature = Creature::new();
-        let mut errors: Vec<DFGHError> = Vec::new();
+    fn read(buffer: Vec<Token>, raw_buffer: Vec<String>, path: &Path) -> (Self, Vec<DFGHError>) {
         let mut block_buffer = Vec::with_capacity(100);
-        let buffer_len = buffer.len();
+        let mut block_start = 0;
+        let mut tile_pages = Vec::new();
+        let mut raw = RawFile::new();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        raw.file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
 
-        for (i_rel_line, line_vec) in buffer.iter().enumerate() {
+        //tile page file name must match file name.
+        let name = path
+            .file_name().get_or_insert(&OsStr::new("no_name"))
+            .to_str().get_or_insert("no_name")
+            .replace(".txt", "").trim().to_string();
+        
+        //create vector (buffer) of all lines between relevant headers and import each buffer.
+        for (i_line, line_vec) in buffer.iter().enumerate() {
             let len = line_vec.len();
-
-            if len >= 2 {
+            if len >=1 {
                 match line_vec[0].as_str() {
-                    "CREATURE_GRAPHICS" => {
-                        creature.name = line_vec[1].clone();
-                    },
-                    "CREATURE_CASTE_GRAPHICS" => {
-                        if len >= 3 {
-                            creature.name = line_vec[1].clone();
-                            creature.caste = Some(Caste::from(line_vec[2].clone()));
-                        } else {
-                            index_err!(i_rel_line, buffer_len, len, 3, errors);
-                        }
-                    },
-                    "LAYER_SET" => {
+                    "TILE_PAGE" => {
                         if block_buffer.len() > 0 {
-                            let (ls_temp, temp) = LayerSet::read(block_buffer.clone(), Vec::new(), path);
-                            let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(i_rel_line, buffer_len, 0..=0, e)).collect();
+                            let (tp_temp, temp) = TilePage::read(block_buffer.clone(), Vec::new(), path);
+                            let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), &buffer, e, i_line, path)).collect();
                             errors.append(&mut es_temp);
-                            if ls_temp.state.ne(&State::Empty) {
-                                creature.layer_sets.push(ls_temp);
+                            if tp_temp.ne(&TilePage::new()) {
+                                raw.push_block(&raw_buffer, block_start..line_vec.line, Some(tp_temp.display()));
+                                tile_pages.push(tp_temp);
+                            } else {
+                                raw.push_block(&raw_buffer, block_start..line_vec.line, None);
                             }
                             block_buffer.clear();
+                            block_start = line_vec.line;
                         }
                     },
-                    other => {
-                        match State::from(other.to_string()) {
-                            State::Custom(_) => {},
-                            _ => {
-                                if block_buffer.len() >= 2 {
-                                    let (ls_temp, temp) = LayerSet::read(block_buffer.clone(), Vec::new(), path);
-                                    let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(i_rel_line, buffer_len, 0..=0, e)).collect();
-                                    errors.append(&mut es_temp);
-                                    if ls_temp.state.ne(&State::Empty) {
-                                        creature.layer_sets.push(ls_temp);
-                                    }
-                                    block_buffer.clear();
-                                }
-                                if len >= 4 {
-                                    let (sl_temp, temp) = SimpleLayer::read(vec![line_vec.clone()], Vec::new(), path);
-                                    let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(i_rel_line, buffer_len, 0..=0, e)).collect();
-                                    errors.append(&mut es_temp);
-                                    if sl_temp.ne(&SimpleLayer::new()) {
-                                        creature.simple_layers.push(sl_temp);
-                                    }
-                                } else {
-                                    index_err!(i_rel_line, buffer_len, len, 4, errors);
-                                }
-                            },
-                        }
-                    },
+                    _ => {}
                 }
             }
             block_buffer.push(line_vec.clone());
         }
         let last_line = buffer.len();
-        if block_buffer.len() >= 2 {
-            let (ls_temp, temp) = LayerSet::read(block_buffer.clone(), Vec::new(), path);
-            let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(last_line, buffer_len, 0..=0, e)).collect();
+        if block_buffer.len() > 0 {
+            let (tp_temp, temp) = TilePage::read(block_buffer.clone(), Vec::new(), path);
+            let mut es_temp = temp.iter().map(|e| wrap_import_file_error(raw_buffer.clone(), &buffer, e, last_line, path)).collect();
             errors.append(&mut es_temp);
-            if ls_temp.state.ne(&State::Empty) {
-                creature.layer_sets.push(ls_temp);
+            if tp_temp.ne(&TilePage::new()) {
+                raw.push_block(&raw_buffer, block_start..raw_buffer.len(), Some(tp_temp.display()));
+                tile_pages.push(tp_temp);
+            } else {
+                raw.push_block(&raw_buffer, block_start..raw_buffer.len(), None);
             }
         }
-        (creature, errors)
-    }
 
-    fn display(&self) -> String {
-        let mut out;
-        if let Some(caste) = &self.caste {
-            out = format!("[CREATURE_CASTE_GRAPHICS:{}:{}]\n",
-                self.name
-                .with_boundaries(&[Boundary::Space])
-                .to_case(Case::UpperSnake)
-                .to_string(),
-                caste.name()
-            );
-        } else {
-            out = format!("[CREATURE_GRAPHICS:{}]\n",
-                self.name
-                .with_boundaries(&[Boundary::Space])
-                .to_case(Case::UpperSnake)
-                .to_string()
-            );
-        }
+        let mut tile_page_file = TilePageFile {name, tile_pages, raw};
+        tile_page_file.raw.header.display = tile_page_file.display_header();
 
-        for simple_layer in &self.simple_layers {
-            out.push_str(&simple_layer.display());
-        }
+        (tile_page_file, errors)
+    }
 
-        for layer_set in &self.layer_sets {
-            out.push_str(&layer_set.display());
+    fn display(&self) -> String {
+        let mut out = self.display_header();
+    
+        for tile_page in self.tile_pages.iter() {
+            out.push_str(&tile_page.display());
         }
 
-        out.push_str("\n");
-
         out
     }
 }
-impl Menu for Creature {
-    fn menu(&mut self, ui: &mut Ui, shared: &mut Shared) {
-        let caste_opt: &mut Option<Caste> = &mut self.caste;
-        let simple_layers = &mut self.simple_layers;
-        let layer_sets = &mut self.layer_sets;
+impl TilePageFile {
+    fn display_header(&self) -> String {
+        format!(
+            "tile_page_{}\n\n[OBJECT:TILE_PAGE]\n\n",
+            self.name.clone()
+            .with_boundaries(&[Boundary::Space])
+            .to_case(Case::Snake)
+            .replace("tile_page_", "")
+            .replace(".txt", "")
+        )
+    }
 
-        ui.separator();
-        ui.text_edit_singleline(&mut self.name);
+    /// Correct the size of each tile page from its image when the image can be read.
+    fn read_image_sizes(&mut self, file_system: &dyn FileSystem, path: &Path) {
+        let graphics_dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
 
-        if layer_sets.is_empty() {
-            ui.add_space(PADDING);
+        for tile_page in self.tile_pages.iter_mut() {
+            let image_path = graphics_dir.join(&tile_page.file_name).with_extension("png");
 
-            let mut caste_bool = caste_opt.is_some();
+            if let Ok(image_dimensions) = file_system.image_dimensions(&image_path) {
+                let imported = tile_page.display();
+                tile_page.image_size = [image_dimensions.0, image_dimensions.1];
 
-            ui.checkbox(&mut caste_bool, "Caste:");
+                //not an edit, so the original text is still written back
+                if let Some(raw_block) = self.raw.blocks.iter_mut().find(|b| b.display == imported) {
+                    raw_block.display = tile_page.display();
+                }
+            }
+        }
+    }
 
-            if caste_bool { 
-                let caste = caste_opt.get_or_insert(Caste::Female);
+    /// Text written on export. Unedited tile pages keep their original text.
+    pub fn display_raw(&self) -> String {
+        self.raw.render(
+            self.display_header(),
+            self.tile_pages.iter().map(|tile_page| tile_page.display()).collect()
+        )
+    }
 
-                egui::ComboBox::from_label("Caste?")
-                    .selected_text(caste.name())
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(caste, Caste::Male, "MALE");
-                        ui.selectable_value(caste, Caste::Female, "FEMALE");
-                        for shared_caste in &shared.creature_shared.castes {
-                            ui.selectable_value(caste, shared_caste.clone(), shared_caste.name());
-                        }
-                        ui.selectable_value(caste, Caste::Custom(String::new()), "(custom)");
-                    });
-    
-                if let Caste::Custom(caste_name) = caste {
-                    ui.text_edit_singleline(caste_name);
-                }
-            } else {
-                caste_opt.take();
+    fn export(&self, normalize_name: bool) -> Result<ExportFile> {
+        let tpf_path;
+        if normalize_name || !self.raw.names_file(&self.name) {
+            let mut tpf_name = format!("{}.txt", self.name.clone())
+                .with_boundaries(&[Boundary::Space])
+                .to_case(Case::Snake);
+
+            if !tpf_name.starts_with("tile_page_") {
+                tpf_name = format!("tile_page_{}", tpf_name);
             }
+
+            tpf_path = PathBuf::from("graphics").join(tpf_name);
+        } else {
+            tpf_path = PathBuf::from("graphics").join(&self.raw.file_name);
         }
+        let bytes = self.raw.encode(&self.display_raw(), &tpf_path)?;
 
-        ui.add_space(PADDING);
+        Ok(ExportFile { path: tpf_path, bytes })
+    }
+}
 
-        if ui.button("Add Simple Layer").clicked() {
-            simple_layers.push(SimpleLayer::default());
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TilePage {
+    pub name: String,
+    pub file_name: PathBuf,
+    pub image_size: [u32; 2],
+    pub tile_size: [u32; 2],
+}
+impl RAW for TilePage {
+    fn new() -> Self {
+        TilePage {
+            name: "(new)".to_string(),
+            file_name: PathBuf::new(),
+            image_size: [0, 0],
+            tile_size: [32, 32],
         }
-        
-        egui::ScrollArea::both()
-            .id_source("layer scroll")
-            .auto_shrink([false, true])
-            .stick_to_right(true)
-            .show(ui, |ui| {
+    }
 
-            let mut delete_sl = None;
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut tile_page = TilePage::new();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        let buffer_len = buffer.len();
 
-            for (i_sl, simple_layer) in simple_layers.iter_mut().enumerate() {
-                ui.push_id(format!("simple_layer{}",i_sl), |ui| {
-                    ui.group(|ui| {
-                        simple_layer.menu(ui, shared);
-                        if ui.button("Remove Simple Layer").clicked() {
-                            delete_sl = Some(i_sl);
+        for (i_line, line_vec) in buffer.iter().enumerate() {
+            let len = line_vec.len();
+            if len >= 2 {
+                match line_vec[0].as_str() {
+                    "TILE_PAGE" => {
+                        tile_page.name = line_vec[1].clone();
+                    },
+                    "FILE" => {
+                        tile_page.file_name = line_vec[1].clone().split(&['\\','/']).collect::<PathBuf>();
+                    },
+                    "TILE_DIM" => {
+                        if len >= 3 {
+                            tile_page.tile_size =
+                                [buffer_err_wrap!(line_vec[1].parse(), i_line, buffer_len, 1..=1, 0, errors),
+                                buffer_err_wrap!(line_vec[2].parse(), i_line, buffer_len, 2..=2, 0, errors)];
+                        } else {
+                            index_err!(i_line, buffer_len, len, 3, errors);
                         }
-                    });
-                    
-                    ui.add_space(PADDING);
-                });
+                    },
+                    "PAGE_DIM_PIXELS" => {
+                        //corrected from the image itself by TilePageFile::read_image_sizes when it can be found.
+                        if len >= 3 {
+                            tile_page.image_size =
+                                [buffer_err_wrap!(line_vec[1].parse(), i_line, buffer_len, 1..=1, 0, errors),
+                                buffer_err_wrap!(line_vec[2].parse(), i_line, buffer_len, 2..=2, 0, errors)];
+                        } else {
+                            index_err!(i_line, buffer_len, len, 3, errors);
+                        }
+                    },
+                    "OBJECT"
+                    | "" => {}//do nothing for expected useless lines
+                    _ => {
+                        errors.push(DFGHError::ImportBufferError(i_line, buffer_len, 0..=(len-1), DFGHError::ImportUnknownError.to_string()));
+                    },
+                }
             }
+        }
+        (tile_page, errors)
+    }
 
-            ui.add_space(PADDING);
-
-            if ui.button("Add Layer Set").clicked() {
-                layer_sets.push(LayerSet::default());
-            }
+    fn display(&self) -> String {
+        format!(
+            "[TILE_PAGE:{}]\n\t[FILE:{}]\n\t[TILE_DIM:{}:{}]\n\t[PAGE_DIM_PIXELS:{}:{}]\n\n",
+            self.name.with_boundaries(&[Boundary::Space]).to_case(Case::UpperSnake),
+            self.file_name.as_os_str().to_string_lossy().replace("\\", "/"),
+            self.tile_size[0],
+            self.tile_size[1],
+            self.image_size[0],
+            self.image_size[1]
+        )
+    }
+}
+impl Menu for TilePage {
+    fn menu(&mut self, ui: &mut Ui, _shared: &mut Shared) {
+        ui.separator();
+        ui.label("TilePage token");
+        ui.text_edit_singleline(&mut self.name);
+        ui.add_space(PADDING);
 
-            let mut delete_ls = None;
+        ui.label("Image file path:");
+        ui.horizontal(|ui| {
+            ui.label("graphics/");
+
+            let mut file_name = self.file_name.clone().as_mut_os_string().to_string_lossy().replace("\\", "/");
+            ui.text_edit_singleline(&mut file_name);
+
+            self.file_name.clear();
+            self.file_name.push(file_name.split(&['\\','/']).collect::<PathBuf>());
+
+            if ui.button("⏷").clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .set_title(&self.name)
+                    .add_filter("png", &["png"])
+                    .pick_file() {
+                    if path.file_name().is_some() {
+                        let mut internal_path = path.components()
+                            .rev()
+                            .map(|c| c.as_os_str())
+                            .take_while(|c| !c.eq_ignore_ascii_case("graphics"))
+                            .collect::<Vec<_>>();
+                        internal_path.reverse();
+                        self.file_name = internal_path.iter()
+                            .map(|&p| p.to_string_lossy().to_string())
+                            .collect::<PathBuf>();
+                    }
+                }
+            }
+        });
+        ui.add_space(PADDING);
+
+        ui.label("Image size (pixels):");
+        ui.horizontal(|ui| {
+            ui.label(format!("Width: {}", self.image_size[0]));
+            ui.label(format!("Height: {}", self.image_size[1]));
+        });
+        ui.add_space(PADDING);
+
+        ui.label("TilePage size (pixels):");
+        ui.horizontal(|ui| {
+            ui.add(egui::Slider::new(&mut self.tile_size[0], 0..=64).prefix("Width: "));
+            ui.add(egui::Slider::new(&mut self.tile_size[1], 0..=96).prefix("Height: "));
+        });
+
+        ui.add_space(PADDING);
+        ui.label("Preview:");
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            ui.add(egui::Label::new(self.display()).wrap(false));
+        });
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum GraphicsFile {
+    #[default]
+    DefaultFile,
+    CreatureFile(String, Vec<Creature>, RawFile),
+    StatueCreatureFile(String, Vec<Statue>, RawFile),
+    PlantFile(String, Vec<Plant>, RawFile),
+    TileGraphicsFile(String, Vec<TileGraphic>, RawFile),
+    OtherFile(String, Vec<UnknownBlock>, RawFile),
+}
+impl RAW for GraphicsFile {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(buffer: Vec<Token>, raw_buffer: Vec<String>, path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut block_buffer = Vec::with_capacity(100);
+        let mut block_start = 0;
+        let mut unsupported = false; //whether the buffered block is a passthrough block
+        let mut first_header = String::new();
+        let mut graphics_file = GraphicsFile::default();
+        let mut raw = RawFile::new();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        raw.file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+        //name must match file name.
+        let file_name = path
+            .file_name().get_or_insert(&OsStr::new("no_name"))
+            .to_str().get_or_insert("no_name")
+            .replace(".txt", "").trim().to_string();
+        
+        //create vector (buffer) of all lines between block headers and import each buffer.
+        for (i_line, line_vec) in buffer.iter().enumerate() {
+            let len = line_vec.len();
+
+            if let GraphicsFile::DefaultFile = graphics_file {//identify graphics file type
+                if len >=2 {
+                    match line_vec[0].as_str() {
+                        "CREATURE_CASTE_GRAPHICS" |
+                        "CREATURE_GRAPHICS" => {
+                            graphics_file = GraphicsFile::CreatureFile(file_name.clone(), Vec::new(), RawFile::new());
+                        },
+                        "STATUE_CREATURE_CASTE_GRAPHICS" |
+                        "STATUE_CREATURE_GRAPHICS" => {
+                            graphics_file = GraphicsFile::StatueCreatureFile(file_name.clone(), Vec::new(), RawFile::new());
+                        },
+                        "PLANT_GRAPHICS" => {
+                            graphics_file = GraphicsFile::PlantFile(file_name.clone(), Vec::new(), RawFile::new());
+                        },
+                        "TILE_GRAPHICS" |
+                        "HELM_GRAPHICS" |
+                        "ARMOR_GRAPHICS" |
+                        "PANTS_GRAPHICS" |
+                        "GLOVES_GRAPHICS" |
+                        "SHOES_GRAPHICS" |
+                        "AMMO_GRAPHICS" |
+                        "SIEGEAMMO_GRAPHICS" |
+                        "WEAPON_GRAPHICS" |
+                        "SHIELD_GRAPHICS" |
+                        "TRAPCOMP_GRAPHICS" |
+                        "BOULDER_GRAPHICS" |
+                        "ROUGH_GEM_GRAPHICS" |
+                        "BARS_GRAPHICS" |
+                        "FOOD_GRAPHICS" |
+                        "TOY_GRAPHICS" |
+                        "TOOL_GRAPHICS" |
+                        "ADD_TOOL_GRAPHICS" |
+                        "FOOD_CONTAINER_GRAPHICS" => {
+                            graphics_file = GraphicsFile::TileGraphicsFile(file_name.clone(), Vec::new(), RawFile::new());
+                        },
+                        "OBJECT" => {continue},
+                        _ => {
+                            //a block type this tool doesn't model, kept so it can be written back
+                            graphics_file = GraphicsFile::OtherFile(file_name.clone(), Vec::new(), RawFile::new());
+                            first_header = line_vec[0].clone();
+                        },
+                    }
+                    //if this doesn't get skipped then we have a graphics file type and need to start buffering.
+                    raw.push_block(&raw_buffer, 0..line_vec.line, None);
+                    block_start = line_vec.line;
+                    block_buffer.push(line_vec.clone());
+                }
+                continue;
+            }
+
+            if len >=1 {
+                let header = line_vec[0].as_str();
+                let top_level = block_buffer.first().map_or(true, |first: &Token| !graphics_file.block_content(&first[0], line_vec));
+                if graphics_file.models_block(header) || header == first_header || top_level {
+                    if block_buffer.len() > 0 {
+                        let mut es_temp = graphics_file.read_block(&mut raw, &block_buffer, &raw_buffer, block_start..line_vec.line, unsupported, path)
+                            .iter().map(|e| wrap_import_file_error(raw_buffer.clone(), &buffer, e, i_line, path)).collect();
+                        errors.append(&mut es_temp);
+                        block_buffer.clear();
+                        block_start = line_vec.line;
+                    }
+                    unsupported = !graphics_file.models_block(header) && !matches!(graphics_file, GraphicsFile::OtherFile(..));
+                }
+            }
+            block_buffer.push(line_vec.clone());
+        }
+
+        let last_line = buffer.len();
+        if block_buffer.len() > 0 {//flush buffer
+            let mut es_temp = graphics_file.read_block(&mut raw, &block_buffer, &raw_buffer, block_start..raw_buffer.len(), unsupported, path)
+                .iter().map(|e| wrap_import_file_error(raw_buffer.clone(), &buffer, e, last_line, path)).collect();
+            errors.append(&mut es_temp);
+        }
+        raw.header.display = graphics_file.display_header();
+        match graphics_file {
+            GraphicsFile::DefaultFile => {},
+            GraphicsFile::CreatureFile(_, _, ref mut gf_raw) |
+            GraphicsFile::StatueCreatureFile(_, _, ref mut gf_raw) |
+            GraphicsFile::PlantFile(_, _, ref mut gf_raw) |
+            GraphicsFile::TileGraphicsFile(_, _, ref mut gf_raw) |
+            GraphicsFile::OtherFile(_, _, ref mut gf_raw) => *gf_raw = raw,
+        }
+
+        (graphics_file, errors)
+    }
+
+    fn display(&self) -> String {
+        let mut out = self.display_header();
+
+        for block in self.display_blocks() {
+            out.push_str(&block);
+        }
+
+        out
+    }
+}
+impl GraphicsFile {
+    fn display_header(&self) -> String {
+        match self {
+            GraphicsFile::DefaultFile => "".to_string(),
+            GraphicsFile::CreatureFile(file_name, ..) => {
+                format!("graphics_creatures_{}\n\n[OBJECT:GRAPHICS]\n\n",
+                    file_name
+                    .with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
+                    .to_case(Case::Snake)
+                    .replace("graphics_", "")
+                    .replace("creatures_", "")
+                    .replace(".txt", "")
+                )
+            },
+            GraphicsFile::StatueCreatureFile(file_name, ..) => {
+                format!("graphics_creatures_{}_statue\n\n[OBJECT:GRAPHICS]\n\n",
+                    file_name
+                    .with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
+                    .to_case(Case::Snake)
+                    .replace("graphics_", "")
+                    .replace("creatures_", "")
+                    .replace("_statue", "")
+                    .replace(".txt", "")
+                )
+            },
+            GraphicsFile::PlantFile(file_name, ..) |
+            GraphicsFile::TileGraphicsFile(file_name, ..) |
+            GraphicsFile::OtherFile(file_name, ..) => {
+                format!("graphics_{}\n\n[OBJECT:GRAPHICS]\n\n",
+                    file_name
+                    .with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
+                    .to_case(Case::Snake)
+                    .replace("graphics_", "")
+                    .replace(".txt", "")
+                )
+            },
+        }
+    }
+
+    fn display_blocks(&self) -> Vec<String> {
+        match self {
+            GraphicsFile::DefaultFile => Vec::new(),
+            GraphicsFile::CreatureFile(_, creatures, _) => creatures.iter().map(|c| c.display()).collect(),
+            GraphicsFile::StatueCreatureFile(_, statues, _) => statues.iter().map(|s| s.display()).collect(),
+            GraphicsFile::PlantFile(_, plants, _) => plants.iter().map(|p| p.display()).collect(),
+            GraphicsFile::TileGraphicsFile(_, tile_graphics, _) => tile_graphics.iter().map(|tg| tg.display()).collect(),
+            GraphicsFile::OtherFile(_, blocks, _) => blocks.iter().map(|b| b.display()).collect(),
+        }
+    }
+
+    /// Whether `header` starts a block this type of file reads into its own model.
+    fn models_block(&self, header: &str) -> bool {
+        match self {
+            GraphicsFile::DefaultFile |
+            GraphicsFile::OtherFile(..) => false,
+            GraphicsFile::CreatureFile(..) => matches!(header, "CREATURE_GRAPHICS" | "CREATURE_CASTE_GRAPHICS"),
+            GraphicsFile::StatueCreatureFile(..) => matches!(header, "STATUE_CREATURE_GRAPHICS" | "STATUE_CREATURE_CASTE_GRAPHICS"),
+            GraphicsFile::PlantFile(..) => header == "PLANT_GRAPHICS",
+            GraphicsFile::TileGraphicsFile(..) => ItemGraphicsKind::from(header.to_string()).is_some(),
+        }
+    }
+
+    /// Whether a token belongs inside a block this type of file reads, given the block's header. Top level
+    /// tokens the block doesn't know, such as TILE_GRAPHICS_RECTANGLE, start a new block that is kept as it is.
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/graphics/graphics_items.txt",
+    ///     "graphics_items\n\n[OBJECT:GRAPHICS]\n\n[TOOL_GRAPHICS:ITEM_TOOL_NEST_BOX]\n\t[DEFAULT:ITEMS:0:0]\n\t[CUSTOM_STATE:ITEMS:1:0]\n\n\
+    ///     [TILE_GRAPHICS_RECTANGLE:ITEMS:0:1:2:2:BOX]\n"
+    /// );
+    /// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+    /// let graphics_file = &graphics.graphics_files[0];
+    ///
+    /// assert_eq!(1, graphics_file.unsupported_blocks().len());
+    /// if let GraphicsFile::TileGraphicsFile(_, tile_graphics, _) = graphics_file {
+    ///     assert_eq!(2, tile_graphics[0].tiles.len());
+    /// }
+    /// ```
+    pub fn block_content(&self, header: &str, token: &Token) -> bool {
+        let name = token[0].as_str();
+
+        //top level graphics tokens are all named for what they draw, some item tile states are too
+        !name.contains("GRAPHICS") || match self {
+            GraphicsFile::TileGraphicsFile(..) => ItemGraphicsKind::from(header.to_string())
+                .is_some_and(|kind| kind.states().contains(&name)),
+            _ => false,
+        }
+    }
+
+    //import one buffered block into the file, or keep it as a passthrough block
+    fn read_block(&mut self, raw: &mut RawFile, block_buffer: &[Token], raw_buffer: &[String], lines: std::ops::Range<usize>,
+        unsupported: bool, path: &Path) -> Vec<DFGHError> {
+        if unsupported {
+            let (block, _) = UnknownBlock::read(block_buffer.to_vec(), Vec::new(), path);
+            raw.push_passthrough(raw_buffer, lines, block);
+            return Vec::new();
+        }
+
+        let (display, errors) = match self {
+            GraphicsFile::DefaultFile => (None, Vec::new()),
+            GraphicsFile::CreatureFile(_, creatures, ..) => {
+                let (c_temp, temp) = Creature::read(block_buffer.to_vec(), Vec::new(), path);
+                if c_temp.ne(&Creature::new()) {
+                    let display = c_temp.display();
+                    creatures.push(c_temp);
+                    (Some(display), temp)
+                } else {
+                    (None, temp)
+                }
+            },
+            GraphicsFile::StatueCreatureFile(_, statues, ..) => {
+                let (s_temp, temp) = Statue::read(block_buffer.to_vec(), Vec::new(), path);
+                if s_temp.ne(&Statue::new()) {
+                    let display = s_temp.display();
+                    statues.push(s_temp);
+                    (Some(display), temp)
+                } else {
+                    (None, temp)
+                }
+            },
+            GraphicsFile::PlantFile(_, plants, ..) => {
+                let (p_temp, temp) = Plant::read(block_buffer.to_vec(), Vec::new(), path);
+                if p_temp.ne(&Plant::new()) {
+                    let display = p_temp.display();
+                    plants.push(p_temp);
+                    (Some(display), temp)
+                } else {
+                    (None, temp)
+                }
+            },
+            GraphicsFile::TileGraphicsFile(_, tile_graphics, ..) => {
+                let (tg_temp, temp) = TileGraphic::read(block_buffer.to_vec(), Vec::new(), path);
+                if tg_temp.ne(&TileGraphic::new()) {
+                    let display = tg_temp.display();
+                    tile_graphics.push(tg_temp);
+                    (Some(display), temp)
+                } else {
+                    (None, temp)
+                }
+            },
+            GraphicsFile::OtherFile(_, blocks, ..) => {
+                let (b_temp, temp) = UnknownBlock::read(block_buffer.to_vec(), Vec::new(), path);
+                let display = b_temp.display();
+                blocks.push(b_temp);
+                (Some(display), temp)
+            },
+        };
+        raw.push_block(raw_buffer, lines, display);
+
+        errors
+    }
+
+    /// Blocks of a type this tool doesn't model, in file order. They are written back unchanged on export.
+    pub fn unsupported_blocks(&self) -> Vec<&UnknownBlock> {
+        match self {
+            GraphicsFile::OtherFile(_, blocks, _) => blocks.iter().collect(),
+            _ => self.raw()
+                .map(|raw| raw.blocks.iter().filter_map(|b| b.passthrough.as_ref()).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    pub fn raw(&self) -> Option<&RawFile> {
+        match self {
+            GraphicsFile::DefaultFile => None,
+            GraphicsFile::CreatureFile(_, _, raw) |
+            GraphicsFile::StatueCreatureFile(_, _, raw) |
+            GraphicsFile::PlantFile(_, _, raw) |
+            GraphicsFile::TileGraphicsFile(_, _, raw) |
+            GraphicsFile::OtherFile(_, _, raw) => Some(raw),
+        }
+    }
+
+    pub fn raw_mut(&mut self) -> Option<&mut RawFile> {
+        match self {
+            GraphicsFile::DefaultFile => None,
+            GraphicsFile::CreatureFile(_, _, raw) |
+            GraphicsFile::StatueCreatureFile(_, _, raw) |
+            GraphicsFile::PlantFile(_, _, raw) |
+            GraphicsFile::TileGraphicsFile(_, _, raw) |
+            GraphicsFile::OtherFile(_, _, raw) => Some(raw),
+        }
+    }
+
+    /// Load the image of each palette when it can be read, which also sets the number of rows.
+    fn read_palette_images(&mut self, file_system: &dyn FileSystem, path: &Path) {
+        let graphics_dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+
+        if let GraphicsFile::CreatureFile(_, creatures, _) = self {
+            for palette in creatures.iter_mut().flat_map(|c| c.layer_sets.iter_mut()).flat_map(|ls| ls.palettes.iter_mut()) {
+                let _ = palette.load_image(file_system, &graphics_dir);
+            }
+        }
+    }
+
+    //every tile assignment in the file, with where it is
+    fn tiles(&self) -> Vec<TileRef<'_>> {
+        let mut tiles = Vec::new();
+
+        match self {
+            GraphicsFile::CreatureFile(_, creatures, _) => {
+                for creature in creatures {
+                    for simple_layer in &creature.simple_layers {
+                        let location = format!("{}, {}", creature.name, simple_layer.state.name());
+                        tiles.push((location, &simple_layer.tile_name, simple_layer.coords, simple_layer.large_coords));
+                    }
+                    for layer_set in &creature.layer_sets {
+                        for layer in layer_set.layer_groups.iter().flat_map(|lg| lg.layers.iter()) {
+                            let location = format!("{}, layer set {}, layer {}", creature.name, layer_set.state.name(), layer.name);
+                            for condition in &layer.conditions {
+                                if let Condition::TissueSwap(_, _, tile_name, coords, large_coords) = condition {
+                                    tiles.push((format!("{} TISSUE_SWAP", location), tile_name, *coords, *large_coords));
+                                }
+                            }
+                            tiles.push((location, &layer.tile_name, layer.coords, layer.large_coords));
+                        }
+                    }
+                }
+            },
+            GraphicsFile::StatueCreatureFile(_, statues, _) => {
+                for statue in statues {
+                    for tile in &statue.tiles {
+                        let location = format!("Statue {}, {}", statue.creature_name, tile.state.name());
+                        tiles.push((location, &tile.tile_name, tile.coords, tile.large_coords));
+                    }
+                }
+            },
+            GraphicsFile::PlantFile(_, plants, _) => {
+                for plant in plants {
+                    for tile in &plant.tiles {
+                        tiles.push((format!("{}, {}", plant.name, tile.stage.name()), &tile.tile_name, tile.coords, None));
+                    }
+                }
+            },
+            GraphicsFile::TileGraphicsFile(_, tile_graphics, _) => {
+                for tile_graphic in tile_graphics {
+                    for tile in &tile_graphic.tiles {
+                        tiles.push((format!("{}, {}", tile_graphic.name, tile.state), &tile.tile_name, tile.coords, tile.large_coords));
+                    }
+                }
+            },
+            _ => {},
+        }
+
+        tiles
+    }
+
+    //the same tile assignments as tiles(), in the same order, to edit them
+    fn tiles_mut(&mut self) -> Vec<TileMut<'_>> {
+        let mut tiles = Vec::new();
+
+        match self {
+            GraphicsFile::CreatureFile(_, creatures, _) => {
+                for creature in creatures {
+                    for simple_layer in creature.simple_layers.iter_mut() {
+                        tiles.push((&simple_layer.tile_name, &mut simple_layer.coords, Some(&mut simple_layer.large_coords)));
+                    }
+                    for layer in creature.layer_sets.iter_mut().flat_map(|ls| ls.layer_groups.iter_mut()).flat_map(|lg| lg.layers.iter_mut()) {
+                        for condition in layer.conditions.iter_mut() {
+                            if let Condition::TissueSwap(_, _, tile_name, coords, large_coords) = condition {
+                                tiles.push((&*tile_name, coords, Some(large_coords)));
+                            }
+                        }
+                        tiles.push((&layer.tile_name, &mut layer.coords, Some(&mut layer.large_coords)));
+                    }
+                }
+            },
+            GraphicsFile::StatueCreatureFile(_, statues, _) => {
+                for tile in statues.iter_mut().flat_map(|s| s.tiles.iter_mut()) {
+                    tiles.push((&tile.tile_name, &mut tile.coords, Some(&mut tile.large_coords)));
+                }
+            },
+            GraphicsFile::PlantFile(_, plants, _) => {
+                for tile in plants.iter_mut().flat_map(|p| p.tiles.iter_mut()) {
+                    tiles.push((&tile.tile_name, &mut tile.coords, None));
+                }
+            },
+            GraphicsFile::TileGraphicsFile(_, tile_graphics, _) => {
+                for tile in tile_graphics.iter_mut().flat_map(|t| t.tiles.iter_mut()) {
+                    tiles.push((&tile.tile_name, &mut tile.coords, Some(&mut tile.large_coords)));
+                }
+            },
+            _ => {},
+        }
+
+        tiles
+    }
+
+    /// Tiles and LARGE_IMAGE rectangles reaching past the grid of their tile page, with the index
+    /// of the tile in the file so it can be clamped with [`GraphicsFile::clamp_tile`].
+    pub fn tile_bounds_errors(&self, shared: &Shared) -> Vec<(usize, DFGHError)> {
+        self.tiles()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i_tile, (location, tile_name, coords, large_coords))| {
+                let tp_info = shared.checked_tile_page(tile_name)?;
+                let [columns, rows] = tp_info.grid()?;
+                if tp_info.fits(coords, large_coords) {
+                    return None;
+                }
+                let tiles = match large_coords {
+                    Some([x2, y2]) => format!("{}:{} to {}:{}", coords[0], coords[1], coords[0].saturating_add(x2), coords[1].saturating_add(y2)),
+                    None => format!("{}:{}", coords[0], coords[1]),
+                };
+                Some((i_tile, DFGHError::TileOutOfBounds(location, tiles, tile_name.clone(), columns.saturating_sub(1), rows.saturating_sub(1))))
+            })
+            .collect()
+    }
+
+    /// Where a tile is and where clamping it would move it.
+    pub fn clamped_tile(&self, i_tile: usize, shared: &Shared) -> Option<(TilePosition, TilePosition)> {
+        let (_, tile_name, coords, large_coords) = self.tiles().into_iter().nth(i_tile)?;
+        let tp_info = shared.checked_tile_page(tile_name)?;
+        let (mut clamped, mut clamped_large) = (coords, large_coords);
+        tp_info.clamp(&mut clamped, &mut clamped_large);
+
+        Some(((coords, large_coords), (clamped, clamped_large)))
+    }
+
+    /// Move one tile that reaches past its tile page back onto the page, leaving every other tile as it is.
+    pub fn clamp_tile(&mut self, i_tile: usize, shared: &Shared) {
+        if let Some((tile_name, coords, large_coords)) = self.tiles_mut().into_iter().nth(i_tile) {
+            if let Some(tp_info) = shared.checked_tile_page(tile_name) {
+                tp_info.clamp(coords, large_coords.unwrap_or(&mut None));
+            }
+        }
+    }
+
+    /// USE_PALETTE conditions that name an undeclared palette or a row it doesn't have.
+    pub fn palette_errors(&self) -> Vec<DFGHError> {
+        match self {
+            GraphicsFile::CreatureFile(_, creatures, _) => creatures
+                .iter()
+                .flat_map(|c| c.layer_sets.iter().flat_map(|ls| ls.palette_errors(&c.name)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    //every layer condition of the creatures in the file, with where it is and the creature's name
+    fn layer_conditions(&self) -> Vec<(String, &str, &Condition)> {
+        let mut conditions = Vec::new();
+
+        if let GraphicsFile::CreatureFile(_, creatures, _) = self {
+            for creature in creatures {
+                for layer_set in &creature.layer_sets {
+                    for layer in layer_set.layer_groups.iter().flat_map(|lg| lg.layers.iter()) {
+                        let location = format!("{}, layer set {}, layer {}", creature.name, layer_set.state.name(), layer.name);
+                        conditions.extend(layer.conditions.iter().map(|c| (location.clone(), creature.name.as_str(), c)));
+                    }
+                }
+            }
+        }
+
+        conditions
+    }
+
+    /// Item conditions that name items missing from the catalog.
+    pub fn item_errors(&self, catalog: &RawCatalog) -> Vec<DFGHError> {
+        self.layer_conditions()
+            .into_iter()
+            .flat_map(|(location, _, condition)| condition
+                .unknown_items(catalog)
+                .into_iter()
+                .map(move |item| DFGHError::UnknownItem(location.clone(), item)))
+            .collect()
+    }
+
+    /// Syndrome classes and materials of conditions that aren't defined in the raws.
+    pub fn material_errors(&self, catalog: &RawCatalog) -> Vec<DFGHError> {
+        self.layer_conditions()
+            .into_iter()
+            .flat_map(|(location, _, condition)| condition.unknown_materials(&location, catalog))
+            .collect()
+    }
+
+    /// Colors and shapings of conditions that aren't defined in the raws.
+    pub fn descriptor_errors(&self, catalog: &RawCatalog) -> Vec<DFGHError> {
+        self.layer_conditions()
+            .into_iter()
+            .flat_map(|(location, _, condition)| condition
+                .unknown_descriptors(catalog)
+                .into_iter()
+                .map(move |descriptor| DFGHError::UnknownDescriptor(location.clone(), descriptor)))
+            .collect()
+    }
+
+    /// Appearance modifier ranges of layers that the creature's modifiers can't reach.
+    ///
+    /// A range is checked against the body part picked by the last CONDITION_BP before it, or
+    /// the layer group's LG_CONDITION_BP.
+    pub fn appearance_errors(&self, catalog: &RawCatalog) -> Vec<DFGHError> {
+        let mut errors = Vec::new();
+
+        if let GraphicsFile::CreatureFile(_, creatures, _) = self {
+            //creatures whose modifiers are only partly known would get false reports
+            for creature in creatures.iter().filter(|c| catalog.appearance_known(&c.name)) {
+                for layer_set in &creature.layer_sets {
+                    for layer_group in &layer_set.layer_groups {
+                        let lg_selection = layer_group.layers
+                            .iter()
+                            .flat_map(|l| l.conditions.iter())
+                            .find_map(|c| match c {
+                                Condition::LGConditionBP(bp_type) => bp_type.selection(),
+                                _ => None,
+                            });
+
+                        for layer in &layer_group.layers {
+                            let location = format!("{}, layer set {}, layer {}", creature.name, layer_set.state.name(), layer.name);
+                            let mut selection = lg_selection;
+                            for condition in &layer.conditions {
+                                match condition {
+                                    Condition::ConditionBP(bp_type) => selection = bp_type.selection().or(selection),
+                                    Condition::BPAppearanceModifierRange(bp_app_mod, min, max) if *bp_app_mod != BPAppMod::None => {
+                                        let part = selection.map(|(s, n)| (s, n.as_str()));
+                                        match catalog.appearance_range(&creature.name, &bp_app_mod.name(), part) {
+                                            None => errors.push(DFGHError::MissingAppearanceModifier(
+                                                location.clone(),
+                                                bp_app_mod.name(),
+                                                part.map_or("any body part".to_string(), |(s, n)| format!("{}:{}", s, n)),
+                                                creature.name.clone(),
+                                            )),
+                                            Some([low, high]) if min > max || *max < low || *min > high => {
+                                                errors.push(DFGHError::ImpossibleAppearanceRange(
+                                                    location.clone(), bp_app_mod.name(), *min, *max, low, high
+                                                ));
+                                            },
+                                            Some(_) => {},
+                                        }
+                                    },
+                                    _ => {},
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// Body parts and tissues selected by conditions that the creature doesn't have.
+    pub fn body_errors(&self, catalog: &RawCatalog) -> Vec<DFGHError> {
+        self.layer_conditions()
+            .into_iter()
+            .flat_map(|(location, creature_name, condition)| condition
+                .unknown_body_references(creature_name, catalog)
+                .into_iter()
+                .map(move |reference| DFGHError::UnknownBodyReference(location.clone(), reference, creature_name.to_string())))
+            .collect()
+    }
+
+    /// Creatures and castes missing from the catalog.
+    pub fn creature_errors(&self, catalog: &RawCatalog) -> Vec<DFGHError> {
+        let mut errors = Vec::new();
+
+        let mut check = |location: String, creature_name: &str, caste: Option<&Caste>| {
+            match catalog.creature(creature_name) {
+                None => errors.push(DFGHError::UnknownCreature(location, creature_name.to_string())),
+                Some(creature) => {
+                    if let Some(caste) = caste.filter(|c| !creature.has_caste(&c.name())) {
+                        errors.push(DFGHError::UnknownCaste(location, creature.name.clone(), caste.name()));
+                    }
+                },
+            }
+        };
+
+        match self {
+            GraphicsFile::CreatureFile(_, creatures, _) => {
+                for creature in creatures {
+                    check(creature.name.clone(), &creature.name, creature.caste.as_ref());
+                }
+                for (location, creature_name, condition) in self.layer_conditions() {
+                    if let Condition::Caste(caste) = condition {
+                        check(location, creature_name, Some(caste));
+                    }
+                }
+            },
+            GraphicsFile::StatueCreatureFile(_, statues, _) => {
+                for statue in statues {
+                    check(format!("Statue {}", statue.creature_name), &statue.creature_name, statue.caste.as_ref());
+                }
+            },
+            _ => {},
+        }
+
+        errors
+    }
+
+    /// Text written on export. Unedited blocks keep their original text, comments and formatting.
+    pub fn display_raw(&self) -> String {
+        match self.raw() {
+            Some(raw) => raw.render(self.display_header(), self.display_blocks()),
+            None => "".to_string(),
+        }
+    }
+
+    fn encoded_raw(&self) -> Result<Vec<u8>> {
+        match self.raw() {
+            Some(raw) => raw.encode(&self.display_raw(), &PathBuf::from(self.name())),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            GraphicsFile::DefaultFile => "(new)".to_string(),
+            GraphicsFile::CreatureFile(name, ..) => name.clone().replace("graphics_", ""),
+            GraphicsFile::StatueCreatureFile(name, ..) => name.clone().replace("graphics_", ""),
+            GraphicsFile::PlantFile(name, ..) => name.clone().replace("graphics_", ""),
+            GraphicsFile::TileGraphicsFile(name, ..) => name.clone().replace("graphics_", ""),
+            GraphicsFile::OtherFile(name, ..) => name.clone().replace("graphics_", ""),
+        }
+    }
+
+    fn export(&self, normalize_name: bool) -> Result<Option<ExportFile>> {
+        match self {
+            GraphicsFile::DefaultFile => Ok(None),
+            GraphicsFile::CreatureFile(name, _, raw) => {
+                let bytes = self.encoded_raw()?;
+                let path = if !normalize_name && raw.names_file(name) {
+                    PathBuf::from("graphics").join(&raw.file_name)
+                } else {
+                    graphics_file_path!("graphics_creatures_", name, "")
+                };
+
+                Ok(Some(ExportFile { path, bytes }))
+            },
+            GraphicsFile::StatueCreatureFile(name, _, raw) => {
+                let bytes = self.encoded_raw()?;
+                let path = if !normalize_name && raw.names_file(name) {
+                    PathBuf::from("graphics").join(&raw.file_name)
+                } else {
+                    graphics_file_path!("graphics_creatures_", name, "_statue")
+                };
+
+                Ok(Some(ExportFile { path, bytes }))
+            },
+            GraphicsFile::PlantFile(name, _, raw) => {
+                let bytes = self.encoded_raw()?;
+                let path = if !normalize_name && raw.names_file(name) {
+                    PathBuf::from("graphics").join(&raw.file_name)
+                } else {
+                    graphics_file_path!("graphics_", name, "")
+                };
+
+                Ok(Some(ExportFile { path, bytes }))
+            },
+            GraphicsFile::TileGraphicsFile(name, _, raw) |
+            GraphicsFile::OtherFile(name, _, raw) => {
+                let bytes = self.encoded_raw()?;
+                let path = if !normalize_name && raw.names_file(name) {
+                    PathBuf::from("graphics").join(&raw.file_name)
+                } else {
+                    graphics_file_path!("graphics_", name, "")
+                };
+
+                Ok(Some(ExportFile { path, bytes }))
+            },
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Creature {
+    pub name: String,
+    pub caste: Option<Caste>,
+    pub simple_layers: Vec<SimpleLayer>,
+    pub layer_sets: Vec<LayerSet>,
+    pub creature_shared: [CreatureShared; 2],
+    pub active_layers: Option<Vec<(String, [[usize;2];2])>>,
+}
+impl RAW for Creature {
+    fn new() -> Creature {
+        Creature {
+            name: String::from("(new)"),
+            caste: None,
+            simple_layers: Vec::new(),
+            layer_sets: Vec::new(),
+            creature_shared: [CreatureShared::new(), CreatureShared::new()],
+            active_layers: None,
+        }
+    }
+
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut creature = Creature::new();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        let mut block_buffer = Vec::with_capacity(100);
+        let buffer_len = buffer.len();
+
+        for (i_rel_line, line_vec) in buffer.iter().enumerate() {
+            let len = line_vec.len();
+
+            if len >= 2 {
+                match line_vec[0].as_str() {
+                    "CREATURE_GRAPHICS" => {
+                        creature.name = line_vec[1].clone();
+                    },
+                    "CREATURE_CASTE_GRAPHICS" => {
+                        if len >= 3 {
+                            creature.name = line_vec[1].clone();
+                            creature.caste = Some(Caste::from(line_vec[2].clone()));
+                        } else {
+                            index_err!(i_rel_line, buffer_len, len, 3, errors);
+                        }
+                    },
+                    "LAYER_SET" => {
+                        if block_buffer.len() > 0 {
+                            let (ls_temp, temp) = LayerSet::read(block_buffer.clone(), Vec::new(), path);
+                            let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(i_rel_line, buffer_len, 0..=0, e)).collect();
+                            errors.append(&mut es_temp);
+                            if ls_temp.state.ne(&State::Empty) {
+                                creature.layer_sets.push(ls_temp);
+                            }
+                            block_buffer.clear();
+                        }
+                    },
+                    other => {
+                        match State::from(other.to_string()) {
+                            State::Custom(_) => {},
+                            _ => {
+                                if block_buffer.len() >= 2 {
+                                    let (ls_temp, temp) = LayerSet::read(block_buffer.clone(), Vec::new(), path);
+                                    let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(i_rel_line, buffer_len, 0..=0, e)).collect();
+                                    errors.append(&mut es_temp);
+                                    if ls_temp.state.ne(&State::Empty) {
+                                        creature.layer_sets.push(ls_temp);
+                                    }
+                                    block_buffer.clear();
+                                }
+                                if len >= 4 {
+                                    let (sl_temp, temp) = SimpleLayer::read(vec![line_vec.clone()], Vec::new(), path);
+                                    let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(i_rel_line, buffer_len, 0..=0, e)).collect();
+                                    errors.append(&mut es_temp);
+                                    if sl_temp.ne(&SimpleLayer::new()) {
+                                        creature.simple_layers.push(sl_temp);
+                                    }
+                                } else {
+                                    index_err!(i_rel_line, buffer_len, len, 4, errors);
+                                }
+                            },
+                        }
+                    },
+                }
+            }
+            block_buffer.push(line_vec.clone());
+        }
+        let last_line = buffer.len();
+        if block_buffer.len() >= 2 {
+            let (ls_temp, temp) = LayerSet::read(block_buffer.clone(), Vec::new(), path);
+            let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(last_line, buffer_len, 0..=0, e)).collect();
+            errors.append(&mut es_temp);
+            if ls_temp.state.ne(&State::Empty) {
+                creature.layer_sets.push(ls_temp);
+            }
+        }
+        (creature, errors)
+    }
+
+    fn display(&self) -> String {
+        let mut out;
+        if let Some(caste) = &self.caste {
+            out = format!("[CREATURE_CASTE_GRAPHICS:{}:{}]\n",
+                self.name
+                .with_boundaries(&[Boundary::Space])
+                .to_case(Case::UpperSnake)
+                .to_string(),
+                caste.name()
+            );
+        } else {
+            out = format!("[CREATURE_GRAPHICS:{}]\n",
+                self.name
+                .with_boundaries(&[Boundary::Space])
+                .to_case(Case::UpperSnake)
+                .to_string()
+            );
+        }
+
+        for simple_layer in &self.simple_layers {
+            out.push_str(&simple_layer.display());
+        }
+
+        for layer_set in &self.layer_sets {
+            out.push_str(&layer_set.display());
+        }
+
+        out.push_str("\n");
+
+        out
+    }
+}
+impl Menu for Creature {
+    fn menu(&mut self, ui: &mut Ui, shared: &mut Shared) {
+        let caste_opt: &mut Option<Caste> = &mut self.caste;
+        let simple_layers = &mut self.simple_layers;
+        let layer_sets = &mut self.layer_sets;
+
+        ui.separator();
+        Creature::name_menu(ui, &mut self.name, &shared.catalog);
+
+        if layer_sets.is_empty() {
+            ui.add_space(PADDING);
+
+            let mut caste_bool = caste_opt.is_some();
+
+            ui.checkbox(&mut caste_bool, "Caste:");
+
+            if caste_bool { 
+                let caste = caste_opt.get_or_insert(Caste::Female);
+
+                egui::ComboBox::from_label("Caste?")
+                    .selected_text(caste.name())
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(caste, Caste::Male, "MALE");
+                        ui.selectable_value(caste, Caste::Female, "FEMALE");
+                        for defined_caste in Caste::defined(&self.name, &shared.catalog) {
+                            ui.selectable_value(caste, defined_caste.clone(), defined_caste.name());
+                        }
+                        for shared_caste in &shared.creature_shared.castes {
+                            ui.selectable_value(caste, shared_caste.clone(), shared_caste.name());
+                        }
+                        ui.selectable_value(caste, Caste::Custom(String::new()), "(custom)");
+                    });
+    
+                if let Caste::Custom(caste_name) = caste {
+                    ui.text_edit_singleline(caste_name);
+                }
+                caste.warning(ui, &self.name, &shared.catalog);
+            } else {
+                caste_opt.take();
+            }
+        }
+
+        ui.add_space(PADDING);
+
+        if ui.button("Add Simple Layer").clicked() {
+            simple_layers.push(SimpleLayer::default());
+        }
+        
+        egui::ScrollArea::both()
+            .id_source("layer scroll")
+            .auto_shrink([false, true])
+            .stick_to_right(true)
+            .show(ui, |ui| {
+
+            let mut delete_sl = None;
+
+            for (i_sl, simple_layer) in simple_layers.iter_mut().enumerate() {
+                ui.push_id(format!("simple_layer{}",i_sl), |ui| {
+                    ui.group(|ui| {
+                        simple_layer.menu(ui, shared);
+                        if ui.button("Remove Simple Layer").clicked() {
+                            delete_sl = Some(i_sl);
+                        }
+                    });
+                    
+                    ui.add_space(PADDING);
+                });
+            }
+
+            ui.add_space(PADDING);
+
+            if ui.button("Add Layer Set").clicked() {
+                layer_sets.push(LayerSet::default());
+            }
+
+            let mut delete_ls = None;
 
             for (i_ls, layer_set) in layer_sets.iter_mut().enumerate() {
                 ui.push_id(format!("layer_set{}",i_ls), |ui| {
@@ -1114,7 +3071,7 @@ impl Menu for Creature {
                         
                         if matches!(layer_set.state, State::Animated) {
                             ui.label("Note: Although ANIMATED is used in vanilla, only DEFAULT, PORTRAIT, CORPSE, BABY:DEFAULT, and CHILD:DEFAULT appear to work properly (v51.01beta20)");
-                        } else if !(matches!(layer_set.state, State::Default | State::Corpse | State::Portrait) && layer_set.sub_state.is_none()) && !(matches!(layer_set.state, State::Baby | State::Child) && matches!(layer_set.sub_state, Some(State::Default))) {
+                        } else if !layer_set.state_known_to_work() {
                             ui.label("Note: Only DEFAULT, PORTRAIT, CORPSE, BABY:DEFAULT, and CHILD:DEFAULT are known to work (v51.01beta20)");
                         }
 
@@ -1253,6 +3210,45 @@ impl Menu for Creature {
     }
 }
 impl Creature {
+    //creature name field with a dropdown of the creatures in the creature raws
+    fn name_menu(ui: &mut Ui, name: &mut String, catalog: &RawCatalog) {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(name);
+
+            if !catalog.creatures.is_empty() {
+                let mut creature_names: Vec<&String> = catalog.creatures.iter().map(|c| &c.name).collect();
+                creature_names.sort();
+
+                egui::ComboBox::from_id_source("defined_creatures")
+                    .selected_text("Defined creatures")
+                    .show_ui(ui, |ui| {
+                    for creature_name in creature_names {
+                        ui.selectable_value(name, creature_name.clone(), creature_name);
+                    }
+                });
+
+                if catalog.creature(name).is_none() {
+                    ui.colored_label(egui::Color32::YELLOW, "⚠")
+                        .on_hover_text("Not defined in the creature raws.");
+                }
+            }
+        });
+    }
+
+    /// Tile page name, coordinates and large coordinates of the first graphic, to preview the creature by.
+    pub fn preview_tile(&self) -> Option<(String, [u32; 2], Option<[u32; 2]>)> {
+        if let Some(simple_layer) = self.simple_layers.first() {
+            return Some((simple_layer.tile_name.clone(), simple_layer.coords, simple_layer.large_coords));
+        }
+
+        self.layer_sets
+            .iter()
+            .flat_map(|layer_set| layer_set.layer_groups.iter())
+            .flat_map(|layer_group| layer_group.layers.iter())
+            .next()
+            .map(|layer| (layer.tile_name.clone(), layer.coords, layer.large_coords))
+    }
+
     fn creature_active_layers(&mut self, ui: &mut Ui, shared: &mut Shared) -> Vec<(String, [[u32;2];2])> {
         let mut out = Vec::new();
         if self.layer_sets.is_empty() {
@@ -1263,7 +3259,7 @@ impl Creature {
             let simple_layer = simple_layers.first().unwrap_or(&default);
 
             let mut coords = [[0,0],[95,63]];
-            if let Some(tp_info) = shared.tile_page_info.get(&simple_layer.tile_name) {
+            if let Some(tp_info) = shared.tile_page(&simple_layer.tile_name) {
                 let upper_left = [simple_layer.coords[0]*tp_info.tile_size[0], simple_layer.coords[1]*tp_info.tile_size[1]];
                 let large = simple_layer.large_coords.unwrap_or([1,1]);
                 let lower_right = [large[0]*tp_info.tile_size[0]+upper_left[0], large[1]*tp_info.tile_size[1]+upper_left[1]];
@@ -1293,7 +3289,7 @@ impl Creature {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct SimpleLayer {
     pub state: State,
     pub tile_name: String,
@@ -1312,7 +3308,7 @@ impl RAW for SimpleLayer {
         }
     }
 
-    fn read(buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, _path: &PathBuf) -> (Self, Vec<DFGHError>) {
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
         let mut simple_layer = SimpleLayer::new();
         let mut errors: Vec<DFGHError> = Vec::new();
         let line_vec = buffer[0].clone();
@@ -1320,7 +3316,7 @@ impl RAW for SimpleLayer {
         let i_line: usize = 1;
         let buffer_len: usize = 1;
 
-        let mut reduced_line = line_vec.clone();
+        let mut reduced_line = line_vec.args.clone();
         reduced_line.retain(|l| l.ne("AS_IS"));
         let reduced_len = reduced_line.len();
 
@@ -1372,8 +3368,8 @@ impl RAW for SimpleLayer {
                         .to_string(),
                     self.coords[0],
                     self.coords[1],
-                    self.coords[0] + x2,
-                    self.coords[1] + y2,
+                    self.coords[0].saturating_add(x2),
+                    self.coords[1].saturating_add(y2),
                     sub_state.name(),
                 )
             } else {
@@ -1385,8 +3381,8 @@ impl RAW for SimpleLayer {
                         .to_string(),
                     self.coords[0],
                     self.coords[1],
-                    self.coords[0] + x2,
-                    self.coords[1] + y2,
+                    self.coords[0].saturating_add(x2),
+                    self.coords[1].saturating_add(y2),
                 )
             }
         } else {
@@ -1475,30 +3471,22 @@ impl Menu for SimpleLayer {
         ui.checkbox(&mut large, "Large Image:");
 
         let [x2, y2] = self.large_coords.get_or_insert([0, 0]);
-        let max_coords;
-        if let Some(tp_info) = shared.tile_page_info.get(&self.tile_name) {
-            max_coords = [(tp_info.image_size[0]/32) as u32, (tp_info.image_size[1]/32) as u32];
-        } else {
-            max_coords = [100,100];
-        }
+        let max_coords = TilePageInfo::slider_max(shared.tile_page(&self.tile_name));
         if large {
             ui.horizontal(|ui| {
-                ui.add(egui::Slider::new(x1, 0..=max_coords[0].checked_sub(*x2+1)
-                    .unwrap_or_default()).prefix("X: "));
+                ui.add(egui::Slider::new(x1, 0..=max_coords[0].saturating_sub(1).saturating_sub(*x2)).prefix("X: "));
                 ui.add(egui::Slider::new(x2, 0..=2).prefix("X + "));
             });
             ui.horizontal(|ui| {
-                ui.add(egui::Slider::new(y1, 0..=max_coords[1].checked_sub(*y2+1)
-                    .unwrap_or_default()).prefix("Y: "));
+                ui.add(egui::Slider::new(y1, 0..=max_coords[1].saturating_sub(1).saturating_sub(*y2)).prefix("Y: "));
                 ui.add(egui::Slider::new(y2, 0..=1).prefix("Y + "));
             });
         } else {
             self.large_coords = None;
-            ui.add(egui::Slider::new(x1, 0..=max_coords[0].checked_sub(1)
-                .unwrap_or(0)).prefix("X: "));
-            ui.add(egui::Slider::new(y1, 0..=max_coords[1].checked_sub(1)
-                .unwrap_or(0)).prefix("Y: "));
+            ui.add(egui::Slider::new(x1, 0..=max_coords[0].saturating_sub(1)).prefix("X: "));
+            ui.add(egui::Slider::new(y1, 0..=max_coords[1].saturating_sub(1)).prefix("Y: "));
         }
+        TilePageInfo::bounds_warning(ui, shared.tile_page(&self.tile_name), &mut self.coords, &mut self.large_coords);
 
         ui.add_space(PADDING);
         ui.label("Preview:");
@@ -1508,7 +3496,7 @@ impl Menu for SimpleLayer {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct LayerSet {
     state: State,
     sub_state: Option<State>,
@@ -1527,14 +3515,24 @@ impl RAW for LayerSet {
         }
     }
 
-    fn read(buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, path: &PathBuf) -> (Self, Vec<DFGHError>) {
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, path: &Path) -> (Self, Vec<DFGHError>) {
         let mut layer_set = LayerSet::new();
         let mut errors: Vec<DFGHError> = Vec::new();
         let mut block_buffer = Vec::with_capacity(100);
+        let mut palette_buffer = Vec::new();
         let buffer_len = buffer.len();
 
         for (i_rel_line, line_vec) in buffer.iter().enumerate() {
             let len = line_vec.len();
+
+            //a palette ends at the first token that isn't one of its own
+            if palette_buffer.len() > 0 && !matches!(line_vec[0].as_str(), "LS_PALETTE_FILE" | "LS_PALETTE_DEFAULT") {
+                let (p_temp, temp) = Palette::read(palette_buffer.clone(), Vec::new(), path);
+                let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(i_rel_line, buffer_len, 0..=0, e)).collect();
+                errors.append(&mut es_temp);
+                layer_set.palettes.push(p_temp);
+                palette_buffer.clear();
+            }
             
             if len >= 1 {
                 match line_vec[0].as_str() {
@@ -1557,46 +3555,13 @@ impl RAW for LayerSet {
                             if lg_temp.ne(&LayerGroup::new()) {
                                 layer_set.layer_groups.push(lg_temp);
                             }
-                            block_buffer.clear();
-                        }
-                    },
-                    "LS_PALETTE" => {
-                        if len >= 2 {
-                            layer_set.palettes.push(Palette{name: line_vec[1].clone(), ..Default::default()});
-                        } else {
-                            index_err!(i_rel_line, buffer_len, len, 2, errors);
-                        }
-                    }
-                    "LS_PALETTE_FILE" => {
-                        if len >= 2 {
-                            let mut default_pal = Palette::new();
-                            let last_palette = layer_set.palettes.last_mut().unwrap_or(&mut default_pal);
-                            let file_name = line_vec[1].clone().split(&['\\','/']).collect::<PathBuf>();
-
-                            //set palette max dimensions based on file if possible
-                            if path.parent().is_some_and(|p| p.join(&file_name).exists()) {
-                                let image_path = path
-                                    .parent().expect("This file should have a parent graphics directory if we are reading from it.")
-                                    .join(&file_name)
-                                    .with_extension("png");
-                                
-                                if let Ok(image_dimensions) = image::image_dimensions(image_path) {
-                                    last_palette.max_row = (image_dimensions.1 as u32).saturating_sub(1);
-                                }
-                            }
-
-                            last_palette.file_name = file_name;
-                        } else {
-                            index_err!(i_rel_line, buffer_len, len, 2, errors);
-                        }
-                    }
-                    "LS_PALETTE_DEFAULT" => {
-                        if len >= 2 {
-                            layer_set.palettes.last_mut().get_or_insert(&mut Palette::new()).default_index = 
-                                buffer_err_wrap!(line_vec[1].parse::<u32>(), i_rel_line, buffer_len, 1..=1, 0, errors);
-                        } else {
-                            index_err!(i_rel_line, buffer_len, len, 2, errors);
+                            block_buffer.clear();
                         }
+                    },
+                    "LS_PALETTE" |
+                    "LS_PALETTE_FILE" |
+                    "LS_PALETTE_DEFAULT" => {
+                        palette_buffer.push(line_vec.clone());
                     }
                     _ => {}
                 }
@@ -1612,6 +3577,12 @@ impl RAW for LayerSet {
                 layer_set.layer_groups.push(lg_temp);
             }
         }
+        if palette_buffer.len() > 0 {
+            let (p_temp, temp) = Palette::read(palette_buffer.clone(), Vec::new(), path);
+            let mut es_temp = temp.iter().map(|e| wrap_import_buffer_error(last_line, buffer_len, 0..=0, e)).collect();
+            errors.append(&mut es_temp);
+            layer_set.palettes.push(p_temp);
+        }
 
         layer_set.rename_layer_groups();
         (layer_set, errors)
@@ -1674,7 +3645,7 @@ impl Menu for LayerSet {
         
         if matches!(self.state, State::Animated) {
             ui.label("Note: Although ANIMATED is used in vanilla, only DEFAULT, PORTRAIT, CORPSE, BABY:DEFAULT, and CHILD:DEFAULT appear to work properly (v51.01beta20)");
-        } else if !(matches!(self.state, State::Default | State::Corpse | State::Portrait) && self.sub_state.is_none()) && !(matches!(self.state, State::Baby | State::Child) && matches!(self.sub_state, Some(State::Default))) {
+        } else if !self.state_known_to_work() {
             ui.label("Note: Only DEFAULT, PORTRAIT, CORPSE, BABY:DEFAULT, and CHILD:DEFAULT are known to work (v51.01beta20)");
         }
 
@@ -1685,10 +3656,15 @@ impl Menu for LayerSet {
 
         ui.add_space(PADDING);
         if ui.button("New Palette").clicked() {
-            self.palettes.push(Palette {name: "(new)".to_string(), file_name: PathBuf::new(), default_index: 0, max_row: 63});
+            self.palettes.push(Palette {name: "(new)".to_string(), file_name: PathBuf::new(), default_index: 0, max_row: 63, rows: Vec::new()});
+        }
+
+        for e in self.palette_errors("") {
+            ui.colored_label(egui::Color32::YELLOW, e.to_string());
         }
 
         let mut delete = None;
+        let mut renamed = None;
         
         egui::ScrollArea::vertical()
             .id_source("Palette scroll")
@@ -1698,7 +3674,12 @@ impl Menu for LayerSet {
             for (i_palette, palette) in self.palettes.iter_mut().enumerate() {
                 ui.push_id(i_palette, |ui| {
                     ui.group(|ui| {
+                        let old_name = palette.name.clone();
                         palette.menu(ui, shared);
+                        if palette.name != old_name {
+                            renamed = Some((i_palette, palette.name.clone()));
+                            palette.name = old_name;
+                        }
                         ui.add_space(PADDING);
                         if ui.button("Remove Palette").clicked() {
                             delete = Some(i_palette);
@@ -1708,12 +3689,97 @@ impl Menu for LayerSet {
             }
         });
 
+        if let Some((i_palette, new_name)) = renamed {
+            self.rename_palette(i_palette, &new_name);
+        }
         if let Some(i_palette) = delete {
             self.palettes.remove(i_palette);//checked
         }
     }
 }
 impl LayerSet {
+    /// Rename one palette and every USE_PALETTE condition in the layer set that refers to it. Conditions are left
+    /// alone while another palette has the old name too, since they may refer to that one.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let text = "[LAYER_SET:DEFAULT]\n\t[LS_PALETTE:SKIN]\n\t[LS_PALETTE:HAIR]\n\t[LAYER_GROUP]\n\t[LAYER:BODY:DWARF:0:0]\n\t\t[USE_PALETTE:SKIN:1]\n";
+    /// let (mut layer_set, _) = LayerSet::read_text(text, "clipboard".as_ref());
+    ///
+    /// layer_set.rename_palette(0, "FUR");
+    /// assert!(layer_set.display().contains("[LS_PALETTE:FUR]"));
+    /// assert!(layer_set.display().contains("[USE_PALETTE:FUR:1]"));
+    ///
+    /// //passing through the name of another palette doesn't merge the two
+    /// layer_set.rename_palette(0, "HAIR");
+    /// layer_set.rename_palette(0, "HAIRY");
+    /// assert!(layer_set.display().contains("[LS_PALETTE:HAIRY]"));
+    /// assert!(layer_set.display().contains("[LS_PALETTE:HAIR]"));
+    /// ```
+    pub fn rename_palette(&mut self, i_palette: usize, new_name: &str) {
+        let Some(old_name) = self.palettes.get(i_palette).map(|p| p.name.clone()) else {
+            return;
+        };
+        self.palettes[i_palette].name = new_name.to_string();
+
+        if self.palettes.iter().any(|p| p.name.eq_ignore_ascii_case(&old_name)) {
+            return;
+        }
+        for condition in self.layer_groups.iter_mut()
+            .flat_map(|lg| lg.layers.iter_mut())
+            .flat_map(|l| l.conditions.iter_mut()) {
+            if let Condition::UsePalette(palette, _) = condition {
+                if palette.name.eq_ignore_ascii_case(&old_name) {
+                    palette.name = new_name.to_string();
+                }
+            }
+        }
+    }
+
+    /// Whether DF is known to draw the layer set's state.
+    pub fn state_known_to_work(&self) -> bool {
+        (matches!(self.state, State::Default | State::Corpse | State::Portrait) && self.sub_state.is_none())
+            || (matches!(self.state, State::Baby | State::Child) && matches!(self.sub_state, Some(State::Default)))
+    }
+
+    /// Check every USE_PALETTE condition against the palettes declared in the layer set and their rows.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// let text = "[LAYER_SET:DEFAULT]\n\t[LS_PALETTE:SKIN]\n\t[LAYER_GROUP]\n\t[LAYER:BODY:DWARF:0:0]\n\t\t[USE_PALETTE:HAIR:1]\n";
+    /// let (layer_set, _) = LayerSet::read_text(text, "clipboard".as_ref());
+    ///
+    /// let errors = layer_set.palette_errors("DWARF");
+    ///
+    /// assert_eq!(1, errors.len());
+    /// assert!(errors[0].to_string().contains("HAIR"));
+    /// ```
+    pub fn palette_errors(&self, creature_name: &str) -> Vec<DFGHError> {
+        let mut errors = Vec::new();
+
+        for layer in self.layer_groups.iter().flat_map(|lg| lg.layers.iter()) {
+            let location = [
+                creature_name.to_string(),
+                format!("layer set {}", self.state.name()),
+                format!("layer {}", layer.name),
+            ].iter().filter(|s| !s.is_empty()).cloned().collect::<Vec<String>>().join(", ");
+
+            for condition in &layer.conditions {
+                if let Condition::UsePalette(palette, row) = condition {
+                    match self.palettes.iter().find(|p| p.name.eq_ignore_ascii_case(&palette.name)) {
+                        None => errors.push(DFGHError::UndeclaredPalette(location.clone(), palette.name.clone())),
+                        Some(declared) if *row > declared.max_row => {
+                            errors.push(DFGHError::PaletteRowOutOfRange(location.clone(), palette.name.clone(), *row, declared.max_row));
+                        },
+                        Some(_) => {},
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
     fn rename_layer_groups(&mut self) {
         for lg in self.layer_groups.iter_mut() {
             if lg.name.eq(&LayerGroup::new().name) {
@@ -1740,7 +3806,7 @@ impl LayerSet {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct LayerGroup {
     pub name: String,       //internal layer group name
     pub layers: Vec<Layer>, //set of layers to display for creature
@@ -1755,13 +3821,13 @@ impl RAW for LayerGroup {
         }
     }
     
-    fn read(buffer: Vec<Vec<String>>, raw_buffer: Vec<String>, path: &PathBuf) -> (Self, Vec<DFGHError>) {
+    fn read(buffer: Vec<Token>, raw_buffer: Vec<String>, path: &Path) -> (Self, Vec<DFGHError>) {
         let mut layer_group = LayerGroup::new();
         let mut errors: Vec<DFGHError> = Vec::new();
         let mut block_buffer = Vec::with_capacity(100);
         let buffer_len = buffer.len();
 
-        if let Some(lg_name) = raw_buffer.get(0).unwrap_or(&String::new()).contains("---").then(|| *raw_buffer[0].split("---").collect::<Vec<&str>>().get(1).unwrap_or(&"(new)")) {
+        if let Some(lg_name) = raw_buffer.first().unwrap_or(&String::new()).contains("---").then(|| *raw_buffer[0].split("---").collect::<Vec<&str>>().get(1).unwrap_or(&"(new)")) {
             layer_group.name = lg_name.to_string();
         }
 
@@ -1962,7 +4028,7 @@ impl Menu for LayerGroup {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Layer {
     pub name: String,
     pub conditions: Vec<Condition>,
@@ -1981,7 +4047,7 @@ impl RAW for Layer {
         }
     }
 
-    fn read(buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, path: &PathBuf) -> (Self, Vec<DFGHError>) {
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, path: &Path) -> (Self, Vec<DFGHError>) {
         let mut layer = Layer::new();
         let mut errors: Vec<DFGHError> = Vec::new();
         let buffer_len = buffer.len();
@@ -1993,7 +4059,7 @@ impl RAW for Layer {
                 match line_vec[0].as_str() {
                     "LAYER" => {
                         if len >= 5 {
-                            let mut reduced_line = line_vec.clone();
+                            let mut reduced_line = line_vec.args.clone();
                             reduced_line.retain(|l| l.ne("AS_IS"));
                             let reduced_len = reduced_line.len();
 
@@ -2084,8 +4150,8 @@ impl RAW for Layer {
                     .to_string(),
                 self.coords[0],
                 self.coords[1],
-                self.coords[0] + x2,
-                self.coords[1] + y2,
+                self.coords[0].saturating_add(x2),
+                self.coords[1].saturating_add(y2),
             ));
         } else {
             out.push_str(&format!(
@@ -2138,30 +4204,22 @@ impl Menu for Layer {
         ui.checkbox(&mut large, "Large Image:");
 
         let [x2, y2] = self.large_coords.get_or_insert([0, 0]);
-        let max_coords;
-        if let Some(tp_info) = shared.tile_page_info.get(&self.tile_name) {
-            max_coords = [(tp_info.image_size[0]/32) as u32, (tp_info.image_size[1]/32) as u32];
-        } else {
-            max_coords = [100,100];
-        }
+        let max_coords = TilePageInfo::slider_max(shared.tile_page(&self.tile_name));
         if large {
             ui.horizontal(|ui| {
-                ui.add(egui::Slider::new(x1, 0..=max_coords[0].checked_sub(*x2+1)
-                    .unwrap_or_default()).prefix("X: "));
+                ui.add(egui::Slider::new(x1, 0..=max_coords[0].saturating_sub(1).saturating_sub(*x2)).prefix("X: "));
                 ui.add(egui::Slider::new(x2, 0..=2).prefix("X + "));
             });
             ui.horizontal(|ui| {
-                ui.add(egui::Slider::new(y1, 0..=max_coords[1].checked_sub(*y2+1)
-                    .unwrap_or_default()).prefix("Y: "));
+                ui.add(egui::Slider::new(y1, 0..=max_coords[1].saturating_sub(1).saturating_sub(*y2)).prefix("Y: "));
                 ui.add(egui::Slider::new(y2, 0..=1).prefix("Y + "));
             });
         } else {
             self.large_coords.take();
-            ui.add(egui::Slider::new(x1, 0..=max_coords[0].checked_sub(1)
-                .unwrap_or(0)).prefix("X: "));
-            ui.add(egui::Slider::new(y1, 0..=max_coords[1].checked_sub(1)
-                .unwrap_or(0)).prefix("Y: "));
+            ui.add(egui::Slider::new(x1, 0..=max_coords[0].saturating_sub(1)).prefix("X: "));
+            ui.add(egui::Slider::new(y1, 0..=max_coords[1].saturating_sub(1)).prefix("Y: "));
         }
+        TilePageInfo::bounds_warning(ui, shared.tile_page(&self.tile_name), &mut self.coords, &mut self.large_coords);
 
         ui.add_space(PADDING);
         if ui.button("Add Condition").clicked() {
@@ -2201,7 +4259,7 @@ impl Menu for Layer {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum Condition {
     #[default]
     Default,
@@ -2242,7 +4300,7 @@ impl RAW for Condition {
         Self::default()
     }
 
-    fn read(buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, _path: &PathBuf) -> (Self, Vec<DFGHError>) {
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
         let mut line_vec = buffer[0].clone();
         let mut condition = Condition::new();
         let mut errors: Vec<DFGHError> = Vec::new();
@@ -2286,7 +4344,7 @@ impl RAW for Condition {
                 "CONDITION_MATERIAL_TYPE" => {
                     if len >= 3 {
                         condition = Condition::MaterialType(
-                            MaterialType::from(line_vec[2].clone())
+                            MaterialType::from(line_vec[1..3].join(":"))
                         )
                     } else if len == 2 {
                         condition = Condition::MaterialType(
@@ -2460,7 +4518,7 @@ impl RAW for Condition {
                 "USE_STANDARD_PALETTE_FROM_ITEM" => condition = Condition::UseStandardPalette,
                 "CONDITION_BP" => {
                     if len >=3 {
-                        let (bp_type, mut es_temp) = BodyPartType::from(line_vec.clone());
+                        let (bp_type, mut es_temp) = BodyPartType::from(line_vec[1..].to_vec());
                         errors.append(&mut es_temp);
                         condition = Condition::ConditionBP(bp_type);
                     } else {
@@ -2469,7 +4527,7 @@ impl RAW for Condition {
                 },
                 "LG_CONDITION_BP" => {
                     if len >= 3 {
-                        let (bp_type, mut es_temp) = BodyPartType::from(line_vec.clone());
+                        let (bp_type, mut es_temp) = BodyPartType::from(line_vec[1..].to_vec());
                         errors.append(&mut es_temp);
                         condition = Condition::LGConditionBP(bp_type);
                     } else {
@@ -2694,7 +4752,7 @@ impl RAW for Condition {
                     out.push_str(&format!(
                         "LARGE_IMAGE:{}:{}:{}:{}]\n",
                         x1,
-                        y2,
+                        y1,
                         x1 + x2,
                         y1 + y2
                     ));
@@ -2770,7 +4828,7 @@ impl Menu for Condition {
                     ItemType::None => {},
                     ItemType::ByCategory(category, equipment) => {
                         ui.label("Category: (e.g. HEAD)");
-                        ui.text_edit_singleline(category);
+                        BodyPartType::name_menu(ui, category, "BY_CATEGORY", shared);
 
                         ui.label("Item type: (e.g. HELM)");
                         egui::ComboBox::from_label("Item type")
@@ -2788,7 +4846,7 @@ impl Menu for Condition {
                         for (i_item, item) in items.iter_mut().enumerate() {
                             ui.push_id(i_item, |ui| {
                                 ui.horizontal(|ui| {
-                                    ui.text_edit_singleline(item);
+                                    Condition::item_menu(ui, item, equipment, &shared.catalog);
                                     if ui.small_button("❌").clicked() {
                                         remove_item = Some(i_item);
                                     }
@@ -2841,7 +4899,7 @@ impl Menu for Condition {
                     }
                     ItemType::ByToken(token, equipment) => {
                         ui.label("Token: (e.g. RH for right hand)");
-                        ui.text_edit_singleline(token);
+                        BodyPartType::name_menu(ui, token, "BY_TOKEN", shared);
 
                         ui.label("Item type: (e.g. GLOVES)");
                         egui::ComboBox::from_label("Item type")
@@ -2859,7 +4917,7 @@ impl Menu for Condition {
                         for (i_item, item) in items.iter_mut().enumerate() {
                             ui.push_id(i_item, |ui| {
                                 ui.horizontal(|ui| {
-                                    ui.text_edit_singleline(item);
+                                    Condition::item_menu(ui, item, equipment, &shared.catalog);
                                     if ui.small_button("❌").clicked() {
                                         remove_item = Some(i_item);
                                     }
@@ -2928,7 +4986,7 @@ impl Menu for Condition {
                         for (i_item, item) in items.iter_mut().enumerate() {
                             ui.push_id(i_item, |ui| {
                                 ui.horizontal(|ui| {
-                                    ui.text_edit_singleline(item);
+                                    Condition::item_menu(ui, item, equipment, &shared.catalog);
                                     if ui.small_button("❌").clicked() {
                                         remove_item = Some(i_item);
                                     }
@@ -3000,7 +5058,7 @@ impl Menu for Condition {
                             for (i_item, item) in items.iter_mut().enumerate() {
                                 ui.push_id(i_item, |ui| {
                                     ui.horizontal(|ui| {
-                                        ui.text_edit_singleline(item);
+                                        Condition::item_menu(ui, item, equipment, &shared.catalog);
                                         if ui.small_button("❌").clicked() {
                                             remove_item = Some(i_item);
                                         }
@@ -3059,7 +5117,7 @@ impl Menu for Condition {
                     "Dye color token:",
                     "http://dwarffortresswiki.org/index.php/Color#Color_tokens",
                 );
-                ui.text_edit_singleline(&mut dye_color.name());
+                dye_color.menu(ui, &shared.catalog);
                 egui::ComboBox::from_label("")
                     .selected_text("Existing Colors")
                     .show_ui(ui, |ui| {
@@ -3116,11 +5174,26 @@ impl Menu for Condition {
                     for material_type_i in MaterialType::iterator() {
                         ui.selectable_value(material_type, material_type_i.clone(), material_type_i.name());
                     }
+                    for metal in shared.catalog.inorganics.iter().filter(|i| i.is_metal()) {
+                        let metal_type = MaterialType::from(format!("METAL:{}", metal.name));
+                        if matches!(metal_type, MaterialType::Custom(..)) {
+                            ui.selectable_value(material_type, metal_type.clone(), metal_type.name())
+                                .on_hover_text(metal.flags.join(", "));
+                        }
+                    }
                     for shared_material_type in &shared.creature_shared.material_types {
                         ui.selectable_value(material_type, shared_material_type.clone(), shared_material_type.name());
                     }
                     ui.selectable_value(material_type, MaterialType::Custom(String::new()), "(custom)");
                 });
+                if let MaterialType::Custom(material_name) = material_type {
+                    ui.text_edit_singleline(material_name);
+                }
+                for problem in self.unknown_materials("", &shared.catalog) {
+                    if let DFGHError::UnknownMaterial(_, kind, name) = problem {
+                        ui.colored_label(egui::Color32::YELLOW, format!("⚠ Unknown {} {}", kind, name));
+                    }
+                }
 
                 ui.add_space(PADDING);
                 ui.label(
@@ -3267,15 +5340,35 @@ impl Menu for Condition {
                     for syn_class_type in SyndromeClass::iterator() {
                         ui.selectable_value(syn_class, syn_class_type.clone(), syn_class_type.name());
                     }
+                    for defined_class in &shared.catalog.syndrome_classes {
+                        let syn_class_type = SyndromeClass::from(defined_class.clone());
+                        if matches!(syn_class_type, SyndromeClass::Custom(..)) {
+                            ui.selectable_value(syn_class, syn_class_type.clone(), syn_class_type.name());
+                        }
+                    }
                     ui.selectable_value(syn_class, SyndromeClass::Custom(String::new()), "(custom)".to_string());
                 });
+                if let SyndromeClass::Custom(syn_class_name) = syn_class {
+                    ui.text_edit_singleline(syn_class_name);
+                }
+                for problem in self.unknown_materials("", &shared.catalog) {
+                    if let DFGHError::UnknownMaterial(_, kind, name) = problem {
+                        ui.colored_label(egui::Color32::YELLOW, format!("⚠ Unknown {} {}", kind, name));
+                    }
+                }
             }
             Condition::TissueLayer(category, tissue) => {
                 ui.label("BY_CATEGORY assumed because it is the only selection type tested to be functional in v50.13");
                 ui.label("Category: (e.g. HEAD or ALL)");
-                ui.text_edit_singleline(category);
+                BodyPartType::name_menu(ui, category, "BY_CATEGORY", shared);
                 ui.label("Tissue: (e.g. HAIR or ALL)");
-                ui.text_edit_singleline(tissue);
+                Condition::defined_name_menu(
+                    ui,
+                    tissue,
+                    shared.catalog.tissues_of(&shared.creature_name),
+                    "defined_tissues",
+                    "Not a tissue of the creature in its raws."
+                );
             }
             Condition::TissueMinLength(length) => {
                 ui.add(egui::DragValue::new(length).speed(1).prefix("Min Length: "));
@@ -3300,7 +5393,7 @@ impl Menu for Condition {
                 for (i_col, tissue_color) in tissue_colors.iter_mut().enumerate() {
                     ui.push_id(i_col, |ui| {
                         ui.horizontal(|ui| {
-                            ui.text_edit_singleline(&mut tissue_color.name());
+                            tissue_color.menu(ui, &shared.catalog);
                             
                             if ui.small_button("❌").clicked() {
                                 remove_item = Some(i_col);
@@ -3351,7 +5444,21 @@ impl Menu for Condition {
                                 for shaping_type in Shaping::iterator() {
                                     ui.selectable_value(shaping, shaping_type.clone(), shaping_type.name());
                                 }
+                                for shaping_name in shared.catalog.shapings() {
+                                    let shaping_type = Shaping::from(shaping_name);
+                                    if matches!(shaping_type, Shaping::Custom(..)) {
+                                        ui.selectable_value(shaping, shaping_type.clone(), shaping_type.name());
+                                    }
+                                }
+                                ui.selectable_value(shaping, Shaping::Custom(String::new()), "(custom)");
                             });
+                            if let Shaping::Custom(shaping_name) = shaping {
+                                ui.text_edit_singleline(shaping_name);
+                                if !shared.catalog.tissue_styles.is_empty() && !shared.catalog.has_shaping(shaping_name) {
+                                    ui.colored_label(egui::Color32::YELLOW, "⚠")
+                                        .on_hover_text("Not a shaping defined in the creature or descriptor shape raws.");
+                                }
+                            }
                             
                             if ui.small_button("❌").clicked() {
                                 remove_item = Some(i_shap);
@@ -3368,8 +5475,6 @@ impl Menu for Condition {
                     shapings.push(Shaping::default());
                 }
 
-                ui.label("Additional shapings are used within graphics_creatures_creatures_layered.txt, but the complete list is not readily prepared.");
-
                 ui.add_space(PADDING);
                 ui.label("requires a CONDITION_TISSUE_LAYER above.");
             }
@@ -3412,30 +5517,24 @@ impl Menu for Condition {
                 ui.checkbox(&mut large, "Large Image:");
 
                 let [x2, y2] = large_coords.get_or_insert([0, 0]);
-                let max_coords;
-                if let Some(tp_info) = shared.tile_page_info.get(tile_page) {
-                    max_coords = [(tp_info.image_size[0]/32) as u32, (tp_info.image_size[1]/32) as u32];
-                } else {
-                    max_coords = [100,100];
-                }
+                let max_coords = TilePageInfo::slider_max(shared.tile_page(tile_page));
                 if large {
                     ui.horizontal(|ui| {
-                        ui.add(egui::Slider::new(x1, 0..=max_coords[0].checked_sub(*x2+1)
-                            .unwrap_or_default()).prefix("X: "));
+                        ui.add(egui::Slider::new(x1, 0..=max_coords[0].saturating_sub(1).saturating_sub(*x2)).prefix("X: "));
                         ui.add(egui::Slider::new(x2, 0..=2).prefix("X + "));
                     });
                     ui.horizontal(|ui| {
-                        ui.add(egui::Slider::new(y1, 0..=max_coords[1].checked_sub(*y2+1)
-                            .unwrap_or_default()).prefix("Y: "));
+                        ui.add(egui::Slider::new(y1, 0..=max_coords[1].saturating_sub(1).saturating_sub(*y2)).prefix("Y: "));
                         ui.add(egui::Slider::new(y2, 0..=1).prefix("Y + "));
                     });
                 } else {
                     large_coords.take();
-                    ui.add(egui::Slider::new(x1, 0..=max_coords[0].checked_sub(1)
-                        .unwrap_or(0)).prefix("X: "));
-                    ui.add(egui::Slider::new(y1, 0..=max_coords[1].checked_sub(1)
-                        .unwrap_or(0)).prefix("Y: "));
+                    ui.add(egui::Slider::new(x1, 0..=max_coords[0].saturating_sub(1)).prefix("X: "));
+                    ui.add(egui::Slider::new(y1, 0..=max_coords[1].saturating_sub(1)).prefix("Y: "));
                 }
+                let mut coords = [*x1, *y1];
+                TilePageInfo::bounds_warning(ui, shared.tile_page(tile_page), &mut coords, large_coords);
+                [*x1, *y1] = coords;
                 
                 ui.add_space(PADDING);
                 ui.label("requires a TISSUE_MIN_LENGTH above.");
@@ -3484,15 +5583,15 @@ impl Menu for Condition {
                     BodyPartType::None => {},
                     BodyPartType::ByType(by_type) => {
                         ui.label("Type: (e.g. GRASP)");
-                        ui.text_edit_singleline(by_type);
+                        BodyPartType::name_menu(ui, by_type, "BY_TYPE", shared);
                     },
                     BodyPartType::ByCategory(category) => {
                         ui.label("Category: (e.g. HEAD)");
-                        ui.text_edit_singleline(category);
+                        BodyPartType::name_menu(ui, category, "BY_CATEGORY", shared);
                     },
                     BodyPartType::ByToken(token) => {
                         ui.label("Token: (e.g. RH for right hand)");
-                        ui.text_edit_singleline(token);
+                        BodyPartType::name_menu(ui, token, "BY_TOKEN", shared);
                     },
                 }
             }
@@ -3510,15 +5609,15 @@ impl Menu for Condition {
                     BodyPartType::None => {},
                     BodyPartType::ByType(by_type) => {
                         ui.label("Type: (e.g. GRASP)");
-                        ui.text_edit_singleline(by_type);
+                        BodyPartType::name_menu(ui, by_type, "BY_TYPE", shared);
                     },
                     BodyPartType::ByCategory(category) => {
                         ui.label("Category: (e.g. HEAD)");
-                        ui.text_edit_singleline(category);
+                        BodyPartType::name_menu(ui, category, "BY_CATEGORY", shared);
                     },
                     BodyPartType::ByToken(token) => {
                         ui.label("Token: (e.g. RH for right hand)");
-                        ui.text_edit_singleline(token);
+                        BodyPartType::name_menu(ui, token, "BY_TOKEN", shared);
                     },
                 }
             }
@@ -3531,18 +5630,32 @@ impl Menu for Condition {
                     }
                 });
 
-                ui.add(
-                    egui::DragValue::new(min)
-                        .speed(1)
-                        .clamp_range(0..=*max)
-                        .prefix("Min: "),
-                );
-                ui.add(
-                    egui::DragValue::new(max)
-                        .speed(1)
-                        .clamp_range(*min..=10000)
-                        .prefix("Max: "),
-                );
+                //bound to the range the edited creature's raws give the modifier, when all its modifiers are known
+                let known = shared.catalog.appearance_known(&shared.creature_name);
+                if let Some([low, high]) = shared.catalog.appearance_range(&shared.creature_name, &bp_app_mod.name(), None).filter(|_| known) {
+                    ui.label(format!("{} ranges from {} to {} on {}", bp_app_mod.name(), low, high, shared.creature_name));
+                    ui.add(egui::Slider::new(min, low..=high).clamp_to_range(false).text("Min"));
+                    ui.add(egui::Slider::new(max, low..=high).clamp_to_range(false).text("Max"));
+                    if min > max || *max < low || *min > high {
+                        ui.colored_label(egui::Color32::YELLOW, "⚠ This range can never match.");
+                    }
+                } else {
+                    if known && !shared.catalog.appearance_modifiers_of(&shared.creature_name).is_empty() && *bp_app_mod != BPAppMod::None {
+                        ui.colored_label(egui::Color32::YELLOW, format!("⚠ {} doesn't have this appearance modifier.", shared.creature_name));
+                    }
+                    ui.add(
+                        egui::DragValue::new(min)
+                            .speed(1)
+                            .clamp_range(0..=*max)
+                            .prefix("Min: "),
+                    );
+                    ui.add(
+                        egui::DragValue::new(max)
+                            .speed(1)
+                            .clamp_range(*min..=10000)
+                            .prefix("Max: "),
+                    );
+                }
             }
             Condition::BPPresent => {
                 ui.label("No additional input needed.");
@@ -3578,6 +5691,169 @@ impl Menu for Condition {
     }
 }
 impl Condition {
+    /// Items of an item condition that the catalog doesn't define.
+    fn unknown_items(&self, catalog: &RawCatalog) -> Vec<String> {
+        match self {
+            Condition::ItemWorn(_, items) |
+            Condition::ShutOffIfItemPresent(_, items) => items
+                .iter()
+                .filter(|i| !i.is_empty() && !i.eq_ignore_ascii_case("ANY") && catalog.item(i).is_none())
+                .cloned()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    //name field with a dropdown of the names defined in the raws, warns about names that aren't
+    fn defined_name_menu(ui: &mut Ui, name: &mut String, defined_names: Vec<String>, id_source: &str, warning: &str) {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(name);
+
+            if !defined_names.is_empty() {
+                let known = name.is_empty() || name.as_str().eq("ALL") || defined_names.iter().any(|n| n.eq_ignore_ascii_case(name));
+
+                egui::ComboBox::from_id_source(id_source)
+                    .selected_text("Defined")
+                    .show_ui(ui, |ui| {
+                    for defined_name in defined_names {
+                        ui.selectable_value(name, defined_name.clone(), defined_name);
+                    }
+                });
+
+                if !known {
+                    ui.colored_label(egui::Color32::YELLOW, "⚠")
+                        .on_hover_text(warning);
+                }
+            }
+        });
+    }
+
+    /// Syndrome classes and inorganic materials of the condition that the raws don't define.
+    fn unknown_materials(&self, location: &str, catalog: &RawCatalog) -> Vec<DFGHError> {
+        let unknown = |kind: &str, name: &String| DFGHError::UnknownMaterial(location.to_string(), kind.to_string(), name.clone());
+
+        match self {
+            Condition::SynClass(SyndromeClass::Custom(syn_class))
+                if !catalog.syndrome_classes.is_empty() && !catalog.has_syndrome_class(syn_class) => {
+                vec![unknown("syndrome class", syn_class)]
+            },
+            Condition::MaterialType(MaterialType::Custom(material)) if !catalog.inorganics.is_empty() => {
+                match material.split_once(':') {
+                    Some(("METAL", name)) if !catalog.inorganic(name).is_some_and(|i| i.is_metal()) => {
+                        vec![unknown("metal", material)]
+                    },
+                    Some(("INORGANIC", name)) if catalog.inorganic(name).is_none() => {
+                        vec![unknown("material", material)]
+                    },
+                    _ => Vec::new(),
+                }
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Colors and shapings of the condition that the descriptor and creature raws don't define.
+    fn unknown_descriptors(&self, catalog: &RawCatalog) -> Vec<String> {
+        let mut unknown = Vec::new();
+
+        match self {
+            Condition::Dye(color) => {
+                unknown.push(color);
+            },
+            Condition::TissueMayHaveColor(colors) => {
+                unknown.extend(colors.iter());
+            },
+            Condition::TissueMayHaveShaping(shapings) => {
+                //the built in shapings are always known, so only check once some raws have been read
+                if !catalog.tissue_styles.is_empty() {
+                    return shapings
+                        .iter()
+                        .filter(|s| !catalog.has_shaping(&s.name()))
+                        .map(|s| format!("shaping {}", s.name()))
+                        .collect();
+                }
+            },
+            _ => {},
+        }
+
+        if catalog.colors.is_empty() {
+            return Vec::new();
+        }
+        unknown
+            .into_iter()
+            .filter_map(|color| match color {
+                Color::Custom(name) if catalog.color(name).is_none() => Some(format!("color {}", name)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Body parts and tissues the condition selects that the creature's raws don't give it.
+    fn unknown_body_references(&self, creature_name: &str, catalog: &RawCatalog) -> Vec<String> {
+        let mut body_parts = Vec::new();
+        let mut tissues = Vec::new();
+
+        match self {
+            Condition::ConditionBP(bp_type) |
+            Condition::LGConditionBP(bp_type) => {
+                body_parts.extend(bp_type.selection());
+            },
+            Condition::ItemWorn(item_type, _) |
+            Condition::ShutOffIfItemPresent(item_type, _) => {
+                match item_type {
+                    ItemType::ByCategory(category, _) => body_parts.push(("BY_CATEGORY", category)),
+                    ItemType::ByToken(token, _) => body_parts.push(("BY_TOKEN", token)),
+                    _ => {},
+                }
+            },
+            Condition::TissueLayer(category, tissue) => {
+                body_parts.push(("BY_CATEGORY", category));
+                tissues.push(tissue);
+            },
+            _ => {},
+        }
+
+        let mut unknown = Vec::new();
+        if !catalog.bodies.is_empty() {
+            for (selection, name) in body_parts.into_iter().filter(|(_, n)| !n.is_empty() && !n.as_str().eq("ALL")) {
+                if !catalog.body_part_names(creature_name, selection).iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                    unknown.push(format!("body part {}:{}", selection, name));
+                }
+            }
+        }
+        let defined_tissues = catalog.tissues_of(creature_name);
+        if !defined_tissues.is_empty() {
+            for tissue in tissues.into_iter().filter(|t| !t.is_empty() && !t.as_str().eq("ALL")) {
+                if !defined_tissues.iter().any(|t| t.eq_ignore_ascii_case(tissue)) {
+                    unknown.push(format!("tissue {}", tissue));
+                }
+            }
+        }
+
+        unknown
+    }
+
+    //item name field with a dropdown of the defined items of the equipment type
+    fn item_menu(ui: &mut Ui, item: &mut String, equipment: &EquipmentType, catalog: &RawCatalog) {
+        ui.text_edit_singleline(item);
+
+        let defined_items = catalog.items_of(equipment);
+        if !defined_items.is_empty() {
+            egui::ComboBox::from_id_source("defined_items")
+                .selected_text("Defined items")
+                .show_ui(ui, |ui| {
+                for defined_item in defined_items {
+                    ui.selectable_value(item, defined_item.name.clone(), &defined_item.name);
+                }
+            });
+        }
+
+        if !catalog.items.is_empty() && !item.is_empty() && !item.eq_ignore_ascii_case("ANY") && catalog.item(item).is_none() {
+            ui.colored_label(egui::Color32::YELLOW, "⚠")
+                .on_hover_text("Not defined in the item raws.");
+        }
+    }
+
     fn name(&self) -> String {
         match self {
             Self::Default => "(default)".to_string(),
@@ -3639,7 +5915,7 @@ impl Condition {
         Condition::TissueNotShaped,
         Condition::TissueSwap(String::new(), 0, String::new(), [0,0], None),
         Condition::ItemQuality(0),
-        Condition::UsePalette(Palette {name: String::new(), file_name: PathBuf::new(), default_index: 0, max_row: 255}, 0),
+        Condition::UsePalette(Palette {name: String::new(), file_name: PathBuf::new(), default_index: 0, max_row: 255, rows: Vec::new()}, 0),
         Condition::UseStandardPalette,
         Condition::ConditionBP(BodyPartType::None),
         Condition::LGConditionBP(BodyPartType::None),
@@ -3650,7 +5926,7 @@ impl Condition {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum State {
     #[default]
     Empty,
@@ -3784,7 +6060,7 @@ impl Ord for State {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum Caste {
     #[default]
     Female,
@@ -3811,9 +6087,25 @@ impl Caste {
             caste => Caste::Custom(caste.to_string()),
         }
     }
+
+    //castes the creature raws give a creature, other than MALE and FEMALE which are always offered
+    fn defined(creature_name: &str, catalog: &RawCatalog) -> Vec<Caste> {
+        catalog.castes_of(creature_name)
+            .into_iter()
+            .map(Caste::from)
+            .filter(|c| matches!(c, Caste::Custom(..)))
+            .collect()
+    }
+
+    fn warning(&self, ui: &mut Ui, creature_name: &str, catalog: &RawCatalog) {
+        if catalog.creature(creature_name).is_some_and(|c| !c.has_caste(&self.name())) {
+            ui.colored_label(egui::Color32::YELLOW, "⚠")
+                .on_hover_text(format!("{} has no caste {} in the creature raws.", creature_name, self.name()));
+        }
+    }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum MaterialType {
     #[default]
     None,
@@ -3850,13 +6142,13 @@ impl MaterialType {
     fn from(string: String) -> MaterialType {
         match string.as_str() {
             "INORGANIC" => MaterialType::Inorganic,
-            "COPPER" => MaterialType::Copper,
-            "SILVER" => MaterialType::Silver,
-            "BRONZE" => MaterialType::Bronze,
-            "BLACK_BRONZE" => MaterialType::BlackBronze,
-            "IRON" => MaterialType::Iron,
-            "STEEL" => MaterialType::Steel,
-            "ADAMANTINE" => MaterialType::Adamantine,
+            "COPPER" | "METAL:COPPER" => MaterialType::Copper,
+            "SILVER" | "METAL:SILVER" => MaterialType::Silver,
+            "BRONZE" | "METAL:BRONZE" => MaterialType::Bronze,
+            "BLACK_BRONZE" | "METAL:BLACK_BRONZE" => MaterialType::BlackBronze,
+            "IRON" | "METAL:IRON" => MaterialType::Iron,
+            "STEEL" | "METAL:STEEL" => MaterialType::Steel,
+            "ADAMANTINE" | "METAL:ADAMANTINE" => MaterialType::Adamantine,
             material_type => MaterialType::Custom(material_type.to_string()),
         }
     }
@@ -3877,7 +6169,7 @@ impl MaterialType {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum MaterialFlag {
     #[default]
     None,
@@ -4082,7 +6374,7 @@ impl MaterialFlag {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum ItemType {
     #[default]
     None,
@@ -4168,7 +6460,7 @@ impl ItemType {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum BodyPartType {
     #[default]
     None,
@@ -4228,6 +6520,26 @@ impl BodyPartType {
         (bp_type, errors)
     }
 
+    fn selection(&self) -> Option<(&str, &String)> {
+        match self {
+            BodyPartType::None => None,
+            BodyPartType::ByType(name) => Some(("BY_TYPE", name)),
+            BodyPartType::ByCategory(name) => Some(("BY_CATEGORY", name)),
+            BodyPartType::ByToken(name) => Some(("BY_TOKEN", name)),
+        }
+    }
+
+    //body part name field with a dropdown of the names the edited creature's body has
+    fn name_menu(ui: &mut Ui, name: &mut String, selection: &str, shared: &Shared) {
+        Condition::defined_name_menu(
+            ui,
+            name,
+            shared.catalog.body_part_names(&shared.creature_name, selection),
+            selection,
+            "Not a body part of the creature in the body raws."
+        );
+    }
+
     fn iterator() -> std::slice::Iter<'static, Self> {
         static BODYPARTTYPES: [BodyPartType; 3] = [
             BodyPartType::ByCategory(String::new()),
@@ -4238,7 +6550,7 @@ impl BodyPartType {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum BPAppMod {
     #[default]
     None,
@@ -4339,7 +6651,7 @@ impl BPAppMod {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum EquipmentType {
     #[default]
     None,
@@ -4400,7 +6712,7 @@ impl EquipmentType {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum Profession {
     #[default]
     Empty,
@@ -4481,352 +6793,1025 @@ impl Profession {
             Profession::Merchant,
             Profession::None,
         ];
-        PROFESSIONS.iter()
+        PROFESSIONS.iter()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SyndromeClass {
+    #[default]
+    Zombie,
+    Necromancer,
+    Vampcurse,
+    RaisedUndead,
+    DisturbedDead,
+    Ghoul,
+    Custom(String),
+}
+impl SyndromeClass {
+    fn name(&self) -> String {
+        match self {
+            Self::Zombie => "ZOMBIE".to_string(),
+            Self::Necromancer => "NECROMANCER".to_string(),
+            Self::Vampcurse => "VAMPCURSE".to_string(),
+            Self::RaisedUndead => "RAISED_UNDEAD".to_string(),
+            Self::DisturbedDead => "DISTURBED_DEAD".to_string(),
+            Self::Ghoul => "GHOUL".to_string(),
+            Self::Custom(syn_class) => {
+                syn_class.with_boundaries(&[Boundary::Space])
+                    .to_case(Case::UpperSnake)
+                    .to_string()
+            },
+        }
+    }
+
+    fn from(string: String) -> Self {
+        match string.as_str() {
+            "ZOMBIE" => Self::Zombie,
+            "NECROMANCER" => Self::Necromancer,
+            "VAMPCURSE" => Self::Vampcurse,
+            "RAISED_UNDEAD" => Self::RaisedUndead,
+            "DISTURBED_DEAD" => Self::DisturbedDead,
+            "GHOUL" => Self::Ghoul,
+            syn_class=> Self::Custom(syn_class.to_string()),
+        }
+    }
+
+    fn iterator() -> std::slice::Iter<'static, Self> {
+        static SYNDROMECLASSES: [SyndromeClass; 6] = [
+            SyndromeClass::Zombie,
+            SyndromeClass::Necromancer,
+            SyndromeClass::Vampcurse,
+            SyndromeClass::RaisedUndead,
+            SyndromeClass::DisturbedDead,
+            SyndromeClass::Ghoul,
+            //Custom
+        ];
+        SYNDROMECLASSES.iter()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Shaping {
+    #[default]
+    StandardHair,
+    StandardBeard,
+    StandardMoustache,
+    StandardSideburns,
+    CleanShaven,
+    NeatlyCombed,
+    Braided,
+    DoubleBraids,
+    PonyTails,
+    Custom(String),
+}
+impl Shaping {
+    fn name(&self) -> String {
+        match self {
+            Self::StandardHair => "STANDARD_HAIR_SHAPINGS".to_string(),
+            Self::StandardBeard => "STANDARD_BEARD_SHAPINGS".to_string(),
+            Self::StandardMoustache => "STANDARD_MOUSTACHE_SHAPINGS".to_string(),
+            Self::StandardSideburns => "STANDARD_SIDEBURNS_SHAPINGS".to_string(),
+            Self::CleanShaven => "CLEAN_SHAVEN".to_string(),
+            Self::NeatlyCombed => "NEATLY_COMBED".to_string(),
+            Self::Braided => "BRAIDED".to_string(),
+            Self::DoubleBraids => "DOUBLE_BRAIDS".to_string(),
+            Self::PonyTails => "PONY_TAILS".to_string(),
+            Self::Custom(shaping) => shaping.clone(),
+        }
+    }
+
+    fn from(string: String) -> Self {
+        match string.as_str() {
+            "STANDARD_HAIR_SHAPINGS" => Self::StandardHair,
+            "STANDARD_BEARD_SHAPINGS" => Self::StandardBeard,
+            "STANDARD_MOUSTACHE_SHAPINGS" => Self::StandardMoustache,
+            "STANDARD_SIDEBURNS_SHAPINGS" => Self::StandardSideburns,
+            "CLEAN_SHAVEN" => Self::CleanShaven,
+            "NEATLY_COMBED" => Self::NeatlyCombed,
+            "BRAIDED" => Self::Braided,
+            "DOUBLE_BRAIDS" => Self::DoubleBraids,
+            "PONY_TAILS" => Self::PonyTails,
+            shaping => Self::Custom(shaping.to_string()),
+        }
+    }
+
+    fn iterator() -> std::slice::Iter<'static, Self> {
+        static SHAPINGS: [Shaping; 9] = [
+            Shaping::StandardHair,
+            Shaping::StandardBeard,
+            Shaping::StandardMoustache,
+            Shaping::StandardSideburns,
+            Shaping::CleanShaven,
+            Shaping::NeatlyCombed,
+            Shaping::Braided,
+            Shaping::DoubleBraids,
+            Shaping::PonyTails,
+        ];
+        SHAPINGS.iter()
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub enum SyndromeClass {
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Color {
     #[default]
-    Zombie,
-    Necromancer,
-    Vampcurse,
-    RaisedUndead,
-    DisturbedDead,
-    Ghoul,
+    None,
     Custom(String),
 }
-impl SyndromeClass {
+impl Color {
     fn name(&self) -> String {
         match self {
-            Self::Zombie => "ZOMBIE".to_string(),
-            Self::Necromancer => "NECROMANCER".to_string(),
-            Self::Vampcurse => "VAMPCURSE".to_string(),
-            Self::RaisedUndead => "RAISED_UNDEAD".to_string(),
-            Self::DisturbedDead => "DISTURBED_DEAD".to_string(),
-            Self::Ghoul => "GHOUL".to_string(),
-            Self::Custom(syn_class) => {
-                syn_class.with_boundaries(&[Boundary::Space])
+            Self::None => "(none)".to_string(),
+            Self::Custom(color) => {
+                color
+                    .with_boundaries(&[Boundary::Space])
                     .to_case(Case::UpperSnake)
                     .to_string()
-            },
+            }
         }
     }
 
     fn from(string: String) -> Self {
         match string.as_str() {
-            "ZOMBIE" => Self::Zombie,
-            "NECROMANCER" => Self::Necromancer,
-            "VAMPCURSE" => Self::Vampcurse,
-            "RAISED_UNDEAD" => Self::RaisedUndead,
-            "DISTURBED_DEAD" => Self::DisturbedDead,
-            "GHOUL" => Self::Ghoul,
-            syn_class=> Self::Custom(syn_class.to_string()),
+            string => Self::Custom(string
+                .with_boundaries(&[Boundary::Space])
+                .to_case(Case::UpperSnake)
+                .to_string())
+        }
+    }
+
+    fn swatch(ui: &mut Ui, rgb: [u8; 3]) {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+        ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgb(rgb[0], rgb[1], rgb[2]));
+    }
+
+    //color token field with a swatch and a dropdown of the descriptor colors, only changing the color when the user does
+    fn menu(&mut self, ui: &mut Ui, catalog: &RawCatalog) {
+        let mut name = match self {
+            Color::None => String::new(),
+            Color::Custom(name) => name.clone(),
+        };
+
+        ui.horizontal(|ui| {
+            match catalog.color(&name) {
+                Some(color) => Color::swatch(ui, color.rgb),
+                None => {
+                    ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                },
+            }
+
+            let mut changed = ui.text_edit_singleline(&mut name).changed();
+
+            if !catalog.colors.is_empty() {
+                let mut colors: Vec<&logic::catalog::ColorDefinition> = catalog.colors.iter().collect();
+                colors.sort_by(|a, b| a.name.cmp(&b.name));
+
+                egui::ComboBox::from_id_source("defined_colors")
+                    .selected_text("Defined colors")
+                    .show_ui(ui, |ui| {
+                    for color in colors {
+                        ui.horizontal(|ui| {
+                            Color::swatch(ui, color.rgb);
+                            changed |= ui.selectable_value(&mut name, color.name.clone(), &color.name).changed();
+                        });
+                    }
+                });
+
+                if !name.is_empty() && catalog.color(&name).is_none() {
+                    ui.colored_label(egui::Color32::YELLOW, "⚠")
+                        .on_hover_text("Not defined in the descriptor color raws.");
+                }
+            }
+
+            //an emptied field is no color rather than an empty token
+            if changed {
+                *self = if name.is_empty() { Color::None } else { Color::Custom(name.clone()) };
+            }
+        });
+    }
+}
+
+/// A STATUE_CREATURE_GRAPHICS or STATUE_CREATURE_CASTE_GRAPHICS block with one tile per state.
+///
+/// DF draws statues 1 tile wide and 2 tall, other shapes are reported by the `statue-shape` lint rule.
+/// ```
+/// # use df_texture_helper::*;
+/// let raw = "[STATUE_CREATURE_CASTE_GRAPHICS:DWARF:FEMALE]\n\t[DEFAULT:STATUES_DWARF:0:0:0:1]\n\t[CORPSE:STATUES_DWARF:1:0]\n";
+/// let raw_lines: Vec<String> = raw.split_inclusive('\n').map(|l| l.to_string()).collect();
+/// let tokens = Token::tokenize(&raw_lines, "graphics_creatures_dwarf_statue.txt".as_ref());
+///
+/// let (statue, errors) = Statue::read(tokens, raw_lines, "graphics_creatures_dwarf_statue.txt".as_ref());
+///
+/// assert_eq!(Some(Caste::Female), statue.caste);
+/// assert_eq!(2, statue.tiles.len());
+/// assert_eq!(Some([0, 1]), statue.tiles[0].large_coords);
+/// assert_eq!(None, statue.tiles[1].large_coords);
+/// assert!(errors.is_empty());
+/// assert!(statue.tiles[1].shape_warning().is_some()); //the second tile is only 1x1
+/// assert_eq!(raw, statue.display());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Statue {
+    pub creature_name: String,
+    pub caste: Option<Caste>,
+    #[serde(default)]
+    pub tiles: Vec<StatueTile>,
+    #[serde(default)]
+    pub other_tokens: Vec<Vec<String>>, //tokens in the block that aren't tile assignments, kept as they were read
+}
+impl RAW for Statue {
+    fn new() -> Self {
+        Self {
+            creature_name: "(new)".to_string(),
+            caste: None,
+            tiles: Vec::new(),
+            other_tokens: Vec::new(),
+        }
+    }
+    
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut statue = Statue::new();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        let buffer_len = buffer.len();        
+
+        for (i_line, line_vec) in buffer.iter().enumerate() {
+            let len = line_vec.len();
+
+            match line_vec[0].as_str() {
+                "STATUE_CREATURE_GRAPHICS" => {
+                    if len >= 2 {
+                        statue.creature_name = line_vec[1].clone();
+                    } else {
+                        index_err!(i_line, buffer_len, len, 2, errors);
+                    }
+                },
+                "STATUE_CREATURE_CASTE_GRAPHICS" => {
+                    if len >= 3 {
+                        statue.creature_name = line_vec[1].clone();
+                        statue.caste = Some(Caste::from(line_vec[2].clone()));
+                    } else {
+                        index_err!(i_line, buffer_len, len, 3, errors);
+                    }
+                },
+                other => {
+                    let is_number = |i: usize| line_vec.get(i).is_some_and(|a| a.parse::<u32>().is_ok());
+                    let known = !matches!(State::from(other.to_string()), State::Custom(_));
+
+                    if !known && !(is_number(2) && is_number(3)) {
+                        //not a tile assignment this tool knows about
+                        statue.other_tokens.push(line_vec.args.clone());
+                        continue;
+                    }
+
+                    if len < 4 {
+                        index_err!(i_line, buffer_len, len, 4, errors);
+                        continue;
+                    }
+
+                    let (x,y) = 
+                        (buffer_err_wrap!(line_vec[2].parse::<u32>(), i_line, buffer_len, 2..=2, 0, errors),
+                        buffer_err_wrap!(line_vec[3].parse::<u32>(), i_line, buffer_len, 3..=3, 0, errors));
+                    let mut statue_tile = StatueTile {
+                        state: State::from(line_vec[0].clone()),
+                        tile_name: line_vec[1].clone(),
+                        coords: [x, y],
+                        large_coords: None,
+                        extra_args: Vec::new(),
+                    };
+
+                    if is_number(4) && is_number(5) {
+                        let (x_l,y_l) = 
+                            (buffer_err_wrap!(line_vec[4].parse::<u32>(), i_line, buffer_len, 4..=4, 0, errors),
+                            buffer_err_wrap!(line_vec[5].parse::<u32>(), i_line, buffer_len, 5..=5, 0, errors));
+                        statue_tile.large_coords = Some([x_l.abs_diff(x), y_l.abs_diff(y)]);
+                        statue_tile.extra_args = line_vec[6..].to_vec();
+                    } else {
+                        statue_tile.extra_args = line_vec[4..].to_vec();
+                    }
+                    statue.tiles.push(statue_tile);
+                }
+            }
+        }
+        (statue, errors)
+    }
+
+    fn display(&self) -> String {
+        let mut out;
+
+        if let Some(caste) = &self.caste {
+            out = format!("[STATUE_CREATURE_CASTE_GRAPHICS:{}:{}]\n",
+                self.creature_name
+                .with_boundaries(&[Boundary::Space])
+                .to_case(Case::UpperSnake)
+                .to_string(),
+                caste.name()
+            );
+        } else {
+            out = format!("\n[STATUE_CREATURE_GRAPHICS:{}]\n",
+                self.creature_name
+                .with_boundaries(&[Boundary::Space])
+                .to_case(Case::UpperSnake)
+                .to_string()
+            );
+        }
+
+        for tile in &self.tiles {
+            out.push_str(&tile.display());
+        }
+
+        for token in &self.other_tokens {
+            out.push_str(&format!("\t[{}]\n", token.join(":")));
+        }
+
+        if self.caste.is_none() {
+            out.push('\n');
+        }
+
+        out
+    }
+}
+impl Menu for Statue {
+    fn menu(&mut self, ui: &mut Ui, shared: &mut Shared) {
+        let caste_opt = &mut self.caste;
+        let tile_names: Vec<&String> = shared.tile_page_info.keys().collect();
+
+        ui.horizontal(|ui| {
+            ui.label("Creature:");
+            Creature::name_menu(ui, &mut self.creature_name, &shared.catalog);
+        });
+
+        let mut caste_bool = caste_opt.is_some();
+
+        ui.checkbox(&mut caste_bool, "Caste?");
+
+        if let Some(caste) = caste_opt {
+            egui::ComboBox::from_label("")
+                .selected_text(caste.name())
+                .show_ui(ui, |ui| {
+                ui.selectable_value(caste, Caste::Female, "FEMALE");
+                ui.selectable_value(caste, Caste::Male, "MALE");
+                for c in Caste::defined(&self.creature_name, &shared.catalog) {
+                    ui.selectable_value(caste, c.clone(), c.name());
+                }
+                for c in &shared.creature_shared.castes {
+                    ui.selectable_value(caste, c.clone(), c.name());
+                }
+                ui.selectable_value(caste, Caste::Custom(String::new()), "(custom)");
+            });
+            if let Caste::Custom(cust_caste) = caste {
+                ui.label("Custom caste:");
+                ui.text_edit_singleline(cust_caste);
+            }
+            caste.warning(ui, &self.creature_name, &shared.catalog);
+        }
+
+        if caste_bool {
+            caste_opt.get_or_insert(Caste::Female);
+        } else {
+            caste_opt.take();
+        }
+
+        ui.add_space(PADDING);
+        let mut remove = None;
+        for (i_tile, tile) in self.tiles.iter_mut().enumerate() {
+            ui.push_id(i_tile, |ui| {
+                ui.horizontal(|ui| {
+                    let state = &mut tile.state;
+                    egui::ComboBox::from_label("State")
+                        .selected_text(state.name())
+                        .show_ui(ui, |ui| {
+                        for s in State::iterator() {
+                            ui.selectable_value(state,  s.clone(), s.name());
+                        }
+                        for s in &shared.creature_shared.states {
+                            ui.selectable_value(state,  s.clone(), s.name());
+                        }
+                        ui.selectable_value(state, State::Custom(String::new()), "(custom)");
+                    });
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(i_tile);
+                    }
+                });
+                if let State::Custom(cust_state) = &mut tile.state {
+                    ui.label("Custom state:");
+                    ui.text_edit_singleline(cust_state);
+                    ui.hyperlink_to("Custom states that may work.", "https://dwarffortresswiki.org/index.php/Graphics_token#Layered_Conditions");
+                }
+
+                egui::ComboBox::from_label("TilePage")
+                    .selected_text(&tile.tile_name)
+                    .show_ui(ui, |ui| {
+                    for &t in &tile_names {
+                        ui.selectable_value(&mut tile.tile_name, t.clone(), t);
+                    }
+                    ui.selectable_value(&mut tile.tile_name, String::new(), "Custom");
+                });
+                if !tile_names.contains(&&tile.tile_name) {
+                    ui.label("Custom tile name:");
+                    ui.text_edit_singleline(&mut tile.tile_name);
+                }
+
+                let max_coords = TilePageInfo::slider_max(shared.tile_page(&tile.tile_name));
+
+                let mut large = tile.large_coords.is_some();
+                ui.checkbox(&mut large, "Large image");
+                if large {
+                    let [x2, y2] = tile.large_coords.get_or_insert([0, 1]);
+                    let [x1, y1] = &mut tile.coords;
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Slider::new(x1, 0..=max_coords[0].saturating_sub(1).saturating_sub(*x2)).prefix("X: "));
+                        ui.add(egui::Slider::new(x2, 0..=2).prefix("X + "));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Slider::new(y1, 0..=max_coords[1].saturating_sub(1).saturating_sub(*y2)).prefix("Y: "));
+                        ui.add(egui::Slider::new(y2, 0..=1).prefix("Y + "));
+                    });
+                } else {
+                    tile.large_coords = None;
+                    let [x, y] = &mut tile.coords;
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Slider::new(x, 0..=max_coords[0].saturating_sub(1)).prefix("X: "));
+                        ui.add(egui::Slider::new(y, 0..=max_coords[1].saturating_sub(1)).prefix("Y: "));
+                    });
+                }
+                TilePageInfo::bounds_warning(ui, shared.tile_page(&tile.tile_name), &mut tile.coords, &mut tile.large_coords);
+
+                if let Some(e) = tile.shape_warning() {
+                    ui.colored_label(egui::Color32::YELLOW, e.to_string());
+                }
+            });
+            ui.add_space(PADDING);
+        }
+        if let Some(i_tile) = remove {
+            self.tiles.remove(i_tile);
+        }
+
+        if ui.small_button("Add Tile").clicked() {
+            let tile_name = self.tiles.last().map(|t| t.tile_name.clone()).unwrap_or_default();
+            self.tiles.push(StatueTile { tile_name, ..StatueTile::new() });
+        }
+        ui.label("Note: only DEFAULT is known to work v51.01");
+
+        ui.add_space(PADDING);
+        ui.label("Preview:");
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            ui.add(egui::Label::new(self.display()).wrap(false));
+        });
+    }
+}
+
+/// A state line in a statue block, `[STATE:TILE_PAGE:X1:Y1:X2:Y2]` or `[STATE:TILE_PAGE:X:Y]`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatueTile {
+    pub state: State,
+    pub tile_name: String,
+    pub coords: [u32; 2],
+    pub large_coords: Option<[u32; 2]>, //extent past coords, [0, 1] for the usual 1x2 statue
+    pub extra_args: Vec<String>,        //arguments after the coordinates, kept as they were read
+}
+impl StatueTile {
+    pub fn new() -> Self {
+        Self {
+            state: State::Default,
+            tile_name: String::new(),
+            coords: [0, 0],
+            large_coords: Some([0, 1]),
+            extra_args: Vec::new(),
         }
     }
 
-    fn iterator() -> std::slice::Iter<'static, Self> {
-        static SYNDROMECLASSES: [SyndromeClass; 6] = [
-            SyndromeClass::Zombie,
-            SyndromeClass::Necromancer,
-            SyndromeClass::Vampcurse,
-            SyndromeClass::RaisedUndead,
-            SyndromeClass::DisturbedDead,
-            SyndromeClass::Ghoul,
-            //Custom
+    fn display(&self) -> String {
+        let mut args = vec![
+            self.state.name(),
+            self.tile_name.with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
+                .to_case(Case::UpperSnake),
+            self.coords[0].to_string(),
+            self.coords[1].to_string(),
         ];
-        SYNDROMECLASSES.iter()
+        if let Some([x2, y2]) = self.large_coords {
+            args.push((self.coords[0].saturating_add(x2)).to_string());
+            args.push((self.coords[1].saturating_add(y2)).to_string());
+        }
+        args.extend(self.extra_args.iter().cloned());
+
+        format!("\t[{}]\n", args.join(":"))
+    }
+
+    /// Warning if the tile isn't the 1x2 shape DF draws statues with.
+    pub fn shape_warning(&self) -> Option<DFGHError> {
+        let [x2, y2] = self.large_coords.unwrap_or([0, 0]);
+
+        if [x2, y2] != [0, 1] {
+            Some(DFGHError::StatueShapeWarning(x2 + 1, y2 + 1))
+        } else {
+            None
+        }
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub enum Shaping {
-    #[default]
-    StandardHair,
-    StandardBeard,
-    StandardMoustache,
-    StandardSideburns,
-    CleanShaven,
-    NeatlyCombed,
-    Braided,
-    DoubleBraids,
-    PonyTails,
+/// A PLANT_GRAPHICS block assigning tiles to the stages a plant is drawn in.
+///
+/// ```
+/// # use df_texture_helper::*;
+/// let raw = "[PLANT_GRAPHICS:STRAWBERRY]\n\t[SHRUB:PLANT_STANDARD:0:3]\n\t[PICKED:PLANT_STANDARD:6:1]\n\t[CROP_SPROUT:PLANT_CROPS:2:0]\n";
+/// let raw_lines: Vec<String> = raw.split_inclusive('\n').map(|l| l.to_string()).collect();
+/// let tokens = Token::tokenize(&raw_lines, "graphics_plants.txt".as_ref());
+///
+/// let (plant, errors) = Plant::read(tokens, raw_lines, "graphics_plants.txt".as_ref());
+///
+/// assert!(errors.is_empty());
+/// assert_eq!("STRAWBERRY", plant.name);
+/// assert_eq!(PlantStage::Picked, plant.tiles[1].stage);
+/// assert_eq!([2, 0], plant.tiles[2].coords);
+/// assert_eq!(format!("{}\n", raw), plant.display());
+/// ```
+/// Malformed tiles are reported, and kept where they were with the tokens this tool doesn't know:
+/// ```
+/// # use df_texture_helper::*;
+/// let raw = "[PLANT_GRAPHICS:WHEAT]\n\t[GROWTH:FRUIT]\n\t[CROP:PLANT_CROPS:0:x]\n\t[SEED:PLANT_STANDARD:1:0]\n\t[PICKED:PLANT_STANDARD]\n";
+/// let raw_lines: Vec<String> = raw.split_inclusive('\n').map(|l| l.to_string()).collect();
+/// let tokens = Token::tokenize(&raw_lines, "graphics_plants.txt".as_ref());
+///
+/// let (plant, errors) = Plant::read(tokens, raw_lines, "graphics_plants.txt".as_ref());
+///
+/// assert_eq!(2, errors.len());
+/// assert_eq!(1, plant.tiles.len());
+/// assert_eq!(format!("{}\n", raw), plant.display());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Plant {
+    pub name: String,
+    pub tiles: Vec<PlantTile>,
+    pub other_tokens: Vec<(usize, Vec<String>)>, //tokens in the block that aren't tile assignments, with the number of tiles read before them
 }
-impl Shaping {
-    fn name(&self) -> String {
-        match self {
-            Self::StandardHair => "STANDARD_HAIR_SHAPINGS".to_string(),
-            Self::StandardBeard => "STANDARD_BEARD_SHAPINGS".to_string(),
-            Self::StandardMoustache => "STANDARD_MOUSTACHE_SHAPINGS".to_string(),
-            Self::StandardSideburns => "STANDARD_SIDEBURNS_SHAPINGS".to_string(),
-            Self::CleanShaven => "CLEAN_SHAVEN".to_string(),
-            Self::NeatlyCombed => "NEATLY_COMBED".to_string(),
-            Self::Braided => "BRAIDED".to_string(),
-            Self::DoubleBraids => "DOUBLE_BRAIDS".to_string(),
-            Self::PonyTails => "PONY_TAILS".to_string(),
+impl RAW for Plant {
+    fn new() -> Self {
+        Self {
+            name: "(new)".to_string(),
+            tiles: Vec::new(),
+            other_tokens: Vec::new(),
         }
     }
 
-    fn from(string: String) -> Self {
-        match string.as_str() {
-            "STANDARD_HAIR_SHAPINGS" => Self::StandardHair,
-            "STANDARD_BEARD_SHAPINGS" => Self::StandardBeard,
-            "STANDARD_MOUSTACHE_SHAPINGS" => Self::StandardMoustache,
-            "STANDARD_SIDEBURNS_SHAPINGS" => Self::StandardSideburns,
-            "CLEAN_SHAVEN" => Self::CleanShaven,
-            "NEATLY_COMBED" => Self::NeatlyCombed,
-            "BRAIDED" => Self::Braided,
-            "DOUBLE_BRAIDS" => Self::DoubleBraids,
-            "PONY_TAILS" => Self::PonyTails,
-            _ => Self::StandardHair,
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut plant = Plant::new();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        let buffer_len = buffer.len();
+
+        for (i_line, line_vec) in buffer.iter().enumerate() {
+            let len = line_vec.len();
+
+            match line_vec[0].as_str() {
+                "PLANT_GRAPHICS" => {
+                    if len >= 2 {
+                        plant.name = line_vec[1].clone();
+                    } else {
+                        index_err!(i_line, buffer_len, len, 2, errors);
+                    }
+                },
+                other => {
+                    let stage = PlantStage::from(other.to_string());
+                    let is_coords = len == 4 && line_vec[2].parse::<u32>().is_ok() && line_vec[3].parse::<u32>().is_ok();
+
+                    if is_coords {
+                        plant.tiles.push(PlantTile {
+                            stage,
+                            tile_name: line_vec[1].clone(),
+                            coords: [line_vec[2].parse().unwrap_or_default(), line_vec[3].parse().unwrap_or_default()],
+                        });
+                        continue;
+                    }
+
+                    //a malformed tile is reported but kept as it was, like tokens this tool doesn't know
+                    if !matches!(stage, PlantStage::Custom(_)) {
+                        if len == 4 {
+                            buffer_err_wrap!(line_vec[2].parse::<u32>(), i_line, buffer_len, 2..=2, 0, errors);
+                            buffer_err_wrap!(line_vec[3].parse::<u32>(), i_line, buffer_len, 3..=3, 0, errors);
+                        } else {
+                            index_err!(i_line, buffer_len, len, 4, errors);
+                        }
+                    }
+                    plant.other_tokens.push((plant.tiles.len(), line_vec.args.clone()));
+                },
+            }
         }
+
+        (plant, errors)
     }
 
-    fn iterator() -> std::slice::Iter<'static, Self> {
-        static SHAPINGS: [Shaping; 9] = [
-            Shaping::StandardHair,
-            Shaping::StandardBeard,
-            Shaping::StandardMoustache,
-            Shaping::StandardSideburns,
-            Shaping::CleanShaven,
-            Shaping::NeatlyCombed,
-            Shaping::Braided,
-            Shaping::DoubleBraids,
-            Shaping::PonyTails,
-        ];
-        SHAPINGS.iter()
+    fn display(&self) -> String {
+        let mut out = format!("[PLANT_GRAPHICS:{}]\n",
+            self.name
+            .with_boundaries(&[Boundary::Space])
+            .to_case(Case::UpperSnake)
+        );
+
+        //other tokens go back in front of the tile they were read before
+        let push_other_tokens = |out: &mut String, before: Option<usize>| {
+            for (_, token) in self.other_tokens.iter().filter(|(i_tile, _)| before.map_or(*i_tile >= self.tiles.len(), |i| *i_tile == i)) {
+                out.push_str(&format!("\t[{}]\n", token.join(":")));
+            }
+        };
+
+        for (i_tile, tile) in self.tiles.iter().enumerate() {
+            push_other_tokens(&mut out, Some(i_tile));
+            out.push_str(&format!(
+                "\t[{}:{}:{}:{}]\n",
+                tile.stage.name(),
+                tile.tile_name.with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
+                    .to_case(Case::UpperSnake),
+                tile.coords[0],
+                tile.coords[1],
+            ));
+        }
+        push_other_tokens(&mut out, None);
+
+        out.push('\n');
+
+        out
     }
 }
+impl Menu for Plant {
+    fn menu(&mut self, ui: &mut Ui, shared: &mut Shared) {
+        let tile_names: Vec<&String> = shared.tile_page_info.keys().collect();
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub enum Color {
+        ui.separator();
+        ui.text_edit_singleline(&mut self.name);
+
+        ui.add_space(PADDING);
+        let mut remove = None;
+        for (i_tile, tile) in self.tiles.iter_mut().enumerate() {
+            ui.push_id(i_tile, |ui| {
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_label("Stage")
+                        .selected_text(tile.stage.name())
+                        .show_ui(ui, |ui| {
+                        for s in PlantStage::iterator() {
+                            ui.selectable_value(&mut tile.stage, s.clone(), s.name());
+                        }
+                        ui.selectable_value(&mut tile.stage, PlantStage::Custom(String::new()), "(custom)");
+                    });
+                    if let PlantStage::Custom(custom_stage) = &mut tile.stage {
+                        ui.text_edit_singleline(custom_stage);
+                    }
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(i_tile);
+                    }
+                });
+
+                egui::ComboBox::from_label("TilePage")
+                    .selected_text(&tile.tile_name)
+                    .show_ui(ui, |ui| {
+                    for &t in &tile_names {
+                        ui.selectable_value(&mut tile.tile_name, t.clone(), t);
+                    }
+                    ui.selectable_value(&mut tile.tile_name, String::new(), "Custom");
+                });
+                if !tile_names.contains(&&tile.tile_name) {
+                    ui.label("Custom tile name:");
+                    ui.text_edit_singleline(&mut tile.tile_name);
+                }
+
+                let max_coords = TilePageInfo::slider_max(shared.tile_page(&tile.tile_name));
+                let [x, y] = &mut tile.coords;
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(x, 0..=max_coords[0].saturating_sub(1)).prefix("X: "));
+                    ui.add(egui::Slider::new(y, 0..=max_coords[1].saturating_sub(1)).prefix("Y: "));
+                });
+                TilePageInfo::bounds_warning(ui, shared.tile_page(&tile.tile_name), &mut tile.coords, &mut None);
+            });
+            ui.add_space(PADDING);
+        }
+        if let Some(i_tile) = remove {
+            self.tiles.remove(i_tile);
+        }
+
+        if ui.small_button("Add Tile").clicked() {
+            let tile_name = self.tiles.last().map(|t| t.tile_name.clone()).unwrap_or_default();
+            let stage = PlantStage::iterator()
+                .find(|s| !self.tiles.iter().any(|t| t.stage.eq(s)))
+                .cloned()
+                .unwrap_or_default();
+            self.tiles.push(PlantTile { stage, tile_name, coords: [0, 0] });
+        }
+
+        ui.add_space(PADDING);
+        ui.label("Preview:");
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            ui.add(egui::Label::new(self.display()).wrap(false));
+        });
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlantTile {
+    pub stage: PlantStage,
+    pub tile_name: String,
+    pub coords: [u32; 2],
+}
+
+/// What a plant tile is drawn for, the first argument of each token in a PLANT_GRAPHICS block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlantStage {
     #[default]
-    None,
+    Shrub,
+    ShrubDead,
+    ShrubFlower,
+    ShrubFruit,
+    ShrubSeed,
+    Picked,
+    DeadPicked,
+    Seed,
+    Crop,
+    CropSprout,
+    CropL,
+    CropM,
+    CropR,
+    Sapling,
+    DeadSapling,
     Custom(String),
 }
-impl Color {
-    fn name(&self) -> String {
+impl PlantStage {
+    pub fn name(&self) -> String {
         match self {
-            Self::None => "(none)".to_string(),
-            Self::Custom(color) => {
-                color
-                    .with_boundaries(&[Boundary::Space])
+            Self::Shrub => "SHRUB".to_string(),
+            Self::ShrubDead => "SHRUB_DEAD".to_string(),
+            Self::ShrubFlower => "SHRUB_FLOWER".to_string(),
+            Self::ShrubFruit => "SHRUB_FRUIT".to_string(),
+            Self::ShrubSeed => "SHRUB_SEED".to_string(),
+            Self::Picked => "PICKED".to_string(),
+            Self::DeadPicked => "DEAD_PICKED".to_string(),
+            Self::Seed => "SEED".to_string(),
+            Self::Crop => "CROP".to_string(),
+            Self::CropSprout => "CROP_SPROUT".to_string(),
+            Self::CropL => "CROP_L".to_string(),
+            Self::CropM => "CROP_M".to_string(),
+            Self::CropR => "CROP_R".to_string(),
+            Self::Sapling => "SAPLING".to_string(),
+            Self::DeadSapling => "DEAD_SAPLING".to_string(),
+            Self::Custom(name) => {
+                name.with_boundaries(&[Boundary::Space])
                     .to_case(Case::UpperSnake)
                     .to_string()
-            }
+            },
         }
     }
 
     fn from(string: String) -> Self {
-        match string.as_str() {
-            string => Self::Custom(string
-                .with_boundaries(&[Boundary::Space])
-                .to_case(Case::UpperSnake)
-                .to_string())
+        match string.to_uppercase().as_str() {
+            "SHRUB" => Self::Shrub,
+            "SHRUB_DEAD" => Self::ShrubDead,
+            "SHRUB_FLOWER" => Self::ShrubFlower,
+            "SHRUB_FRUIT" => Self::ShrubFruit,
+            "SHRUB_SEED" => Self::ShrubSeed,
+            "PICKED" => Self::Picked,
+            "DEAD_PICKED" => Self::DeadPicked,
+            "SEED" => Self::Seed,
+            "CROP" => Self::Crop,
+            "CROP_SPROUT" => Self::CropSprout,
+            "CROP_L" => Self::CropL,
+            "CROP_M" => Self::CropM,
+            "CROP_R" => Self::CropR,
+            "SAPLING" => Self::Sapling,
+            "DEAD_SAPLING" => Self::DeadSapling,
+            other => Self::Custom(other.to_string()),
         }
     }
+
+    fn iterator() -> std::slice::Iter<'static, Self> {
+        static STAGES: [PlantStage; 15] = [
+            PlantStage::Shrub,
+            PlantStage::ShrubDead,
+            PlantStage::ShrubFlower,
+            PlantStage::ShrubFruit,
+            PlantStage::ShrubSeed,
+            PlantStage::Picked,
+            PlantStage::DeadPicked,
+            PlantStage::Seed,
+            PlantStage::Crop,
+            PlantStage::CropSprout,
+            PlantStage::CropL,
+            PlantStage::CropM,
+            PlantStage::CropR,
+            PlantStage::Sapling,
+            PlantStage::DeadSapling,
+        ];
+        STAGES.iter()
+    }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct Statue {
-    pub creature_name: String,
-    pub state: State,
-    pub tile_name: String,
-    pub coords: [u32; 2],
-    pub large_coords: Option<[u32; 2]>,
-    pub caste: Option<Caste>,
+/// An item or tile graphics block such as WEAPON_GRAPHICS, HELM_GRAPHICS or TOOL_GRAPHICS.
+///
+/// ```
+/// # use df_texture_helper::*;
+/// let raw = "[WEAPON_GRAPHICS:ITEM_WEAPON_AXE_BATTLE]\n\t[DEFAULT:ITEM_WEAPONS:0:0]\n\t[LARGE_IMAGE:ITEM_WEAPONS_LARGE:2:0:3:1]\n";
+/// let raw_lines: Vec<String> = raw.split_inclusive('\n').map(|l| l.to_string()).collect();
+/// let tokens = Token::tokenize(&raw_lines, "graphics_items.txt".as_ref());
+///
+/// let (weapon, errors) = TileGraphic::read(tokens, raw_lines, "graphics_items.txt".as_ref());
+///
+/// assert!(errors.is_empty());
+/// assert_eq!(ItemGraphicsKind::Weapon, weapon.kind);
+/// assert_eq!("ITEM_WEAPON_AXE_BATTLE", weapon.name);
+/// assert_eq!(Some([1, 1]), weapon.tiles[1].large_coords);
+/// assert_eq!(format!("{}\n", raw), weapon.display());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TileGraphic {
+    pub kind: ItemGraphicsKind,
+    pub name: String,                   //item or object id
+    pub header_args: Vec<String>,       //any arguments on the block token after the id
+    pub tiles: Vec<ItemTile>,
+    pub other_tokens: Vec<Vec<String>>, //tokens in the block that aren't tile assignments, kept as they were read
 }
-impl RAW for Statue {
+impl RAW for TileGraphic {
     fn new() -> Self {
         Self {
-            creature_name: "(new)".to_string(),
-            state: State::Default,
-            tile_name: String::new(),
-            coords: [0, 0],
-            large_coords: None,
-            caste: None,
+            kind: ItemGraphicsKind::Tile,
+            name: "(new)".to_string(),
+            header_args: Vec::new(),
+            tiles: Vec::new(),
+            other_tokens: Vec::new(),
         }
     }
-    
-    fn read(buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, _path: &PathBuf) -> (Self, Vec<DFGHError>) {
-        let mut statue = Statue::new();
+
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut tile_graphic = TileGraphic::new();
         let mut errors: Vec<DFGHError> = Vec::new();
-        let buffer_len = buffer.len();        
+        let buffer_len = buffer.len();
 
         for (i_line, line_vec) in buffer.iter().enumerate() {
             let len = line_vec.len();
 
-            match line_vec[0].as_str() {
-                "STATUE_CREATURE_GRAPHICS" => {
+            if i_line == 0 {
+                if let Some(kind) = ItemGraphicsKind::from(line_vec[0].clone()) {
                     if len >= 2 {
-                        statue.creature_name = line_vec[1].clone();
+                        tile_graphic.kind = kind;
+                        tile_graphic.name = line_vec[1].clone();
+                        tile_graphic.header_args = line_vec[2..].to_vec();
                     } else {
                         index_err!(i_line, buffer_len, len, 2, errors);
                     }
-                },
-                "STATUE_CREATURE_CASTE_GRAPHICS" => {
-                    if len >= 3 {
-                        statue.creature_name = line_vec[1].clone();
-                        statue.caste = Some(Caste::from(line_vec[2].clone()));
-                    } else {
-                        index_err!(i_line, buffer_len, len, 3, errors);
-                    }
-                },
-                other => {
-                    match State::from(other.to_string()) {
-                        State::Custom(_) => {},
-                        _ => {
-                            let mut reduced_line = line_vec.clone();
-                            reduced_line.retain(|l| l.ne("AS_IS"));
-                            let reduced_len = reduced_line.len();
-                
-                            if reduced_len > 3 {//don't false index error on caste statues 
-                                if reduced_len == 6 {
-                                    let (x,y) = 
-                                        (buffer_err_wrap!(line_vec[2].parse::<u32>(), i_line, buffer_len, 2..=2, 0, errors),
-                                        buffer_err_wrap!(line_vec[3].parse::<u32>(), i_line, buffer_len, 3..=3, 0, errors));
-                                    let (x_l,y_l) = 
-                                        (buffer_err_wrap!(line_vec[4].parse::<u32>(), i_line, buffer_len, 4..=4, 0, errors),
-                                        buffer_err_wrap!(line_vec[5].parse::<u32>(), i_line, buffer_len, 5..=5, 0, errors));
-                                    statue.state = State::from(line_vec[0].clone());
-                                    statue.tile_name = reduced_line[1].clone();
-                                    statue.coords = [x, y];
-                                    statue.large_coords = Some([x_l.abs_diff(x), y_l.abs_diff(y)]);
-                                }
-                                break
-                            } else {
-                                index_err!(i_line, buffer_len, len, 6, errors);
-                            }
-                        }
-                    }
+                    continue;
                 }
             }
-        }
-        (statue, errors)
-    }
 
-    fn display(&self) -> String {
-        let mut out;
-        let [x2, y2] = self.large_coords.unwrap_or([0, 1]);
+            let is_number = |i: usize| line_vec.get(i).is_some_and(|a| a.parse::<u32>().is_ok());
+            let known = tile_graphic.kind.states().contains(&line_vec[0].as_str());
 
-        if let Some(caste) = &self.caste {
-            out = format!("[STATUE_CREATURE_CASTE_GRAPHICS:{}:{}]\n",
-                self.creature_name
-                .with_boundaries(&[Boundary::Space])
-                .to_case(Case::UpperSnake)
-                .to_string(),
-                caste.name()
-            );
-        } else {
-            out = format!("\n[STATUE_CREATURE_GRAPHICS:{}]\n",
-                self.creature_name
-                .with_boundaries(&[Boundary::Space])
-                .to_case(Case::UpperSnake)
-                .to_string()
-            );
+            if !known && !(is_number(2) && is_number(3)) {
+                //not a tile assignment this tool knows about
+                tile_graphic.other_tokens.push(line_vec.args.clone());
+                continue;
+            }
+
+            if line_vec[0] == "LARGE_IMAGE" {
+                if len >= 6 {
+                    let (x,y) =
+                        (buffer_err_wrap!(line_vec[2].parse::<u32>(), i_line, buffer_len, 2..=2, 0, errors),
+                        buffer_err_wrap!(line_vec[3].parse::<u32>(), i_line, buffer_len, 3..=3, 0, errors));
+                    let (x_l,y_l) =
+                        (buffer_err_wrap!(line_vec[4].parse::<u32>(), i_line, buffer_len, 4..=4, 0, errors),
+                        buffer_err_wrap!(line_vec[5].parse::<u32>(), i_line, buffer_len, 5..=5, 0, errors));
+                    tile_graphic.tiles.push(ItemTile {
+                        state: line_vec[0].clone(),
+                        tile_name: line_vec[1].clone(),
+                        coords: [x, y],
+                        large_coords: Some([x_l.abs_diff(x), y_l.abs_diff(y)]),
+                        extra_args: line_vec[6..].to_vec(),
+                    });
+                } else {
+                    index_err!(i_line, buffer_len, len, 6, errors);
+                }
+            } else if len >= 4 {
+                let (x,y) =
+                    (buffer_err_wrap!(line_vec[2].parse::<u32>(), i_line, buffer_len, 2..=2, 0, errors),
+                    buffer_err_wrap!(line_vec[3].parse::<u32>(), i_line, buffer_len, 3..=3, 0, errors));
+                tile_graphic.tiles.push(ItemTile {
+                    state: line_vec[0].clone(),
+                    tile_name: line_vec[1].clone(),
+                    coords: [x, y],
+                    large_coords: None,
+                    extra_args: line_vec[4..].to_vec(),
+                });
+            } else {
+                index_err!(i_line, buffer_len, len, 4, errors);
+            }
+        }
+
+        (tile_graphic, errors)
+    }
+
+    fn display(&self) -> String {
+        let mut header = vec![self.kind.name(), self.name.clone()];
+        header.extend(self.header_args.iter().cloned());
+        let mut out = format!("[{}]\n", header.join(":"));
+
+        for tile in &self.tiles {
+            out.push_str(&tile.display());
         }
-        out.push_str(format!(
-            "\t[{}:{}:{}:{}:{}:{}]\n",
-            self.state.name(),
-            self.tile_name.with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
-                .to_case(Case::UpperSnake),
-            self.coords[0],
-            self.coords[1],
-            self.coords[0] + x2,
-            self.coords[1] + y2,
-        ).as_str());
 
-        if self.caste.is_none() {
-            out.push('\n');
+        for token in &self.other_tokens {
+            out.push_str(&format!("\t[{}]\n", token.join(":")));
         }
 
+        out.push('\n');
+
         out
     }
 }
-impl Menu for Statue {
+impl Menu for TileGraphic {
     fn menu(&mut self, ui: &mut Ui, shared: &mut Shared) {
-        let [x1, y1] = &mut self.coords;
-        let state = &mut self.state;
-        let caste_opt = &mut self.caste;
         let tile_names: Vec<&String> = shared.tile_page_info.keys().collect();
-        
-        egui::ComboBox::from_label("State")
-            .selected_text(state.name())
+
+        ui.separator();
+        egui::ComboBox::from_label("Graphics type")
+            .selected_text(self.kind.name())
             .show_ui(ui, |ui| {
-            for s in State::iterator() {
-                ui.selectable_value(state,  s.clone(), s.name());
-            }
-            for s in &shared.creature_shared.states {
-                ui.selectable_value(state,  s.clone(), s.name());
+            for k in ItemGraphicsKind::iterator() {
+                ui.selectable_value(&mut self.kind, k.clone(), k.name());
             }
-            ui.selectable_value(state, State::Custom(String::new()), "(custom)");
         });
-        if let State::Custom(cust_state) = state {
-            ui.label("Custom state:");
-            ui.text_edit_singleline(cust_state);
-            ui.hyperlink_to("Custom states that may work.", "https://dwarffortresswiki.org/index.php/Graphics_token#Layered_Conditions");
-        }
-        ui.label("Note: only DEFAULT is known to work v51.01");
+        ui.horizontal(|ui| {
+            ui.label("Item:");
+            ui.text_edit_singleline(&mut self.name);
+        });
 
-        let mut caste_bool = caste_opt.is_some();
+        ui.add_space(PADDING);
+        let states = self.kind.states();
+        let mut remove = None;
+        for (i_tile, tile) in self.tiles.iter_mut().enumerate() {
+            ui.push_id(i_tile, |ui| {
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_label("State")
+                        .selected_text(&tile.state)
+                        .show_ui(ui, |ui| {
+                        for &state in states {
+                            ui.selectable_value(&mut tile.state, state.to_string(), state);
+                        }
+                        //a placeholder, an empty state would export as [:TILE:0:0]
+                        ui.selectable_value(&mut tile.state, "CUSTOM".to_string(), "(custom)");
+                    });
+                    if !states.contains(&tile.state.as_str()) {
+                        ui.text_edit_singleline(&mut tile.state);
+                        if tile.state.trim().is_empty() {
+                            tile.state = "CUSTOM".to_string();
+                        }
+                    }
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(i_tile);
+                    }
+                });
 
-        ui.checkbox(&mut caste_bool, "Caste?");
+                egui::ComboBox::from_label("TilePage")
+                    .selected_text(&tile.tile_name)
+                    .show_ui(ui, |ui| {
+                    for &t in &tile_names {
+                        ui.selectable_value(&mut tile.tile_name, t.clone(), t);
+                    }
+                    ui.selectable_value(&mut tile.tile_name, String::new(), "Custom");
+                });
+                if !tile_names.contains(&&tile.tile_name) {
+                    ui.label("Custom tile name:");
+                    ui.text_edit_singleline(&mut tile.tile_name);
+                }
 
-        if let Some(caste) = caste_opt {
-            egui::ComboBox::from_label("")
-                .selected_text(caste.name())
-                .show_ui(ui, |ui| {
-                ui.selectable_value(caste, Caste::Female, "FEMALE");
-                ui.selectable_value(caste, Caste::Male, "MALE");
-                for c in &shared.creature_shared.castes {
-                    ui.selectable_value(caste, c.clone(), c.name());
+                let max_coords = TilePageInfo::slider_max(shared.tile_page(&tile.tile_name));
+
+                let mut large = tile.large_coords.is_some();
+                ui.checkbox(&mut large, "Large image");
+                if large {
+                    let [x2, y2] = tile.large_coords.get_or_insert([1, 1]);
+                    let [x1, y1] = &mut tile.coords;
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Slider::new(x1, 0..=max_coords[0].saturating_sub(1).saturating_sub(*x2)).prefix("X: "));
+                        ui.add(egui::Slider::new(x2, 0..=3).prefix("X + "));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Slider::new(y1, 0..=max_coords[1].saturating_sub(1).saturating_sub(*y2)).prefix("Y: "));
+                        ui.add(egui::Slider::new(y2, 0..=3).prefix("Y + "));
+                    });
+                } else {
+                    tile.large_coords = None;
+                    let [x, y] = &mut tile.coords;
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Slider::new(x, 0..=max_coords[0].saturating_sub(1)).prefix("X: "));
+                        ui.add(egui::Slider::new(y, 0..=max_coords[1].saturating_sub(1)).prefix("Y: "));
+                    });
                 }
-                ui.selectable_value(caste, Caste::Custom(String::new()), "(custom)");
+                TilePageInfo::bounds_warning(ui, shared.tile_page(&tile.tile_name), &mut tile.coords, &mut tile.large_coords);
             });
-            if let Caste::Custom(cust_caste) = caste {
-                ui.label("Custom caste:");
-                ui.text_edit_singleline(cust_caste);
-            }
-        }
-
-        if caste_bool {
-            caste_opt.get_or_insert(Caste::Female);
-        } else {
-            caste_opt.take();
+            ui.add_space(PADDING);
         }
-
-        ui.add_space(PADDING);
-        egui::ComboBox::from_label("TilePage")
-            .selected_text(&self.tile_name)
-            .show_ui(ui, |ui| {
-            for &t in &tile_names {
-                ui.selectable_value(&mut self.tile_name, t.clone(), t);
-            }
-            ui.selectable_value(&mut self.tile_name, String::new(), "Custom");
-        });
-        if !tile_names.contains(&&self.tile_name) {
-            ui.label("Custom tile name:");
-            ui.text_edit_singleline(&mut self.tile_name);
+        if let Some(i_tile) = remove {
+            self.tiles.remove(i_tile);
         }
 
-        ui.add_space(PADDING);
-        let [x2, y2] = self.large_coords.get_or_insert([0, 0]);
-        let max_coords;
-        if let Some(tp_info) = shared.tile_page_info.get(&self.tile_name) {
-            max_coords = [(tp_info.image_size[0]/32) as u32, (tp_info.image_size[1]/32) as u32];
-        } else {
-            max_coords = [100,100];
+        if ui.small_button("Add Tile").clicked() {
+            let tile_name = self.tiles.last().map(|t| t.tile_name.clone()).unwrap_or_default();
+            let state = states.first().copied().unwrap_or("DEFAULT").to_string();
+            self.tiles.push(ItemTile { state, tile_name, ..Default::default() });
         }
-        ui.horizontal(|ui| {
-            ui.add(egui::Slider::new(x1, 0..=max_coords[0].checked_sub(*x2+1)
-                .unwrap_or_default()).prefix("X: "));
-            ui.add(egui::Slider::new(x2, 0..=2).prefix("X + "));
-        });
-        ui.horizontal(|ui| {
-            ui.add(egui::Slider::new(y1, 0..=max_coords[1].checked_sub(*y2+1)
-                .unwrap_or_default()).prefix("Y: "));
-            ui.add(egui::Slider::new(y2, 0..=1).prefix("Y + "));
-        });
 
         ui.add_space(PADDING);
         ui.label("Preview:");
@@ -4836,92 +7821,261 @@ impl Menu for Statue {
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct Plant {
-    pub name: String,
+/// A tile assignment inside an item graphics block, `[STATE:TILE_PAGE:X:Y]` or `[LARGE_IMAGE:TILE_PAGE:X1:Y1:X2:Y2]`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemTile {
+    pub state: String,
     pub tile_name: String,
-    pub coords: [Option<[u32; 2]>; 9],
+    pub coords: [u32; 2],
+    pub large_coords: Option<[u32; 2]>, //extent past coords for LARGE_IMAGE
+    pub extra_args: Vec<String>,        //arguments after the coordinates, kept as they were read
 }
-impl RAW for Plant {
-    fn new() -> Self {
-        const COORDS_REPEAT_VALUE: Option<[u32; 2]> = None;
-        Self {
-            name: "(new)".to_string(),
-            tile_name: String::new(),
-            coords: [COORDS_REPEAT_VALUE; 9],
+impl ItemTile {
+    fn display(&self) -> String {
+        let mut args = vec![
+            self.state.clone(),
+            self.tile_name.with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
+                .to_case(Case::UpperSnake),
+            self.coords[0].to_string(),
+            self.coords[1].to_string(),
+        ];
+        if let Some([x2, y2]) = self.large_coords {
+            args.push((self.coords[0].saturating_add(x2)).to_string());
+            args.push((self.coords[1].saturating_add(y2)).to_string());
         }
+        args.extend(self.extra_args.iter().cloned());
+
+        format!("\t[{}]\n", args.join(":"))
     }
+}
 
-    fn read(_buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, _path: &PathBuf) -> (Self, Vec<DFGHError>) {
-        let errors = Vec::new();
-        //todo
-        (Plant::new(), errors)
+/// The block token an item graphics block starts with.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ItemGraphicsKind {
+    #[default]
+    Tile,
+    Helm,
+    Armor,
+    Pants,
+    Gloves,
+    Shoes,
+    Ammo,
+    SiegeAmmo,
+    Weapon,
+    Shield,
+    TrapComp,
+    Boulder,
+    RoughGem,
+    Bars,
+    Food,
+    Toy,
+    Tool,
+    AddTool,
+    FoodContainer,
+}
+impl ItemGraphicsKind {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Tile => "TILE_GRAPHICS".to_string(),
+            Self::Helm => "HELM_GRAPHICS".to_string(),
+            Self::Armor => "ARMOR_GRAPHICS".to_string(),
+            Self::Pants => "PANTS_GRAPHICS".to_string(),
+            Self::Gloves => "GLOVES_GRAPHICS".to_string(),
+            Self::Shoes => "SHOES_GRAPHICS".to_string(),
+            Self::Ammo => "AMMO_GRAPHICS".to_string(),
+            Self::SiegeAmmo => "SIEGEAMMO_GRAPHICS".to_string(),
+            Self::Weapon => "WEAPON_GRAPHICS".to_string(),
+            Self::Shield => "SHIELD_GRAPHICS".to_string(),
+            Self::TrapComp => "TRAPCOMP_GRAPHICS".to_string(),
+            Self::Boulder => "BOULDER_GRAPHICS".to_string(),
+            Self::RoughGem => "ROUGH_GEM_GRAPHICS".to_string(),
+            Self::Bars => "BARS_GRAPHICS".to_string(),
+            Self::Food => "FOOD_GRAPHICS".to_string(),
+            Self::Toy => "TOY_GRAPHICS".to_string(),
+            Self::Tool => "TOOL_GRAPHICS".to_string(),
+            Self::AddTool => "ADD_TOOL_GRAPHICS".to_string(),
+            Self::FoodContainer => "FOOD_CONTAINER_GRAPHICS".to_string(),
+        }
     }
 
-    fn display(&self) -> String {
-        //todo
-        "".to_string()
+    pub fn from(string: String) -> Option<Self> {
+        match string.to_uppercase().as_str() {
+            "TILE_GRAPHICS" => Some(Self::Tile),
+            "HELM_GRAPHICS" => Some(Self::Helm),
+            "ARMOR_GRAPHICS" => Some(Self::Armor),
+            "PANTS_GRAPHICS" => Some(Self::Pants),
+            "GLOVES_GRAPHICS" => Some(Self::Gloves),
+            "SHOES_GRAPHICS" => Some(Self::Shoes),
+            "AMMO_GRAPHICS" => Some(Self::Ammo),
+            "SIEGEAMMO_GRAPHICS" => Some(Self::SiegeAmmo),
+            "WEAPON_GRAPHICS" => Some(Self::Weapon),
+            "SHIELD_GRAPHICS" => Some(Self::Shield),
+            "TRAPCOMP_GRAPHICS" => Some(Self::TrapComp),
+            "BOULDER_GRAPHICS" => Some(Self::Boulder),
+            "ROUGH_GEM_GRAPHICS" => Some(Self::RoughGem),
+            "BARS_GRAPHICS" => Some(Self::Bars),
+            "FOOD_GRAPHICS" => Some(Self::Food),
+            "TOY_GRAPHICS" => Some(Self::Toy),
+            "TOOL_GRAPHICS" => Some(Self::Tool),
+            "ADD_TOOL_GRAPHICS" => Some(Self::AddTool),
+            "FOOD_CONTAINER_GRAPHICS" => Some(Self::FoodContainer),
+            _ => None,
+        }
     }
-}
-impl Menu for Plant {
-    fn menu(&mut self, ui: &mut Ui, shared: &mut Shared) {
-        //todo
-        ui.label("plant menu");
-        let tile_names: Vec<&String> = shared.tile_page_info.keys().collect();
-        for tile_name in tile_names {
-            ui.label(tile_name);
+
+    /// Tile states offered in the editor for this kind of block. Others can still be typed in.
+    pub fn states(&self) -> &'static [&'static str] {
+        match self {
+            Self::Weapon => &[
+                "DEFAULT",
+                "LARGE_IMAGE",
+                "WEAPON_GRAPHICS_DEFAULT",
+                "WEAPON_GRAPHICS_UPRIGHT_1T",
+                "WEAPON_GRAPHICS_UPRIGHT_2T",
+                "WEAPON_GRAPHICS_UPRIGHT_3T",
+                "WEAPON_GRAPHICS_UPRIGHT_4T",
+                "WEAPON_GRAPHICS_UPRIGHT_5T",
+                "WEAPON_GRAPHICS_UPRIGHT_6T",
+                "WEAPON_GRAPHICS_UPRIGHT_7T",
+                "WEAPON_GRAPHICS_UPRIGHT_8T",
+                "WEAPON_GRAPHICS_UPRIGHT_9T",
+                "WEAPON_GRAPHICS_UPRIGHT_10T",
+            ],
+            Self::Ammo | Self::SiegeAmmo => &[
+                "DEFAULT",
+                "LARGE_IMAGE",
+                "AMMO_GRAPHICS_STRAIGHT_DEFAULT",
+                "AMMO_GRAPHICS_STRAIGHT_WOOD",
+                "AMMO_GRAPHICS_DIAGONAL_DEFAULT",
+                "AMMO_GRAPHICS_DIAGONAL_WOOD",
+            ],
+            Self::TrapComp => &[
+                "DEFAULT",
+                "LARGE_IMAGE",
+                "TRAPCOMP_GRAPHICS_WEAPON",
+                "TRAPCOMP_GRAPHICS_WEAPON_TRAP",
+                "TRAPCOMP_GRAPHICS_UPRIGHT_1T",
+                "TRAPCOMP_GRAPHICS_UPRIGHT_2T",
+                "TRAPCOMP_GRAPHICS_UPRIGHT_3T",
+            ],
+            Self::Tool | Self::AddTool => &[
+                "DEFAULT",
+                "LARGE_IMAGE",
+                "SHAPE",
+            ],
+            _ => &[
+                "DEFAULT",
+                "LARGE_IMAGE",
+            ],
         }
     }
+
+    fn iterator() -> std::slice::Iter<'static, Self> {
+        static KINDS: [ItemGraphicsKind; 19] = [
+            ItemGraphicsKind::Tile,
+            ItemGraphicsKind::Helm,
+            ItemGraphicsKind::Armor,
+            ItemGraphicsKind::Pants,
+            ItemGraphicsKind::Gloves,
+            ItemGraphicsKind::Shoes,
+            ItemGraphicsKind::Ammo,
+            ItemGraphicsKind::SiegeAmmo,
+            ItemGraphicsKind::Weapon,
+            ItemGraphicsKind::Shield,
+            ItemGraphicsKind::TrapComp,
+            ItemGraphicsKind::Boulder,
+            ItemGraphicsKind::RoughGem,
+            ItemGraphicsKind::Bars,
+            ItemGraphicsKind::Food,
+            ItemGraphicsKind::Toy,
+            ItemGraphicsKind::Tool,
+            ItemGraphicsKind::AddTool,
+            ItemGraphicsKind::FoodContainer,
+        ];
+        KINDS.iter()
+    }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
-pub struct TileGraphic {
-    pub name: String,
-    pub tile_name: String,
-    pub coords: [u32; 2],
-    
+/// A graphics block of a type this tool doesn't model yet, kept as its tokens so it survives an export.
+///
+/// ```
+/// # use df_texture_helper::*;
+/// let raw = "[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[DEFAULT:DWARF:0:0]\n\n[NEW_GRAPHICS:THING] kept\n\t[FOO:BAR:1]\n";
+/// let raw_lines: Vec<String> = raw.split_inclusive('\n').map(|l| l.to_string()).collect();
+/// let tokens = Token::tokenize(&raw_lines, "graphics_creatures_mix.txt".as_ref());
+///
+/// let (graphics_file, errors) = GraphicsFile::read(tokens, raw_lines, "graphics_creatures_mix.txt".as_ref());
+///
+/// assert!(errors.is_empty());
+/// let unsupported = graphics_file.unsupported_blocks();
+/// assert_eq!("NEW_GRAPHICS:THING", unsupported[0].name());
+/// assert_eq!(vec![vec!["FOO".to_string(), "BAR".to_string(), "1".to_string()]], unsupported[0].tokens);
+/// assert!(graphics_file.display_raw().ends_with("[NEW_GRAPHICS:THING] kept\n\t[FOO:BAR:1]\n"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnknownBlock {
+    pub header: Vec<String>,        //block token and its arguments
+    pub tokens: Vec<Vec<String>>,   //every token in the block after the header
 }
-impl RAW for TileGraphic {
+impl RAW for UnknownBlock {
     fn new() -> Self {
-        Self {
-            name: "(new)".to_string(),
-            tile_name: String::new(),
-            coords: [0, 0],
-        }
+        Self::default()
     }
 
-    fn read(_buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, _path: &PathBuf) -> (Self, Vec<DFGHError>) {
-        let errors = Vec::new();
-        //todo
-        (TileGraphic::new(), errors)
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut block = UnknownBlock::new();
+
+        for (i_line, line_vec) in buffer.iter().enumerate() {
+            if i_line == 0 {
+                block.header = line_vec.args.clone();
+            } else {
+                block.tokens.push(line_vec.args.clone());
+            }
+        }
+
+        (block, Vec::new())
     }
 
     fn display(&self) -> String {
-        //todo
-        let out = "".to_string();
+        let mut out = format!("[{}]\n", self.header.join(":"));
+
+        for token in &self.tokens {
+            out.push_str(&format!("\t[{}]\n", token.join(":")));
+        }
+
+        out.push('\n');
 
         out
     }
 }
-impl Menu for TileGraphic {
-    fn menu(&mut self, ui: &mut Ui, shared: &mut Shared) {
-        //todo
-        ui.label("tile graphic menu");
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            let tile_names: Vec<&String> = shared.tile_page_info.keys().collect();
-            for tile_name in tile_names {
-                ui.label(tile_name);
-            }
-        });
+impl UnknownBlock {
+    pub fn name(&self) -> String {
+        self.header.iter().take(2).cloned().collect::<Vec<String>>().join(":")
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+/// An LS_PALETTE declared in a layer set, with the rows of its palette image once it has been loaded.
+///
+/// ```
+/// # use df_texture_helper::*;
+/// let text = "\t\t[LS_PALETTE:SKIN]\n\t\t\t[LS_PALETTE_FILE:images/dwarf_skin.png]\n\t\t\t[LS_PALETTE_DEFAULT:2]\n\n";
+///
+/// let (palette, errors) = Palette::read_text(text, "clipboard".as_ref());
+///
+/// assert!(errors.is_empty());
+/// assert_eq!("SKIN", palette.name);
+/// assert_eq!(std::path::PathBuf::from("images").join("dwarf_skin.png"), palette.file_name);
+/// assert_eq!(2, palette.default_index);
+/// assert_eq!(text, palette.display());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct Palette {
-    name: String,
-    file_name: PathBuf,
-    default_index: u32,
-    max_row: u32,
+    pub name: String,
+    pub file_name: PathBuf,         //image path relative to the graphics folder
+    pub default_index: u32,
+    pub max_row: u32,
+    #[serde(skip)]
+    pub rows: Vec<Vec<[u8; 4]>>,    //colors in each row of the image, empty until the image is loaded
 }
 impl RAW for Palette {
     fn new() -> Self {
@@ -4930,17 +8084,51 @@ impl RAW for Palette {
             file_name: PathBuf::new(),
             default_index: 0,
             max_row: 255,
+            rows: Vec::new(),
         }
     }
 
-    fn read(_buffer: Vec<Vec<String>>, _raw_buffer: Vec<String>, _path: &PathBuf) -> (Self, Vec<DFGHError>) {
-        let errors = Vec::new();
-        //handled within layer set read function
-        (Palette::new(), errors)
+    fn read(buffer: Vec<Token>, _raw_buffer: Vec<String>, _path: &Path) -> (Self, Vec<DFGHError>) {
+        let mut palette = Palette::new();
+        let mut errors: Vec<DFGHError> = Vec::new();
+        let buffer_len = buffer.len();
+
+        for (i_line, line_vec) in buffer.iter().enumerate() {
+            let len = line_vec.len();
+
+            match line_vec[0].as_str() {
+                "LS_PALETTE" => {
+                    if len >= 2 {
+                        palette.name = line_vec[1].clone();
+                    } else {
+                        index_err!(i_line, buffer_len, len, 2, errors);
+                    }
+                },
+                "LS_PALETTE_FILE" => {
+                    if len >= 2 {
+                        //max row is set from the image by Palette::load_image.
+                        palette.file_name = line_vec[1].split(&['\\','/']).collect::<PathBuf>();
+                    } else {
+                        index_err!(i_line, buffer_len, len, 2, errors);
+                    }
+                },
+                "LS_PALETTE_DEFAULT" => {
+                    if len >= 2 {
+                        palette.default_index = buffer_err_wrap!(line_vec[1].parse::<u32>(), i_line, buffer_len, 1..=1, 0, errors);
+                    } else {
+                        index_err!(i_line, buffer_len, len, 2, errors);
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        (palette, errors)
     }
+
     fn display(&self) -> String {
         format!(
-            "\t\t[LS_PALETTE:{}]\n\t\t\t[LS_PALETTE_FILE:images/{}.png]\n\t\t\t[LS_PALETTE_DEFAULT:{}]\n\n",
+            "\t\t[LS_PALETTE:{}]\n\t\t\t[LS_PALETTE_FILE:{}]\n\t\t\t[LS_PALETTE_DEFAULT:{}]\n\n",
             self.name.with_boundaries(&[Boundary::Space, Boundary::LowerUpper])
                 .to_case(Case::UpperSnake),
             self.file_name.as_os_str().to_string_lossy().replace("\\", "/"),
@@ -4948,6 +8136,19 @@ impl RAW for Palette {
         )
     }
 }
+impl Palette {
+    /// Load the palette image from the graphics folder, setting the rows and the last valid row.
+    pub fn load_image(&mut self, file_system: &dyn FileSystem, graphics_dir: &Path) -> Result<()> {
+        let image = file_system
+            .open_image(&graphics_dir.join(&self.file_name).with_extension("png"))?
+            .to_rgba8();
+
+        self.max_row = image.height().saturating_sub(1);
+        self.rows = image.rows().map(|row| row.map(|pixel| pixel.0).collect()).collect();
+
+        Ok(())
+    }
+}
 impl Menu for Palette {
     fn menu(&mut self, ui: &mut Ui, _shared: &mut Shared) {
         ui.horizontal(|ui| {
@@ -4990,10 +8191,40 @@ impl Menu for Palette {
         ui.add_space(PADDING);
 
         ui.label("Palette Rows:");
-        ui.horizontal(|ui| {
+        if self.rows.is_empty() {
+            ui.horizontal(|ui| {
+                ui.add(egui::Slider::new(&mut self.default_index, 0..=self.max_row).drag_value_speed(1.0).prefix("Default Row: "));
+                ui.add(egui::Slider::new(&mut self.max_row, 0..=255).drag_value_speed(1.0).prefix("Max Row: "));
+            });
+            ui.label("The palette image hasn't been loaded, rows can't be shown.");
+        } else {
             ui.add(egui::Slider::new(&mut self.default_index, 0..=self.max_row).drag_value_speed(1.0).prefix("Default Row: "));
-            ui.add(egui::Slider::new(&mut self.max_row, 0..=255).drag_value_speed(1.0).prefix("Max Row: "));
-        });
+
+            //swatch grid, click a row number to make it the default
+            let default_index = &mut self.default_index;
+            egui::ScrollArea::vertical()
+                .id_source("palette swatches")
+                .max_height(240.0)
+                .show(ui, |ui| {
+                for (i_row, row) in (0u32..).zip(self.rows.iter()) {
+                    ui.horizontal(|ui| {
+                        let mut row_label = egui::RichText::new(format!("{:>3}", i_row)).monospace();
+                        if i_row == *default_index {
+                            row_label = row_label.strong().underline();
+                        }
+                        if ui.add(egui::Label::new(row_label).sense(egui::Sense::click())).clicked() {
+                            *default_index = i_row;
+                        }
+
+                        for color in row.iter().take(64) {
+                            let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                            ui.painter().rect_filled(rect, 0.0,
+                                egui::Color32::from_rgba_unmultiplied(color[0], color[1], color[2], color[3]));
+                        }
+                    });
+                }
+            });
+        }
 
         ui.add_space(PADDING);
         
@@ -5002,37 +8233,55 @@ impl Menu for Palette {
     }
 }
 
+/// Coords of a tile and the extent of its LARGE_IMAGE rectangle, if it is one.
+pub type TilePosition = ([u32; 2], Option<[u32; 2]>);
+//(location, tile page, coords, LARGE_IMAGE extent)
+type TileRef<'a> = (String, &'a String, [u32; 2], Option<[u32; 2]>);
+//(tile page, coords, LARGE_IMAGE extent if the tile can have one)
+type TileMut<'a> = (&'a String, &'a mut [u32; 2], Option<&'a mut Option<[u32; 2]>>);
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Shared {
     tile_page_info: HashMap<String, TilePageInfo>,
+    library_tile_page_info: HashMap<String, TilePageInfo>, //vanilla tile pages, only for checking references to them
     creature_shared: CreatureShared,
-    //
+    catalog: RawCatalog,
+    creature_name: String,  //creature being edited, so conditions can offer what its body has
+    images: Vec<PathBuf>,   //png files under graphics/images, relative to the graphics folder
 }
 impl Shared {
     fn new() -> Self {
         Self {
             tile_page_info: HashMap::new(),
+            library_tile_page_info: HashMap::new(),
             creature_shared: CreatureShared::new(),
+            catalog: RawCatalog::new(),
+            creature_name: String::new(),
+            images: Vec::new(),
         }
     }
 
     fn clear(&mut self) {
         self.tile_page_info.clear();
+        self.library_tile_page_info.clear();
         self.creature_shared = CreatureShared::new();
+        self.catalog = RawCatalog::new();
+        self.images.clear();
     }
 
-    fn update(&mut self, tp_files: &Vec<TilePageFile>, g_files: &mut Vec<GraphicsFile>, folder: &PathBuf) {
+    fn update(&mut self, tp_files: &Vec<TilePageFile>, g_files: &mut Vec<GraphicsFile>, folder: &Path, file_system: &dyn FileSystem) {
         for tp_file in tp_files.iter() {
             for tp in tp_file.tile_pages.iter() {
                 self.tile_page_info.entry(tp.name.clone())
-                    .or_insert_with(|| {Self::tile_page_info(tp, folder)}
+                    .or_insert_with(|| {Self::tile_page_info(tp, folder, file_system)}
                 );
             }
         }
+        self.images = Self::images(&folder.join("graphics"), &PathBuf::from("images"), file_system);
         self.creature_shared = CreatureShared::new();
         for g_file in g_files.iter_mut() {
             match g_file {
-                GraphicsFile::CreatureFile(_, creatures) => {
+                GraphicsFile::CreatureFile(_, creatures, ..) => {
                     for creature in creatures {
                         CreatureShared::update_c_shared(creature);
 
@@ -5045,20 +8294,63 @@ impl Shared {
         }
     }
 
-    fn tile_page_info(tp: &TilePage, folder: &PathBuf) -> TilePageInfo {
+    //tile page info by name, ignoring case like the tile name checks do
+    fn tile_page(&self, name: &str) -> Option<&TilePageInfo> {
+        Self::find_tile_page(&self.tile_page_info, name)
+    }
+
+    //tile page info for checking references, falling back on the vanilla tile pages of a loaded library
+    fn checked_tile_page(&self, name: &str) -> Option<&TilePageInfo> {
+        self.tile_page(name).or_else(|| Self::find_tile_page(&self.library_tile_page_info, name))
+    }
+
+    fn find_tile_page<'a>(tile_page_info: &'a HashMap<String, TilePageInfo>, name: &str) -> Option<&'a TilePageInfo> {
+        tile_page_info.get(name).or_else(|| {
+            tile_page_info
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, info)| info)
+        })
+    }
+
+    //combine the shared data of graphics loaded from another folder, image paths stay pointed at their own folder
+    fn merge(&mut self, other: Shared) {
+        for (name, info) in other.tile_page_info {
+            self.tile_page_info.entry(name).or_insert(info);
+        }
+        self.creature_shared.append(&other.creature_shared);
+        self.creature_shared.sort_and_dedup();
+    }
+
+    //png files in a folder under the graphics folder and its subfolders
+    fn images(graphics_dir: &Path, folder: &Path, file_system: &dyn FileSystem) -> Vec<PathBuf> {
+        let mut images = Vec::new();
+
+        for path in file_system.read_dir(&graphics_dir.join(folder)).unwrap_or_default() {
+            let Ok(relative) = path.strip_prefix(graphics_dir).map(|p| p.to_path_buf()) else { continue };
+            if file_system.is_dir(&path) {
+                images.append(&mut Self::images(graphics_dir, &relative, file_system));
+            } else if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("png")) {
+                images.push(relative);
+            }
+        }
+        images.sort();
+
+        images
+    }
+
+    fn tile_page_info(tp: &TilePage, folder: &Path, file_system: &dyn FileSystem) -> TilePageInfo {
         let image_path = folder.join("graphics")
             .join(tp.file_name.clone()).with_extension("png");
-        let image = image::open(&image_path).ok();
-        let image_size: [u32; 2];
         let tile_size = tp.tile_size;
 
-        if let Some(dyn_image) = &image {
-            image_size = [dyn_image.width() as u32, dyn_image.height() as u32];
-        } else {
-            image_size = tp.image_size;
-        }
+        //only the size is needed here, the preview decodes the image when it's shown
+        let image_size = file_system
+            .image_dimensions(&image_path)
+            .map(|(width, height)| [width, height])
+            .unwrap_or(tp.image_size);
 
-        TilePageInfo {image_path, image_size, tile_size, image, ..Default::default()}
+        TilePageInfo {image_path, image_size, tile_size, ..Default::default()}
     }
 }
 
@@ -5067,7 +8359,6 @@ pub struct TilePageInfo {
     image_path: PathBuf,
     image_size: [u32; 2],
     tile_size: [u32; 2],
-    image: Option<image::DynamicImage>,
     texture: Option<egui::TextureHandle>,
 }
 impl Debug for TilePageInfo {
@@ -5076,13 +8367,89 @@ impl Debug for TilePageInfo {
             .field("image_path", &self.image_path)
             .field("image_size", &self.image_size)
             .field("tile_size", &self.tile_size)
-            .field("image", &self.image)
             .field("texture", &self.texture.clone().map(|t| t.name()))
             .finish()
     }
 }
+impl TilePageInfo {
+    //tile columns and rows of the page, None until both the image and tile size are known
+    fn grid(&self) -> Option<[u32; 2]> {
+        if self.image_size.contains(&0) || self.tile_size.contains(&0) {
+            None
+        } else {
+            Some([self.image_size[0] / self.tile_size[0], self.image_size[1] / self.tile_size[1]])
+        }
+    }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+    //coordinate slider range for a tile page, a generous default while the page is unknown
+    fn slider_max(tp_info: Option<&TilePageInfo>) -> [u32; 2] {
+        tp_info.and_then(TilePageInfo::grid).unwrap_or([100, 100])
+    }
+
+    //whether a tile and the LARGE_IMAGE extent past it lie inside the grid
+    fn fits(&self, coords: [u32; 2], large_coords: Option<[u32; 2]>) -> bool {
+        let [x2, y2] = large_coords.unwrap_or_default();
+        self.grid().map_or(true, |[columns, rows]| coords[0].saturating_add(x2) < columns && coords[1].saturating_add(y2) < rows)
+    }
+
+    //move the tile back onto the grid, shrinking a LARGE_IMAGE extent wider than the page
+    fn clamp(&self, coords: &mut [u32; 2], large_coords: &mut Option<[u32; 2]>) {
+        if let Some(grid) = self.grid() {
+            for i in 0..2 {
+                let last = grid[i].saturating_sub(1);
+                if let Some(large) = large_coords.as_mut() {
+                    large[i] = large[i].min(last);
+                    coords[i] = coords[i].min(last - large[i]);
+                } else {
+                    coords[i] = coords[i].min(last);
+                }
+            }
+        }
+    }
+
+    //warning next to the coordinate sliders when the tile is off the page, with a quick fix
+    fn bounds_warning(ui: &mut Ui, tp_info: Option<&TilePageInfo>, coords: &mut [u32; 2], large_coords: &mut Option<[u32; 2]>) {
+        if let Some(tp_info) = tp_info.filter(|i| !i.fits(*coords, *large_coords)) {
+            let [columns, rows] = tp_info.grid().unwrap_or_default();
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!("⚠ Outside the tile page, which has tiles 0:0 to {}:{}", columns.saturating_sub(1), rows.saturating_sub(1))
+                );
+                if ui.small_button("Clamp").clicked() {
+                    tp_info.clamp(coords, large_coords);
+                }
+            });
+        }
+    }
+}
+
+//closest name by edit distance, if it's near enough to be a typo
+fn closest_name<'a>(name: &str, names: &[&'a String]) -> Option<&'a String> {
+    let name = name.to_uppercase();
+    let distance = |other: &str| {
+        let other: Vec<char> = other.to_uppercase().chars().collect();
+        let mut row: Vec<usize> = (0..=other.len()).collect();
+        for (i, a) in name.chars().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = i + 1;
+            for (j, b) in other.iter().enumerate() {
+                let substitution = diagonal + if a == *b { 0 } else { 1 };
+                diagonal = row[j + 1];
+                row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+            }
+        }
+        row[other.len()]
+    };
+
+    names.iter()
+        .map(|n| (distance(n), *n))
+        .filter(|(d, _)| *d <= (name.len() / 3).max(2))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, n)| n)
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct CreatureShared {
     palettes: Vec<Palette>,
     colors: Vec<Color>,
@@ -5,16 +5,24 @@ use rfd;
 use std::path::PathBuf;
 use std::path;
 
-use crate::{Plant, Statue, TileGraphic, PADDING};
+use crate::{Plant, Statue, TileGraphic, RawFile, Project, ModInfo, ReferenceLibrary, Token, PADDING};
 use super::error;
+use super::catalog::RawCatalog;
+use super::export::{ExportPlan, ExportStatus};
+use super::lint::{Diagnostic, LintAction, Linter};
+use super::vfs::DiskFileSystem;
 use crate::{RAW, Menu, Graphics, TilePageFile, TilePage, GraphicsFile, 
     Creature, LayerSet, LayerGroup, Layer, SimpleLayer, Condition};//, State, Caste};
 use error::{DFGHError, Result, error_window};
 
+//marks a preview of a reference library tile page, which may share a name with one of the mod's
+const LIBRARY_PREFIX: &str = "library:";
+
 #[derive(Debug, Default, Clone, Copy)]
 pub enum MainWindow {
     #[default]
     DefaultMenu,
+    ModInfoMenu,
     TilePageFileDefaultMenu,
     TilePageFileMenu,
     TilePageMenu,
@@ -29,6 +37,8 @@ pub enum MainWindow {
     StatueMenu,
     PlantMenu,
     TileGraphicMenu,
+    UnsupportedBlockMenu,
+    LibraryMenu,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -95,26 +105,24 @@ impl From<Condition> for ContextData {
 }
 impl From<Statue> for ContextData {
     fn from(value: Statue) -> Self {
-        let _x = value;
-        todo!()
+        ContextData::Statue(value)
     }
 }
 impl From<Plant> for ContextData {
     fn from(value: Plant) -> Self {
-        let _x = value;
-        todo!()
+        ContextData::Plant(value)
     }
 }
 impl From<TileGraphic> for ContextData {
     fn from(value: TileGraphic) -> Self {
-        let _x = value;
-        todo!()
+        ContextData::TileGraphic(value)
     }
 }
 impl From<MainWindow> for ContextData {
     fn from(main_window: MainWindow) -> Self {
         match main_window {
             MainWindow::DefaultMenu => ContextData::GraphicsFile(GraphicsFile::default()),
+            MainWindow::ModInfoMenu => ContextData::None,
             MainWindow::TilePageFileDefaultMenu => ContextData::TilePageFile(TilePageFile::new()),
             MainWindow::TilePageFileMenu => ContextData::TilePageFile(TilePageFile::new()),
             MainWindow::TilePageMenu => ContextData::TilePage(TilePage::new()),
@@ -129,6 +137,8 @@ impl From<MainWindow> for ContextData {
             MainWindow::StatueMenu => ContextData::Statue(Statue::new()),
             MainWindow::PlantMenu => ContextData::Plant(Plant::new()),
             MainWindow::TileGraphicMenu => ContextData::TileGraphic(TileGraphic::new()),
+            MainWindow::UnsupportedBlockMenu => ContextData::None,
+            MainWindow::LibraryMenu => ContextData::None,
         }
     }
 }
@@ -151,6 +161,7 @@ enum Action {
     Copy(ContextData),
     Cut(ContextData),
     Paste,
+    PasteText(String),
     Duplicate(ContextData),
     Insert(ContextData),
     Undo,
@@ -158,7 +169,11 @@ enum Action {
     Delete(ContextData),
     Import,
     Export,
+    Open,
+    Save,
+    LoadLibrary,
     Update,
+    Lint,
     Zoom(PreviewZoom),
     Debug,
 }
@@ -219,6 +234,14 @@ pub struct DFGraphicsHelper {
     selected: ContextData,
     undo_buffer: Vec<(Vec<TilePageFile>, Vec<GraphicsFile>, GraphicsIndices)>,
     redo_buffer: Vec<(Vec<TilePageFile>, Vec<GraphicsFile>, GraphicsIndices)>,
+    export_plan: Option<ExportPlan>,
+    backup: bool,
+    normalize_names: bool,
+    library: Option<ReferenceLibrary>,
+    library_selected: ContextData,
+    linter: Linter,
+    diagnostics: Vec<Diagnostic>,
+    lint_window: bool,
     pub errors: Vec<DFGHError>,
 }
 impl DFGraphicsHelper {
@@ -239,6 +262,14 @@ impl DFGraphicsHelper {
             selected: ContextData::default(),
             undo_buffer: Vec::with_capacity(1000),
             redo_buffer: Vec::with_capacity(100),
+            export_plan: None,
+            backup: true,
+            normalize_names: false,
+            library: None,
+            library_selected: ContextData::default(),
+            linter: Linter::new(),
+            diagnostics: Vec::new(),
+            lint_window: false,
             errors: Vec::new(),
         }
     }
@@ -260,6 +291,9 @@ impl DFGraphicsHelper {
         let (graphics, path, mut errors) = Graphics::import(&mut self.path);
         (self.loaded_graphics, self.path) = (graphics, path);
         self.errors.append(&mut errors);
+        self.update_catalog();
+        self.lint();
+        self.lint_window |= !self.diagnostics.is_empty();
 
         self.action = Action::None;
     }
@@ -269,20 +303,292 @@ impl DFGraphicsHelper {
             if let Some(path) = rfd::FileDialog::new()
                 .set_title("Choose Mod Folder")
                 .pick_folder() {
-                let export_result = self.loaded_graphics.export(&path);
-                if export_result.is_err() {
-                    self.errors.push(export_result.unwrap_err());
-                };
+                self.path = path;
+            }
+        }
+
+        if self.path.exists() {
+            match self.loaded_graphics.export_plan(&self.path, self.normalize_names) {
+                Ok(plan) => self.export_plan = Some(plan),
+                Err(e) => self.errors.push(e),
             }
-        } else {
-            let export_result = self.loaded_graphics.export(&self.path);
-            if export_result.is_err() {
-                self.errors.push(export_result.unwrap_err());
-            };
         }
         self.action = Action::None;
     }
 
+    fn export_window(&mut self, ctx: &Context) {
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let mut replan = false;
+
+        if let Some(plan) = &self.export_plan {
+            egui::Window::new("Export")
+                .collapsible(false)
+                .constrain(true)
+                .title_bar(true)
+                .default_size([600.0, 400.0])
+                .show(ctx, |ui| {
+                ui.label(format!("Exporting to:\t{}", plan.folder.display()));
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                    for (i_file, planned_file) in plan.files.iter().enumerate() {
+                        let label = format!("{}\t{}", planned_file.status.name(), planned_file.path.display());
+                        match planned_file.status {
+                            ExportStatus::Create | ExportStatus::Change => {
+                                egui::CollapsingHeader::new(label)
+                                    .id_source(i_file)
+                                    .show(ui, |ui| {
+                                    ui.add(egui::Label::new(egui::RichText::new(&planned_file.diff)
+                                        .monospace())
+                                        .wrap(false)
+                                    );
+                                });
+                            },
+                            ExportStatus::Unchanged => {
+                                ui.weak(label);
+                            },
+                            ExportStatus::Stale => {
+                                ui.label(label)
+                                    .on_hover_text("Existing file that won't be overwritten. It is left as is.");
+                            },
+                        }
+                    }
+                });
+
+                ui.separator();
+                ui.checkbox(&mut self.backup, "Back up graphics and info.txt next to the mod first");
+                if ui.checkbox(&mut self.normalize_names, "Normalize file names")
+                    .on_hover_text("Name files from their object names (tile_page_*, graphics_creatures_*) instead of keeping the imported file names.")
+                    .changed() {
+                    replan = true;
+                }
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::RIGHT), |ui| {
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                    if ui.add_enabled(plan.changes().next().is_some(), egui::Button::new("Export")).clicked() {
+                        confirmed = true;
+                    }
+                });
+            });
+        }
+
+        if confirmed {
+            //the graphics can be edited while the preview is open, so only write what was shown
+            if let Some(plan) = self.export_plan.take() {
+                match self.loaded_graphics.export_plan(&plan.folder, self.normalize_names) {
+                    Ok(current) if current.eq(&plan) => {
+                        if let Err(e) = plan.write(self.backup) {
+                            self.errors.push(e);
+                        }
+                    },
+                    Ok(current) => {
+                        self.export_plan = Some(current);
+                        self.errors.push(DFGHError::ExportPlanChanged);
+                    },
+                    Err(e) => self.errors.push(e),
+                }
+            }
+        } else if cancelled {
+            self.export_plan = None;
+        } else if replan {
+            let folder = self.export_plan.as_ref().map(|plan| plan.folder.clone()).unwrap_or_default();
+            match self.loaded_graphics.export_plan(&folder, self.normalize_names) {
+                Ok(plan) => self.export_plan = Some(plan),
+                Err(e) => {
+                    self.export_plan = None;
+                    self.errors.push(e);
+                },
+            }
+        }
+    }
+
+    fn load_library(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title("Choose Dwarf Fortress Folder")
+            .pick_folder() {
+            let (library, errors) = ReferenceLibrary::load(&path);
+
+            //problems in the vanilla files can't be fixed here, so only report files that were skipped
+            let problems = errors.len();
+            self.errors.extend(errors.into_iter().filter(|e| matches!(e, DFGHError::SkippedFileError(..) | DFGHError::SkippedFiles(..) | DFGHError::NoGraphicsDirectory(..))));
+            if problems > 0 && !library.mods.is_empty() {
+                self.errors.push(DFGHError::LibraryImportError(library.path.clone(), problems));
+            }
+
+            self.library = Some(library);
+            self.update_catalog();
+            self.lint();
+        }
+
+        self.action = Action::None;
+    }
+
+    //references are checked against the mod's own definitions, a loaded library only adds the vanilla ones
+    fn update_catalog(&mut self) {
+        self.loaded_graphics.shared.catalog = RawCatalog::from_sources(&DiskFileSystem, &self.path).0;
+        if let Some(library) = &self.library {
+            self.loaded_graphics.use_library_catalog(library);
+        }
+    }
+
+    fn lint(&mut self) {
+        self.diagnostics = self.linter.run(&self.loaded_graphics);
+        self.action = Action::None;
+    }
+
+    fn lint_window(&mut self, ctx: &Context) {
+        let mut open = self.lint_window;
+        let mut action = None;
+
+        egui::Window::new("Lint")
+            .open(&mut open)
+            .constrain(true)
+            .default_size([600.0, 400.0])
+            .show(ctx, |ui| {
+            action = self.linter.menu(ui, &self.diagnostics);
+            ui.separator();
+            if ui.button("Run Again").clicked() {
+                action = Some(LintAction::Rerun);
+            }
+        });
+
+        self.lint_window = open;
+        match action {
+            Some(LintAction::Rerun) => self.lint(),
+            Some(LintAction::Fix(fix)) => {
+                self.save_state();
+                fix.apply(&mut self.loaded_graphics);
+                self.lint();
+            },
+            Some(LintAction::PickTile(i_file, i_tile)) => self.pick_tile(i_file, i_tile),
+            None => {},
+        }
+    }
+
+    //select the menu of a tile, counted in the same order as the tile bounds check, to pick it on the preview
+    fn pick_tile(&mut self, i_file: usize, i_tile: usize) {
+        let mut count = 0;
+        let mut found = None;
+
+        match self.loaded_graphics.graphics_files.get(i_file) {
+            Some(GraphicsFile::CreatureFile(_, creatures, ..)) => {
+                'creatures: for (i_creature, creature) in creatures.iter().enumerate() {
+                    for i_simple_layer in 0..creature.simple_layers.len() {
+                        if count == i_tile {
+                            found = Some(([0, 0, i_file, i_creature, 0, 0, i_simple_layer, 0], MainWindow::SimpleLayerMenu));
+                            break 'creatures;
+                        }
+                        count += 1;
+                    }
+                    for (i_layer_set, layer_set) in creature.layer_sets.iter().enumerate() {
+                        for (i_layer_group, layer_group) in layer_set.layer_groups.iter().enumerate() {
+                            for (i_layer, layer) in layer_group.layers.iter().enumerate() {
+                                for (i_condition, condition) in layer.conditions.iter().enumerate() {
+                                    if matches!(condition, Condition::TissueSwap(..)) {
+                                        if count == i_tile {
+                                            found = Some(([0, 0, i_file, i_creature, i_layer_set, i_layer_group, i_layer, i_condition], MainWindow::ConditionMenu));
+                                            break 'creatures;
+                                        }
+                                        count += 1;
+                                    }
+                                }
+                                if count == i_tile {
+                                    found = Some(([0, 0, i_file, i_creature, i_layer_set, i_layer_group, i_layer, 0], MainWindow::LayerMenu));
+                                    break 'creatures;
+                                }
+                                count += 1;
+                            }
+                        }
+                    }
+                }
+            },
+            Some(GraphicsFile::StatueCreatureFile(_, statues, ..)) => {
+                found = Self::nth_tile(statues.iter().map(|s| s.tiles.len()), i_tile)
+                    .map(|(i_statue, i_statue_tile)| ([0, 0, i_file, i_statue, 0, 0, i_statue_tile, 0], MainWindow::StatueMenu));
+            },
+            Some(GraphicsFile::PlantFile(_, plants, ..)) => {
+                found = Self::nth_tile(plants.iter().map(|p| p.tiles.len()), i_tile)
+                    .map(|(i_plant, i_plant_tile)| ([0, 0, i_file, i_plant, 0, 0, i_plant_tile, 0], MainWindow::PlantMenu));
+            },
+            Some(GraphicsFile::TileGraphicsFile(_, tile_graphics, ..)) => {
+                found = Self::nth_tile(tile_graphics.iter().map(|t| t.tiles.len()), i_tile)
+                    .map(|(i_tile_graphic, i_graphic_tile)| ([0, 0, i_file, i_tile_graphic, 0, 0, i_graphic_tile, 0], MainWindow::TileGraphicMenu));
+            },
+            _ => {},
+        }
+
+        if let Some((indices, main_window)) = found {
+            self.indices = indices.into();
+            self.selected = ContextData::from(main_window);
+            self.main_window = main_window;
+            self.action = Action::Zoom(PreviewZoom::Selected);
+        }
+    }
+
+    //which block holds the nth tile of a file, and which of its tiles it is
+    fn nth_tile(tile_counts: impl Iterator<Item = usize>, n: usize) -> Option<(usize, usize)> {
+        let mut count = 0;
+        for (i_block, tiles) in tile_counts.enumerate() {
+            if n < count + tiles {
+                return Some((i_block, n - count));
+            }
+            count += tiles;
+        }
+        None
+    }
+
+    //name of the creature the layer menus are editing
+    fn selected_creature_name(&self) -> String {
+        match self.loaded_graphics.graphics_files.get(self.indices.graphics_file_index) {
+            Some(GraphicsFile::CreatureFile(_, creatures, ..)) => creatures
+                .get(self.indices.graphics_index)
+                .map(|c| c.name.clone())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    fn open(&mut self) {
+        if let Some(file) = rfd::FileDialog::new()
+            .set_title("Open Project")
+            .add_filter("DF Graphics Helper Project", &[Project::EXTENSION])
+            .pick_file() {
+            self.save_state();
+            match Project::load(&file) {
+                Ok(project) => {
+                    (self.loaded_graphics, self.path) = (project.graphics, project.path);
+                    self.linter.config = project.lint_config;
+                    self.update_catalog();
+                    self.lint();
+                    self.main_window = MainWindow::DefaultMenu;
+                },
+                Err(e) => self.errors.push(e),
+            }
+        }
+
+        self.action = Action::None;
+    }
+
+    fn save(&mut self) {
+        if let Some(file) = rfd::FileDialog::new()
+            .set_title("Save Project")
+            .add_filter("DF Graphics Helper Project", &[Project::EXTENSION])
+            .save_file() {
+            let mut project = Project::new(self.path.clone(), self.loaded_graphics.clone());
+            project.lint_config = self.linter.config.clone();
+            if let Err(e) = project.save(&file) {
+                self.errors.push(e);
+            }
+        }
+
+        self.action = Action::None;
+    }
+
     fn undo(&mut self) {
         if let Some(undo_state) = self.undo_buffer.pop() {
             self.redo_buffer.push(undo_state.clone());
@@ -346,8 +652,18 @@ impl DFGraphicsHelper {
                     ContextData::LayerGroup(_) |
                     ContextData::Layer(_) |
                     ContextData::SimpleLayer(_) |
+                    ContextData::Statue(_) => {
+                        if ui.button("Graphics File").clicked() {
+                            ui.close_menu();
+                            let data = ContextData::from(GraphicsFile::new());
+                            inner_action = Action::Insert(data);
+                        } else if ui.button("Statue").clicked() {
+                            ui.close_menu();
+                            let data = ContextData::from(Statue::new());
+                            inner_action = Action::Insert(data);
+                        }
+                    },
                     ContextData::Condition(_) |
-                    ContextData::Statue(_) |
                     ContextData::Plant(_) |
                     ContextData::TileGraphic(_) => {
                         if ui.button("Graphics File").clicked() {
@@ -418,6 +734,134 @@ impl DFGraphicsHelper {
         Ok(())
     }
 
+    fn paste_text(&mut self, text: &str) -> Result<()> {
+        //text this app put on the clipboard pastes the copied object as is, and so does any object
+        //that has no raw text form since the system clipboard doesn't hold it
+        let copied_text = Self::clipboard_text(&self.copied);
+        let has_copy = !matches!(self.copied, ContextData::None);
+        if (has_copy && copied_text.is_none()) || copied_text.is_some_and(|copied| copied.replace("\r\n", "\n") == text) {
+            return self.paste();
+        }
+
+        let (data, mut errors) = Self::read_clipboard(text);
+        self.errors.append(&mut errors);
+
+        if let ContextData::None = data {
+            self.action = Action::None;
+            return Err(DFGHError::UnknownClipboardText);
+        }
+        self.insert(data)?;
+
+        Ok(())
+    }
+
+    /// Raw text put on the system clipboard when an object is copied.
+    fn clipboard_text(data: &ContextData) -> Option<String> {
+        match data {
+            ContextData::Creature(creature) => Some(creature.display()),
+            ContextData::LayerSet(layer_set) => Some(layer_set.display()),
+            ContextData::LayerGroup(layer_group) => Some(layer_group.display()),
+            ContextData::Layer(layer) => Some(layer.display()),
+            ContextData::Condition(condition) => Some(condition.display()),
+            _ => None,
+        }
+    }
+
+    /// Read raw text from the system clipboard as whichever object its first token starts.
+    fn read_clipboard(text: &str) -> (ContextData, Vec<DFGHError>) {
+        let path = PathBuf::from("clipboard");
+        let raw_lines: Vec<String> = text.split_inclusive('\n').map(|l| l.to_string()).collect();
+        let first_token = Token::tokenize(&raw_lines, &path)
+            .first()
+            .map(|token| token[0].clone())
+            .unwrap_or_default();
+
+        match first_token.as_str() {
+            "" => (ContextData::None, Vec::new()),
+            "CREATURE_GRAPHICS" |
+            "CREATURE_CASTE_GRAPHICS" => {
+                let (creature, errors) = Creature::read_text(text, &path);
+                (ContextData::from(creature), errors)
+            },
+            "LAYER_SET" => {
+                let (layer_set, errors) = LayerSet::read_text(text, &path);
+                (ContextData::from(layer_set), errors)
+            },
+            "LAYER_GROUP" => {
+                let (layer_group, errors) = LayerGroup::read_text(text, &path);
+                (ContextData::from(layer_group), errors)
+            },
+            "LAYER" => {
+                let (layer, errors) = Layer::read_text(text, &path);
+                (ContextData::from(layer), errors)
+            },
+            token => {
+                let (condition, errors) = Condition::read_text(text, &path);
+                //anything unrecognized reads as a custom condition, only accept it if it looks like one
+                if matches!(condition, Condition::Custom(_)) && !token.starts_with("CONDITION_") {
+                    (ContextData::None, Vec::new())
+                } else {
+                    (ContextData::from(condition), errors)
+                }
+            },
+        }
+    }
+
+    /// The object currently selected in the tree.
+    fn selected_data(&self) -> ContextData {
+        let indices = &self.indices;
+        let graphics = &self.loaded_graphics;
+
+        let graphics_file = graphics.graphics_files.get(indices.graphics_file_index);
+        let creature = match graphics_file {
+            Some(GraphicsFile::CreatureFile(_, creatures, ..)) => creatures.get(indices.graphics_index),
+            _ => None,
+        };
+        let layer_set = creature.and_then(|c| c.layer_sets.get(indices.layer_set_index));
+        let layer_group = layer_set.and_then(|ls| ls.layer_groups.get(indices.layer_group_index));
+        let layer = layer_group.and_then(|lg| lg.layers.get(indices.layer_index));
+
+        let selected = match &self.selected {
+            ContextData::TilePageFile(_) => graphics.tile_page_files
+                .get(indices.tile_page_file_index)
+                .cloned()
+                .map(ContextData::from),
+            ContextData::TilePage(_) => graphics.tile_page_files
+                .get(indices.tile_page_file_index)
+                .and_then(|tpf| tpf.tile_pages.get(indices.tile_page_index))
+                .cloned()
+                .map(ContextData::from),
+            ContextData::GraphicsFile(_) => graphics_file.cloned().map(ContextData::from),
+            ContextData::Creature(_) => creature.cloned().map(ContextData::from),
+            ContextData::SimpleLayer(_) => creature
+                .and_then(|c| c.simple_layers.get(indices.layer_index))
+                .cloned()
+                .map(ContextData::from),
+            ContextData::LayerSet(_) => layer_set.cloned().map(ContextData::from),
+            ContextData::LayerGroup(_) => layer_group.cloned().map(ContextData::from),
+            ContextData::Layer(_) => layer.cloned().map(ContextData::from),
+            ContextData::Condition(_) => layer
+                .and_then(|l| l.conditions.get(indices.condition_index))
+                .cloned()
+                .map(ContextData::from),
+            ContextData::Statue(_) => match graphics_file {
+                Some(GraphicsFile::StatueCreatureFile(_, statues, ..)) => statues.get(indices.graphics_index).cloned().map(ContextData::from),
+                _ => None,
+            },
+            ContextData::Plant(_) => match graphics_file {
+                Some(GraphicsFile::PlantFile(_, plants, ..)) => plants.get(indices.graphics_index).cloned().map(ContextData::from),
+                _ => None,
+            },
+            ContextData::TileGraphic(_) => match graphics_file {
+                Some(GraphicsFile::TileGraphicsFile(_, tile_graphics, ..)) => tile_graphics.get(indices.graphics_index).cloned().map(ContextData::from),
+                _ => None,
+            },
+            ContextData::None => None,
+        };
+
+        selected.unwrap_or_default()
+    }
+
     fn insert(&mut self, data: ContextData) -> Result<()> {
         self.save_state();
 
@@ -453,7 +897,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Creature(creature) => {
-                if let GraphicsFile::CreatureFile(_, cs) = &mut graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     if indices.graphics_index < cs.len() {
@@ -464,7 +908,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::LayerSet(layer_set) => {
-                if let GraphicsFile::CreatureFile(_, cs) = &mut graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let lss = &mut cs.get_mut(indices.graphics_index)
@@ -478,7 +922,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::LayerGroup(layer_group) => {
-                if let GraphicsFile::CreatureFile(_, cs) = &mut graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let lgs = &mut cs.get_mut(indices.graphics_index)
@@ -495,7 +939,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Layer(layer) => {
-                if let GraphicsFile::CreatureFile(_, cs) = &mut graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let ls = &mut cs.get_mut(indices.graphics_index)
@@ -515,7 +959,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Condition(condition) => {
-                if let GraphicsFile::CreatureFile(_, cs) = &mut graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let conds = &mut cs.get_mut(indices.graphics_index)
@@ -534,7 +978,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::SimpleLayer(simple_layer) => {
-                if let GraphicsFile::CreatureFile(_, cs) = &mut graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let sls = &mut cs.get_mut(indices.graphics_index)
@@ -548,7 +992,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Statue(statue) => {
-                if let GraphicsFile::StatueCreatureFile(_, ss) = &mut graphics.graphics_files
+                if let GraphicsFile::StatueCreatureFile(_, ss, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     if indices.graphics_index < ss.len() {
@@ -559,7 +1003,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Plant(plant) => {
-                if let GraphicsFile::PlantFile(_, ps) = &mut graphics.graphics_files
+                if let GraphicsFile::PlantFile(_, ps, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     if indices.graphics_index < ps.len() {
@@ -570,7 +1014,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::TileGraphic(tile_graphic) => {
-                if let GraphicsFile::TileGraphicsFile(_, tgs) = &mut graphics.graphics_files
+                if let GraphicsFile::TileGraphicsFile(_, tgs, ..) = &mut graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     if indices.graphics_index < tgs.len() {
@@ -630,7 +1074,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Creature(_) => {
-                if let GraphicsFile::CreatureFile(_, cs) = graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     if indices.graphics_index < cs.len() {
@@ -644,7 +1088,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::LayerSet(_) => {
-                if let GraphicsFile::CreatureFile(_, cs) = graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let lss = &mut cs
@@ -662,7 +1106,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::LayerGroup(_) => {
-                if let GraphicsFile::CreatureFile(_, cs) = graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let lgs = &mut cs
@@ -683,7 +1127,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Layer(_) => {
-                if let GraphicsFile::CreatureFile(_, cs) = graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let ls = &mut cs
@@ -707,7 +1151,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Condition(_) => {
-                if let GraphicsFile::CreatureFile(_, cs) = graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let conds = &mut cs
@@ -726,7 +1170,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::SimpleLayer(_) => {
-                if let GraphicsFile::CreatureFile(_, cs) = graphics.graphics_files
+                if let GraphicsFile::CreatureFile(_, cs, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     let sls = &mut cs
@@ -744,7 +1188,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Statue(_) => {
-                if let GraphicsFile::StatueCreatureFile(_, ss) = graphics.graphics_files
+                if let GraphicsFile::StatueCreatureFile(_, ss, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     if indices.graphics_index < ss.len() {
@@ -758,7 +1202,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::Plant(_) => {
-                if let GraphicsFile::PlantFile(_, ps) = graphics.graphics_files
+                if let GraphicsFile::PlantFile(_, ps, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     if indices.graphics_index < ps.len() {
@@ -772,7 +1216,7 @@ impl DFGraphicsHelper {
                 }
             },
             ContextData::TileGraphic(_) => {
-                if let GraphicsFile::TileGraphicsFile(_, tgs) = graphics.graphics_files
+                if let GraphicsFile::TileGraphicsFile(_, tgs, ..) = graphics.graphics_files
                     .get_mut(indices.graphics_file_index)
                     .ok_or(DFGHError::IndexError)? {
                     if indices.graphics_index < tgs.len() {
@@ -795,7 +1239,8 @@ impl DFGraphicsHelper {
         self.texture = None;
         self.preview = false;
         self.preview_name = "".to_string();
-        self.loaded_graphics.update_shared(&self.path)
+        self.loaded_graphics.update_shared(&self.path);
+        self.update_catalog();
     }
 
     fn zoom(&mut self, zoom: PreviewZoom) {
@@ -853,6 +1298,15 @@ impl DFGraphicsHelper {
     fn main_tree(&mut self, ui: &mut Ui, ctx: &Context) {
         let graphics = &mut self.loaded_graphics;
 
+        if ui.add(egui::Label::new("Mod Info")
+            .wrap(false)
+            .sense(Sense::click()))
+            .clicked()
+        {
+            self.main_window = MainWindow::ModInfoMenu;
+        };
+        ui.separator();
+
         if ui.add(egui::Label::new("Tile Page Files")
             .wrap(false)
             .sense(Sense::click()))
@@ -944,7 +1398,7 @@ impl DFGraphicsHelper {
             .body(|ui| {
                 match graphics_file {
                     GraphicsFile::DefaultFile => {/*do nothing*/},
-                    GraphicsFile::CreatureFile(_, creatures) => {
+                    GraphicsFile::CreatureFile(_, creatures, ..) => {
                         //creatures
                         for (i_creature, creature) in creatures.iter_mut().enumerate() {
                             let id_c = ui.make_persistent_id(
@@ -1093,7 +1547,7 @@ impl DFGraphicsHelper {
                             });
                         }
                     },
-                    GraphicsFile::StatueCreatureFile(_, statues) => {
+                    GraphicsFile::StatueCreatureFile(_, statues, ..) => {
                         for (i_statue, statue) in statues.iter_mut().enumerate() {
                             let statue_response = ui.add(egui::Label::new(
                                 format!("{} {}",
@@ -1113,7 +1567,7 @@ impl DFGraphicsHelper {
                             });
                         }
                     },
-                    GraphicsFile::PlantFile(_, plants) => {
+                    GraphicsFile::PlantFile(_, plants, ..) => {
                         for (i_plant, plant) in plants.iter_mut().enumerate() {
                             let plant_response = ui.add(egui::Label::new(
                                 format!("{}", plant.name))
@@ -1131,7 +1585,7 @@ impl DFGraphicsHelper {
                             });
                         }
                     },
-                    GraphicsFile::TileGraphicsFile(_, tile_graphics) => {
+                    GraphicsFile::TileGraphicsFile(_, tile_graphics, ..) => {
                         for (i_tile_graphic, tile_graphics) in tile_graphics.iter_mut().enumerate() {
                             let tile_graphics_response = ui.add(egui::Label::new(
                                 format!("{}", tile_graphics.name))
@@ -1149,11 +1603,248 @@ impl DFGraphicsHelper {
                             });
                         }
                     },
+                    GraphicsFile::OtherFile(..) => {/*listed below*/},
+                }
+
+                //blocks this tool can't edit, shown read-only
+                for (i_block, block) in graphics_file.unsupported_blocks().iter().enumerate() {
+                    let block_response = ui.add(egui::Label::new(
+                        egui::RichText::new(format!("{} (unsupported)", block.name())).weak())
+                        .sense(Sense::click())
+                    );
+                    if block_response.clicked() {
+                        self.indices = [0, 0, i_file, i_block, 0, 0, 0, 0].into();
+                        self.main_window = MainWindow::UnsupportedBlockMenu;
+                    }
+                }
+            });
+        }
+
+        self.library_tree(ui, ctx);
+    }
+
+    fn library_tree(&mut self, ui: &mut Ui, ctx: &Context) {
+        if let Some(library) = &self.library {
+            ui.separator();
+            let id_library = ui.make_persistent_id("reference_library");
+            egui::collapsing_header::CollapsingState::load_with_default_open(ctx, id_library, false)
+                .show_header(ui, |ui| {
+                ui.label("Reference Library")
+                    .on_hover_text(format!("Read-only\n{}", library.path.display()));
+            })
+            .body(|ui| {
+                let mut selected = None;
+
+                //tile page files
+                for (i_tile_page_file, tile_page_file) in library.graphics.tile_page_files.iter().enumerate() {
+                    let id_t = ui.make_persistent_id(format!("library_tile_page_file{}", i_tile_page_file));
+                    egui::collapsing_header::CollapsingState::load_with_default_open(ctx, id_t, false)
+                        .show_header(ui, |ui| {
+                        let response = ui.add(egui::Label::new(
+                            tile_page_file.name.replace("tile_page_", ""))
+                            .wrap(false)
+                            .sense(Sense::click()));
+                        if response.clicked() {
+                            selected = Some(ContextData::from(tile_page_file.clone()));
+                        }
+                        response.context_menu(|ui| {
+                            if ui.button("Copy").clicked() {
+                                self.copied = ContextData::from(tile_page_file.clone());
+                                ui.close_menu();
+                            }
+                        });
+                    })
+                    .body(|ui| {
+                        for tile_page in tile_page_file.tile_pages.iter() {
+                            let response = ui.add(egui::Label::new(&tile_page.name)
+                                .wrap(false)
+                                .sense(Sense::click()));
+                            if response.clicked() {
+                                selected = Some(ContextData::from(tile_page.clone()));
+                            }
+                            response.context_menu(|ui| {
+                                if ui.button("Copy").clicked() {
+                                    self.copied = ContextData::from(tile_page.clone());
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                    });
+                }
+
+                //creature, statue, plant and tile graphics files
+                for (i_file, graphics_file) in library.graphics.graphics_files.iter().enumerate() {
+                    let id_g = ui.make_persistent_id(format!("library_graphics_file{}", i_file));
+                    egui::collapsing_header::CollapsingState::load_with_default_open(ctx, id_g, false)
+                        .show_header(ui, |ui| {
+                        let response = ui.add(egui::Label::new(graphics_file.name())
+                            .wrap(false)
+                            .sense(Sense::click()));
+                        if response.clicked() {
+                            selected = Some(ContextData::from(graphics_file.clone()));
+                        }
+                        response.context_menu(|ui| {
+                            if ui.button("Copy").clicked() {
+                                self.copied = ContextData::from(graphics_file.clone());
+                                ui.close_menu();
+                            }
+                        });
+                    })
+                    .body(|ui| {
+                        match graphics_file {
+                            GraphicsFile::CreatureFile(_, creatures, ..) => {
+                                for creature in creatures.iter() {
+                                    let response = ui.add(egui::Label::new(&creature.name)
+                                        .wrap(false)
+                                        .sense(Sense::click()));
+                                    if response.clicked() {
+                                        selected = Some(ContextData::from(creature.clone()));
+                                    }
+                                    response.context_menu(|ui| {
+                                        if ui.button("Copy").clicked() {
+                                            self.copied = ContextData::from(creature.clone());
+                                            ui.close_menu();
+                                        }
+                                    });
+                                }
+                            },
+                            GraphicsFile::StatueCreatureFile(_, statues, ..) => {
+                                for statue in statues.iter() {
+                                    let response = ui.add(egui::Label::new(format!("{} {}",
+                                        statue.creature_name,
+                                        statue.caste.clone().map_or("".to_string(), |c| c.name())))
+                                        .wrap(false)
+                                        .sense(Sense::click()));
+                                    if response.clicked() {
+                                        selected = Some(ContextData::Statue(statue.clone()));
+                                    }
+                                    response.context_menu(|ui| {
+                                        if ui.button("Copy").clicked() {
+                                            self.copied = ContextData::Statue(statue.clone());
+                                            ui.close_menu();
+                                        }
+                                    });
+                                }
+                            },
+                            GraphicsFile::PlantFile(_, plants, ..) => {
+                                for plant in plants.iter() {
+                                    let response = ui.add(egui::Label::new(&plant.name)
+                                        .wrap(false)
+                                        .sense(Sense::click()));
+                                    if response.clicked() {
+                                        selected = Some(ContextData::from(plant.clone()));
+                                    }
+                                    response.context_menu(|ui| {
+                                        if ui.button("Copy").clicked() {
+                                            self.copied = ContextData::from(plant.clone());
+                                            ui.close_menu();
+                                        }
+                                    });
+                                }
+                            },
+                            GraphicsFile::TileGraphicsFile(_, tile_graphics, ..) => {
+                                for tile_graphic in tile_graphics.iter() {
+                                    let response = ui.add(egui::Label::new(&tile_graphic.name)
+                                        .wrap(false)
+                                        .sense(Sense::click()));
+                                    if response.clicked() {
+                                        selected = Some(ContextData::from(tile_graphic.clone()));
+                                    }
+                                    response.context_menu(|ui| {
+                                        if ui.button("Copy").clicked() {
+                                            self.copied = ContextData::from(tile_graphic.clone());
+                                            ui.close_menu();
+                                        }
+                                    });
+                                }
+                            },
+                            _ => {},
+                        }
+                    });
+                }
+
+                if let Some(selected) = selected {
+                    self.library_selected = selected;
+                    self.main_window = MainWindow::LibraryMenu;
+                    self.action = Action::Zoom(PreviewZoom::Selected);
                 }
             });
         }
     }
 
+    fn library_menu(&mut self, ui: &mut Ui) -> Result<()> {
+        ui.horizontal(|ui| {
+            ui.label("Reference Library (read-only)");
+            if ui.button("Copy").clicked() {
+                self.copied = self.library_selected.clone();
+            }
+        });
+        ui.separator();
+
+        //previews look up the library's tile pages rather than the mod's
+        let display;
+        let preview_tile: Option<(String, [Option<[u32; 2]>; 2])>;
+        match &self.library_selected {
+            ContextData::TilePageFile(tile_page_file) => {
+                display = tile_page_file.display();
+                preview_tile = None;
+            },
+            ContextData::TilePage(tile_page) => {
+                display = tile_page.display();
+                preview_tile = Some((tile_page.name.clone(), [None, None]));
+            },
+            ContextData::GraphicsFile(graphics_file) => {
+                display = graphics_file.display();
+                preview_tile = None;
+            },
+            ContextData::Creature(creature) => {
+                display = creature.display();
+                preview_tile = creature
+                    .preview_tile()
+                    .map(|(tile_name, coords, large_coords)| (tile_name, [Some(coords), large_coords]));
+            },
+            ContextData::Statue(statue) => {
+                display = statue.display();
+                preview_tile = statue.tiles
+                    .first()
+                    .map(|tile| (tile.tile_name.clone(), [Some(tile.coords), tile.large_coords]));
+            },
+            ContextData::Plant(plant) => {
+                display = plant.display();
+                preview_tile = plant.tiles
+                    .first()
+                    .map(|tile| (tile.tile_name.clone(), [Some(tile.coords), None]));
+            },
+            ContextData::TileGraphic(tile_graphic) => {
+                display = tile_graphic.display();
+                preview_tile = tile_graphic.tiles
+                    .first()
+                    .map(|tile| (tile.tile_name.clone(), [Some(tile.coords), tile.large_coords]));
+            },
+            _ => {
+                display = String::new();
+                preview_tile = None;
+            },
+        }
+
+        match preview_tile {
+            Some((tile_name, selected_region)) => {
+                self.preview = true;
+                self.preview_name = format!("{}{}", LIBRARY_PREFIX, tile_name);
+                self.selected_region = selected_region;
+            },
+            None => {
+                self.preview = false;
+                self.preview_name = String::new();
+                self.selected_region = [None, None];
+            },
+        }
+
+        ui.add(egui::Label::new(egui::RichText::new(display).monospace()).wrap(false));
+
+        Ok(())
+    }
+
     fn default_menu(&mut self, ui: &mut Ui) -> Result<()> {
         ui.label("Welcome!");
         ui.separator();
@@ -1171,6 +1862,29 @@ impl DFGraphicsHelper {
         Ok(())
     }
 
+    fn mod_info_menu(&mut self, ui: &mut Ui) -> Result<()> {
+        ui.label("Mod Info Menu (info.txt)");
+
+        let shared = &mut self.loaded_graphics.shared;
+        match &mut self.loaded_graphics.mod_info {
+            Some(mod_info) => {
+                mod_info.menu(ui, shared);
+            },
+            None => {
+                ui.separator();
+                if ui.small_button("Create info.txt").clicked() {
+                    self.loaded_graphics.mod_info = Some(ModInfo::new());
+                }
+            },
+        }
+
+        self.preview = false;
+        self.preview_name = String::new();
+        self.selected_region = [None, None];
+
+        Ok(())
+    }
+
     fn tile_page_file_default_menu(&mut self, ui: &mut Ui) -> Result<()> {
         ui.label("Tile Page File Menu");
         ui.separator();
@@ -1208,6 +1922,7 @@ impl DFGraphicsHelper {
 
             ui.separator();
             ui.text_edit_singleline(&mut tile_page_file.name);
+            tile_page_file.raw.encoding_label(ui);
             ui.add_space(PADDING);
 
             if ui.button("New Tile Page").clicked() {
@@ -1294,24 +2009,24 @@ impl DFGraphicsHelper {
                     .selected_text(&graphics_file.name())
                     .show_ui(ui, |ui| {
                     ui.selectable_value(graphics_file,
-                        GraphicsFile::CreatureFile("(new)".to_string(), Vec::new()),
+                        GraphicsFile::CreatureFile("(new)".to_string(), Vec::new(), RawFile::new()),
                         "Creature"
                     );
                     ui.selectable_value(graphics_file,
-                        GraphicsFile::StatueCreatureFile("(new)".to_string(), Vec::new()),
+                        GraphicsFile::StatueCreatureFile("(new)".to_string(), Vec::new(), RawFile::new()),
                         "Statue"
                     );
                     ui.selectable_value(graphics_file,
-                        GraphicsFile::PlantFile("(new)".to_string(), Vec::new()),
+                        GraphicsFile::PlantFile("(new)".to_string(), Vec::new(), RawFile::new()),
                         "Plant"
                     );
                     ui.selectable_value(graphics_file,
-                        GraphicsFile::TileGraphicsFile("(new)".to_string(), Vec::new()),
+                        GraphicsFile::TileGraphicsFile("(new)".to_string(), Vec::new(), RawFile::new()),
                         "Tile Graphic"
                     );
                 });
             },
-            GraphicsFile::CreatureFile(name, _) => {
+            GraphicsFile::CreatureFile(name, ..) => {
                 ui.horizontal(|ui| {
                     ui.label("Creature File Menu");
                     if ui.button("Delete").clicked() {
@@ -1328,7 +2043,7 @@ impl DFGraphicsHelper {
                     self.action = Action::Insert(ContextData::Creature(Creature::new()));
                 }
             },
-            GraphicsFile::StatueCreatureFile(name, _) => {
+            GraphicsFile::StatueCreatureFile(name, ..) => {
                 ui.horizontal(|ui| {
                     ui.label("Statue File Menu");
                     if ui.button("Delete").clicked() {
@@ -1345,7 +2060,7 @@ impl DFGraphicsHelper {
                     self.action = Action::Insert(ContextData::Statue(Statue::new()));
                 }
             },
-            GraphicsFile::PlantFile(name, _) => {
+            GraphicsFile::PlantFile(name, ..) => {
                 ui.horizontal(|ui| {
                     ui.label("Plant File Menu");
                     if ui.button("Delete").clicked() {
@@ -1362,7 +2077,7 @@ impl DFGraphicsHelper {
                     self.action = Action::Insert(ContextData::Plant(Plant::new()));
                 }
             },
-            GraphicsFile::TileGraphicsFile(name, _) => {
+            GraphicsFile::TileGraphicsFile(name, ..) => {
                 ui.horizontal(|ui| {
                     ui.label("Tile Graphic File Menu");
                     if ui.button("Delete").clicked() {
@@ -1379,6 +2094,25 @@ impl DFGraphicsHelper {
                     self.action = Action::Insert(ContextData::TileGraphic(TileGraphic::new()));
                 }
             },
+            GraphicsFile::OtherFile(name, ..) => {
+                ui.horizontal(|ui| {
+                    ui.label("Unsupported Graphics File Menu");
+                    if ui.button("Delete").clicked() {
+                        self.action = Action::Delete(ContextData::GraphicsFile(GraphicsFile::new()));
+                    }
+                });
+
+                ui.add_space(PADDING);
+                ui.label("File Name:");
+                ui.text_edit_singleline(name);
+
+                ui.add_space(PADDING);
+                ui.label("The blocks in this file aren't supported yet. They will be exported unchanged.");
+            },
+        }
+        if let Some(raw) = graphics_file.raw() {
+            ui.add_space(PADDING);
+            raw.encoding_label(ui);
         }
 
         self.preview = false;
@@ -1408,19 +2142,19 @@ impl DFGraphicsHelper {
                     .selected_text(&graphics_file.name())
                     .show_ui(ui, |ui| {
                     ui.selectable_value(graphics_file,
-                        GraphicsFile::CreatureFile("(new)".to_string(), Vec::new()),
+                        GraphicsFile::CreatureFile("(new)".to_string(), Vec::new(), RawFile::new()),
                         "Creature"
                     );
                     ui.selectable_value(graphics_file,
-                        GraphicsFile::StatueCreatureFile("(new)".to_string(), Vec::new()),
+                        GraphicsFile::StatueCreatureFile("(new)".to_string(), Vec::new(), RawFile::new()),
                         "Statue"
                     );
                     ui.selectable_value(graphics_file,
-                        GraphicsFile::PlantFile("(new)".to_string(), Vec::new()),
+                        GraphicsFile::PlantFile("(new)".to_string(), Vec::new(), RawFile::new()),
                         "Plant"
                     );
                     ui.selectable_value(graphics_file,
-                        GraphicsFile::TileGraphicsFile("(new)".to_string(), Vec::new()),
+                        GraphicsFile::TileGraphicsFile("(new)".to_string(), Vec::new(), RawFile::new()),
                         "Tile Graphic"
                     );
                 });
@@ -1431,7 +2165,7 @@ impl DFGraphicsHelper {
                 
                 return Ok(())
             },
-            GraphicsFile::CreatureFile(_, creatures) => {
+            GraphicsFile::CreatureFile(_, creatures, ..) => {
                 ui.horizontal(|ui| {
                     ui.label("Creature Menu");
                     if ui.button("Delete").clicked() {
@@ -1451,7 +2185,7 @@ impl DFGraphicsHelper {
                 
                 return Ok(())
             }
-            GraphicsFile::StatueCreatureFile(_, statues) => {
+            GraphicsFile::StatueCreatureFile(_, statues, ..) => {
                 ui.horizontal(|ui| {
                     ui.label("Statue Menu");
                     if ui.button("Delete").clicked() {
@@ -1471,7 +2205,7 @@ impl DFGraphicsHelper {
                 
                 return Ok(())
             }
-            GraphicsFile::PlantFile(_, plants) => {
+            GraphicsFile::PlantFile(_, plants, ..) => {
                 ui.horizontal(|ui| {
                     ui.label("Plant Menu");
                     if ui.button("Delete").clicked() {
@@ -1491,7 +2225,7 @@ impl DFGraphicsHelper {
                 
                 return Ok(())
             }
-            GraphicsFile::TileGraphicsFile(_, tile_graphics) => {
+            GraphicsFile::TileGraphicsFile(_, tile_graphics, ..) => {
                 ui.horizontal(|ui| {
                     ui.label("Tile Graphic Menu");
                     if ui.button("Delete").clicked() {
@@ -1509,11 +2243,41 @@ impl DFGraphicsHelper {
                 self.preview_name = String::new();
                 self.selected_region = [None, None];
                 
+                return Ok(())
+            }
+            GraphicsFile::OtherFile(..) => {
+                self.main_window = MainWindow::UnsupportedBlockMenu;
+
                 return Ok(())
             }
         }
     }
 
+    fn unsupported_block_menu(&mut self, ui: &mut Ui) -> Result<()> {
+        ui.label("Unsupported Block (read-only)");
+        ui.separator();
+
+        let indices = &mut self.indices;
+        let graphics_file = self.loaded_graphics.graphics_files
+            .get(indices.graphics_file_index)
+            .ok_or(DFGHError::IndexError)?;
+        let block = graphics_file.unsupported_blocks()
+            .get(indices.graphics_index)
+            .ok_or(DFGHError::IndexError)?
+            .display();
+
+        ui.add_space(PADDING);
+        ui.label("This block will be exported unchanged.");
+        ui.add_space(PADDING);
+        ui.add(egui::Label::new(egui::RichText::new(block).monospace()).wrap(false));
+
+        self.preview = false;
+        self.preview_name = String::new();
+        self.selected_region = [None, None];
+
+        Ok(())
+    }
+
     fn layer_set_menu(&mut self, ui: &mut Ui) -> Result<()> {
         ui.horizontal(|ui| {
             ui.label("Layer Set Menu");
@@ -1524,7 +2288,7 @@ impl DFGraphicsHelper {
         
         let indices = &mut self.indices;
 
-        if let GraphicsFile::CreatureFile(_, creatures) = &mut self
+        if let GraphicsFile::CreatureFile(_, creatures, ..) = &mut self
             .loaded_graphics
             .graphics_files
             .get_mut(indices.graphics_file_index)
@@ -1564,7 +2328,7 @@ impl DFGraphicsHelper {
         
         let indices = &mut self.indices;
 
-        if let GraphicsFile::CreatureFile(_, creatures) = &mut self
+        if let GraphicsFile::CreatureFile(_, creatures, ..) = &mut self
             .loaded_graphics
             .graphics_files
             .get_mut(indices.graphics_file_index)
@@ -1607,7 +2371,7 @@ impl DFGraphicsHelper {
         
         let indices = &mut self.indices;
 
-        if let GraphicsFile::CreatureFile(_, creatures) = &mut self
+        if let GraphicsFile::CreatureFile(_, creatures, ..) = &mut self
             .loaded_graphics
             .graphics_files
             .get_mut(indices.graphics_file_index)
@@ -1656,7 +2420,7 @@ impl DFGraphicsHelper {
         
         let indices = &mut self.indices;
 
-        if let GraphicsFile::CreatureFile(_, creatures) = &mut self
+        if let GraphicsFile::CreatureFile(_, creatures, ..) = &mut self
             .loaded_graphics
             .graphics_files
             .get_mut(indices.graphics_file_index)
@@ -1719,7 +2483,7 @@ impl DFGraphicsHelper {
         
         let indices = &mut self.indices;
 
-        if let GraphicsFile::CreatureFile(_, creatures) = &mut self
+        if let GraphicsFile::CreatureFile(_, creatures, ..) = &mut self
             .loaded_graphics
             .graphics_files
             .get_mut(indices.graphics_file_index)
@@ -1762,7 +2526,7 @@ impl DFGraphicsHelper {
         
         let indices = &mut self.indices;
 
-        if let GraphicsFile::StatueCreatureFile(_, statues) = &mut self
+        if let GraphicsFile::StatueCreatureFile(_, statues, ..) = &mut self
             .loaded_graphics
             .graphics_files
             .get_mut(indices.graphics_file_index)
@@ -1778,13 +2542,31 @@ impl DFGraphicsHelper {
     
                 let shared = &mut self.loaded_graphics.shared;
     
+                //right clicks in the preview set the coordinates of the chosen tile
+                if !statue.tiles.is_empty() {
+                    indices.layer_index = indices.layer_index.min(statue.tiles.len() - 1);
+                    egui::ComboBox::from_label("Preview tile")
+                        .selected_text(statue.tiles[indices.layer_index].state.name())
+                        .show_ui(ui, |ui| {
+                        for (i_tile, tile) in statue.tiles.iter().enumerate() {
+                            ui.selectable_value(&mut indices.layer_index, i_tile, tile.state.name());
+                        }
+                    });
+                }
+
                 statue.menu(ui, shared);
     
-                self.preview = true;
-                self.preview_name = statue.tile_name.clone();
-                self.selected_region = [Some(statue.coords), statue.large_coords];
-                if let Some(coords) = self.cursor_coords {
-                    statue.coords = coords;
+                if let Some(tile) = statue.tiles.get_mut(indices.layer_index) {
+                    self.preview = true;
+                    self.preview_name = tile.tile_name.clone();
+                    self.selected_region = [Some(tile.coords), tile.large_coords];
+                    if let Some(coords) = self.cursor_coords {
+                        tile.coords = coords;
+                    }
+                } else {
+                    self.preview = false;
+                    self.preview_name = String::new();
+                    self.selected_region = [None, None];
                 }
             }
         }
@@ -1801,7 +2583,7 @@ impl DFGraphicsHelper {
         
         let indices = &mut self.indices;
 
-        if let GraphicsFile::PlantFile(_, plants) = &mut self
+        if let GraphicsFile::PlantFile(_, plants, ..) = &mut self
             .loaded_graphics
             .graphics_files
             .get_mut(indices.graphics_file_index)
@@ -1817,13 +2599,31 @@ impl DFGraphicsHelper {
     
                 let shared = &mut self.loaded_graphics.shared;
     
+                //right clicks in the preview set the coordinates of the chosen tile
+                if !plant.tiles.is_empty() {
+                    indices.layer_index = indices.layer_index.min(plant.tiles.len() - 1);
+                    egui::ComboBox::from_label("Preview tile")
+                        .selected_text(plant.tiles[indices.layer_index].stage.name())
+                        .show_ui(ui, |ui| {
+                        for (i_tile, tile) in plant.tiles.iter().enumerate() {
+                            ui.selectable_value(&mut indices.layer_index, i_tile, tile.stage.name());
+                        }
+                    });
+                }
+
                 plant.menu(ui, shared);
     
-                self.preview = true;
-                self.preview_name = plant.tile_name.clone();
-                self.selected_region = [None, None];
-                if let Some(coords) = self.cursor_coords {
-                    plant.coords[0] = Some(coords);
+                if let Some(tile) = plant.tiles.get_mut(indices.layer_index) {
+                    self.preview = true;
+                    self.preview_name = tile.tile_name.clone();
+                    self.selected_region = [Some(tile.coords), None];
+                    if let Some(coords) = self.cursor_coords {
+                        tile.coords = coords;
+                    }
+                } else {
+                    self.preview = false;
+                    self.preview_name = String::new();
+                    self.selected_region = [None, None];
                 }
             }
         }
@@ -1840,7 +2640,7 @@ impl DFGraphicsHelper {
         
         let indices = &mut self.indices;
 
-        if let GraphicsFile::TileGraphicsFile(_, tile_graphics) = &mut self
+        if let GraphicsFile::TileGraphicsFile(_, tile_graphics, ..) = &mut self
             .loaded_graphics
             .graphics_files
             .get_mut(indices.graphics_file_index)
@@ -1856,13 +2656,31 @@ impl DFGraphicsHelper {
     
                 let shared = &mut self.loaded_graphics.shared;
     
+                //right clicks in the preview set the coordinates of the chosen tile
+                if !tile_graphic.tiles.is_empty() {
+                    indices.layer_index = indices.layer_index.min(tile_graphic.tiles.len() - 1);
+                    egui::ComboBox::from_label("Preview tile")
+                        .selected_text(tile_graphic.tiles[indices.layer_index].state.clone())
+                        .show_ui(ui, |ui| {
+                        for (i_tile, tile) in tile_graphic.tiles.iter().enumerate() {
+                            ui.selectable_value(&mut indices.layer_index, i_tile, tile.state.clone());
+                        }
+                    });
+                }
+
                 tile_graphic.menu(ui, shared);
     
-                self.preview = true;
-                self.preview_name = tile_graphic.tile_name.clone();
-                self.selected_region = [Some(tile_graphic.coords), None];
-                if let Some(coords) = self.cursor_coords {
-                    tile_graphic.coords = coords;
+                if let Some(tile) = tile_graphic.tiles.get_mut(indices.layer_index) {
+                    self.preview = true;
+                    self.preview_name = tile.tile_name.clone();
+                    self.selected_region = [Some(tile.coords), tile.large_coords];
+                    if let Some(coords) = self.cursor_coords {
+                        tile.coords = coords;
+                    }
+                } else {
+                    self.preview = false;
+                    self.preview_name = String::new();
+                    self.selected_region = [None, None];
                 }
             }
         }
@@ -2017,8 +2835,13 @@ impl DFGraphicsHelper {
         } else {
             //load texture based on name if not loaded
             self.action = Action::Zoom(PreviewZoom::Selected);
-            let entry_option = self.loaded_graphics.shared.tile_page_info
-                .get_mut(&self.preview_name);
+            let entry_option = match self.preview_name.strip_prefix(LIBRARY_PREFIX) {
+                Some(tile_name) => self.library
+                    .as_mut()
+                    .and_then(|library| library.graphics.shared.tile_page_info.get_mut(tile_name)),
+                None => self.loaded_graphics.shared.tile_page_info
+                    .get_mut(&self.preview_name),
+            };
 
             if let Some(entry) = entry_option {
                 let image_path = entry.image_path.clone();
@@ -2052,6 +2875,16 @@ impl eframe::App for DFGraphicsHelper {
             error_window(self, ctx);
         }
 
+        //Export Window
+        if self.export_plan.is_some() {
+            self.export_window(ctx);
+        }
+
+        //Lint Window
+        if self.lint_window {
+            self.lint_window(ctx);
+        }
+
         //Draw File menu tab and internal items
         egui::TopBottomPanel::top("top control panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
@@ -2074,11 +2907,29 @@ impl eframe::App for DFGraphicsHelper {
                         self.action = Action::Export;
                         ui.close_menu();
                     }
+                    ui.separator();
+                    if ui.button("Open Project..").clicked() {
+                        self.action = Action::Open;
+                        ui.close_menu();
+                    }
+                    if ui.button("Save Project..").clicked() {
+                        self.action = Action::Save;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button("Load Reference Library..").clicked() {
+                        self.action = Action::LoadLibrary;
+                        ui.close_menu();
+                    }
                 });
                 if ui.button("Update").clicked() {
                     self.loaded_graphics.shared.clear();
                     self.action = Action::Update;
                 }
+                if ui.button("Lint").clicked() {
+                    self.lint_window = true;
+                    self.action = Action::Lint;
+                }
             });
         });
 
@@ -2112,8 +2963,10 @@ impl eframe::App for DFGraphicsHelper {
             //Draw main window by matching self.main_window
             egui::ScrollArea::horizontal()
                 .show(ui, |ui| {
+                self.loaded_graphics.shared.creature_name = self.selected_creature_name();
                 let result;
                 match self.main_window {
+                    MainWindow::ModInfoMenu =>              result = self.mod_info_menu(ui),
                     MainWindow::TilePageFileDefaultMenu =>  result = self.tile_page_file_default_menu(ui),
                     MainWindow::GraphicsFileDefaultMenu =>  result = self.graphics_file_default_menu(ui),
                     MainWindow::TilePageFileMenu =>         result = self.tile_page_file_menu(ui),
@@ -2128,6 +2981,8 @@ impl eframe::App for DFGraphicsHelper {
                     MainWindow::StatueMenu =>               result = self.statue_menu(ui),
                     MainWindow::PlantMenu =>                result = self.plant_menu(ui),//todo
                     MainWindow::TileGraphicMenu =>          result = self.tile_graphic_menu(ui),//todo
+                    MainWindow::UnsupportedBlockMenu =>     result = self.unsupported_block_menu(ui),
+                    MainWindow::LibraryMenu =>              result = self.library_menu(ui),
                     MainWindow::DefaultMenu =>              result = self.default_menu(ui),
                 }
                 if result.is_err() {
@@ -2191,17 +3046,19 @@ impl eframe::App for DFGraphicsHelper {
                 if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::E)) {
                     self.action = Action::Export;
                 }
-                if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::X)) {
-                    dbg!("Cut");
-                    self.action = Action::Cut(self.selected.clone());
-                }
-                if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::C)) {
-                    dbg!("Copy");
-                    self.action = Action::Copy(self.selected.clone());
-                }
-                if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::V)) {
-                    dbg!("Paste");
-                    self.action = Action::Paste;
+                if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::S)) {
+                    self.action = Action::Save;
+                }
+                //cut, copy and paste arrive as events carrying the system clipboard, leave them to text fields
+                if !ctx.wants_keyboard_input() {
+                    for event in ctx.input(|i| i.events.clone()) {
+                        match event {
+                            egui::Event::Cut => self.action = Action::Cut(self.selected_data()),
+                            egui::Event::Copy => self.action = Action::Copy(self.selected_data()),
+                            egui::Event::Paste(text) => self.action = Action::PasteText(text),
+                            _ => {},
+                        }
+                    }
                 }
                 if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::Q)) {
                     self.action = Action::Debug;
@@ -2217,14 +3074,23 @@ impl eframe::App for DFGraphicsHelper {
                     result = self.delete(selected.clone());
                 }
                 Action::Copy(selected) => {
+                    if let Some(text) = Self::clipboard_text(selected) {
+                        ctx.copy_text(text);
+                    }
                     self.copy(selected.clone());
                 }
                 Action::Cut(selected) => {
+                    if let Some(text) = Self::clipboard_text(selected) {
+                        ctx.copy_text(text);
+                    }
                     result = self.cut(selected.clone());
                 }
                 Action::Paste => {
                     result = self.paste();
                 }
+                Action::PasteText(text) => {
+                    result = self.paste_text(&text.clone());
+                }
                 Action::Duplicate(selected) => {
                     self.copy(selected.clone());
                     result = self.paste();
@@ -2244,9 +3110,21 @@ impl eframe::App for DFGraphicsHelper {
                 Action::Export => {
                     self.export();
                 }
+                Action::Open => {
+                    self.open();
+                }
+                Action::LoadLibrary => {
+                    self.load_library();
+                }
+                Action::Save => {
+                    self.save();
+                }
                 Action::Update => {
                     self.update();
                 }
+                Action::Lint => {
+                    self.lint();
+                }
                 Action::Zoom(zoom) => {
                     self.zoom(zoom.clone());
                 }
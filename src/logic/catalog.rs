@@ -0,0 +1,835 @@
+use std::path::{Path, PathBuf};
+
+use super::error::DFGHError;
+use super::vfs::FileSystem;
+use crate::{Encoding, EquipmentType, Token};
+
+/// An item defined in an item raw, e.g. `[ITEM_HELM:ITEM_HELM_HELM]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ItemDefinition {
+    pub kind: String,   //item object token, e.g. ITEM_HELM
+    pub name: String,   //item id, e.g. ITEM_HELM_HELM
+}
+impl ItemDefinition {
+    /// Equipment type the item can be selected as in a CONDITION_ITEM_WORN.
+    pub fn equipment(&self) -> EquipmentType {
+        EquipmentType::from(self.kind.trim_start_matches("ITEM_").to_string())
+    }
+}
+
+//body part type flags that BY_TYPE can select
+const BODY_PART_TYPES: [&str; 27] = [
+    "UPPERBODY", "LOWERBODY", "HEAD", "GRASP", "STANCE", "LIMB", "DIGIT", "MOUTH", "EMBEDDED",
+    "INTERNAL", "SIGHT", "HEAR", "SMELL", "BREATHE", "THOUGHT", "NERVOUS", "CIRCULATION", "SKELETON",
+    "GUTS", "FLIER", "JOINT", "APERTURE", "SOCKET", "CONNECTOR", "SMALL", "TOTEMABLE", "UNDER_PRESSURE",
+];
+
+//tissue style shapings the game has built in
+const BUILT_IN_SHAPINGS: [&str; 5] = ["CLEAN_SHAVEN", "NEATLY_COMBED", "BRAIDED", "DOUBLE_BRAIDS", "PONY_TAILS"];
+
+/// A color from a descriptor_color raw, e.g. `[COLOR:AMBER][NAME:amber][RGB:255:191:0]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ColorDefinition {
+    pub name: String,
+    pub rgb: [u8; 3],
+}
+
+/// An inorganic material from an inorganic raw, e.g. `[INORGANIC:IRON][USE_MATERIAL_TEMPLATE:METAL_TEMPLATE]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InorganicDefinition {
+    pub name: String,
+    pub material_template: String,
+    pub flags: Vec<String>,     //tokens without arguments, e.g. IS_METAL or ITEMS_WEAPON
+}
+impl InorganicDefinition {
+    pub fn is_metal(&self) -> bool {
+        self.flags.iter().any(|f| f.eq("IS_METAL")) || self.material_template.eq("METAL_TEMPLATE")
+    }
+}
+
+/// A BP_APPEARANCE_MODIFIER of the body parts selected by the SET_BP_GROUP above it.
+///
+/// e.g. `[SET_BP_GROUP:BY_CATEGORY:EYE][BP_APPEARANCE_MODIFIER:CLOSE_SET:0:70:90:100:110:130:200]`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AppearanceModifier {
+    pub selection: (String, String),    //e.g. (BY_CATEGORY, EYE)
+    pub name: String,                   //e.g. CLOSE_SET
+    pub range: [u32; 2],                //lowest and highest value
+}
+
+/// A creature defined in a creature raw, with the castes it has.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CreatureDefinition {
+    pub name: String,
+    pub castes: Vec<String>,
+    pub bodies: Vec<String>,            //body templates from BODY
+    pub body_detail_plans: Vec<String>, //from BODY_DETAIL_PLAN
+    pub tissues: Vec<String>,           //from TISSUE and USE_TISSUE_TEMPLATE
+    pub appearance_modifiers: Vec<AppearanceModifier>,
+    pub varied: bool,                   //applies creature variations, which can add or change appearance modifiers
+    pub selected: bool,                 //only seen in SELECT_CREATURE, the rest of it is defined elsewhere
+    pub copies_tags: bool,              //uses COPY_TAGS_FROM, so it has the tokens of another creature too
+}
+impl CreatureDefinition {
+    /// Creatures without CASTE tokens, or only selected from another file, aren't checked for castes.
+    pub fn has_caste(&self, caste: &str) -> bool {
+        self.selected || self.castes.is_empty() || self.castes.iter().any(|c| c.eq_ignore_ascii_case(caste))
+    }
+}
+
+/// A part of a body template, e.g. `[BP:RH:right hand:STP][GRASP][CATEGORY:HAND]`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BodyPartDefinition {
+    pub token: String,
+    pub categories: Vec<String>,
+    pub types: Vec<String>,
+}
+
+/// A body template from a body raw, which creatures build their bodies from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BodyDefinition {
+    pub name: String,
+    pub parts: Vec<BodyPartDefinition>,
+}
+
+/// A body detail plan, with the tissues it adds to a creature.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BodyDetailPlan {
+    pub name: String,
+    pub tissues: Vec<String>,
+    pub appearance_modifiers: Vec<AppearanceModifier>,
+    pub has_arguments: bool,    //leaves body parts or appearance modifier values to !ARG placeholders
+}
+
+/// Definitions read from the object raws of a mod, which the graphics refer to by name.
+///
+/// The vanilla definitions can be merged in from a reference library so that objects the mod
+/// doesn't define itself are known too.
+///
+/// ```
+/// # use df_texture_helper::logic::catalog::*;
+/// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+/// let mut files = MemoryFileSystem::new();
+/// files.insert("Mod/objects/item_helm.txt", "item_helm\n\n[OBJECT:ITEM]\n\n[ITEM_HELM:ITEM_HELM_HELM]\n\t[NAME:helm:helms]\n");
+/// files.insert("Mod/objects/item_weapon.txt", "item_weapon\n\n[OBJECT:ITEM]\n\n[ITEM_WEAPON:ITEM_WEAPON_PICK]\n");
+///
+/// let (catalog, errors) = RawCatalog::from_sources(&files, "Mod".as_ref());
+///
+/// assert!(errors.is_empty());
+/// assert_eq!(2, catalog.items.len());
+/// assert!(catalog.item("ITEM_HELM_HELM").is_some());
+/// assert!(catalog.item("ITEM_HELM_HLEM").is_none());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RawCatalog {
+    pub items: Vec<ItemDefinition>,
+    pub creatures: Vec<CreatureDefinition>,
+    pub bodies: Vec<BodyDefinition>,
+    pub body_detail_plans: Vec<BodyDetailPlan>,
+    pub tissue_templates: Vec<String>,
+    pub colors: Vec<ColorDefinition>,
+    pub shapes: Vec<String>,            //from descriptor_shape raws
+    pub tissue_styles: Vec<String>,     //shapings from TISSUE_STYLE_UNIT in creature raws
+    pub syndrome_classes: Vec<String>,  //from SYN_CLASS in any syndrome
+    pub inorganics: Vec<InorganicDefinition>,
+    pub vanilla: bool,      //vanilla definitions have been merged in
+}
+impl RawCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the definitions in the `objects` folder of a mod.
+    pub fn from_sources(file_system: &dyn FileSystem, folder: &Path) -> (RawCatalog, Vec<DFGHError>) {
+        let mut catalog = RawCatalog::new();
+        let mut errors = Vec::new();
+
+        //entries of the objects folder that can't be read are reported once here, read_objects leaves them out
+        if let Ok((_, entry_errors)) = file_system.read_dir_entries(&folder.join("objects")) {
+            errors.extend(entry_errors.into_iter().map(|e| DFGHError::SkippedFileError(folder.join("objects"), e.to_string())));
+        }
+
+        for (_, tokens) in read_objects(file_system, folder, "item_", &mut errors) {
+            catalog.read_items(&tokens);
+        }
+        for (_, tokens) in read_objects(file_system, folder, "creature_", &mut errors) {
+            catalog.read_creatures(&tokens);
+            catalog.read_syndrome_classes(&tokens);
+        }
+        for (_, tokens) in read_objects(file_system, folder, "body_", &mut errors) {
+            catalog.read_bodies(&tokens);
+        }
+        for (_, tokens) in read_objects(file_system, folder, "b_detail_plan_", &mut errors) {
+            catalog.read_body_detail_plans(&tokens);
+        }
+        for (_, tokens) in read_objects(file_system, folder, "tissue_template_", &mut errors) {
+            catalog.read_tissue_templates(&tokens);
+        }
+        for (_, tokens) in read_objects(file_system, folder, "descriptor_color_", &mut errors) {
+            catalog.read_colors(&tokens);
+        }
+        for (_, tokens) in read_objects(file_system, folder, "descriptor_shape_", &mut errors) {
+            catalog.read_shapes(&tokens);
+        }
+        for (_, tokens) in read_objects(file_system, folder, "inorganic_", &mut errors) {
+            catalog.read_inorganics(&tokens);
+            catalog.read_syndrome_classes(&tokens);
+        }
+        //syndromes are also defined in plant materials and interactions
+        for prefix in ["plant_", "interaction_"] {
+            for (_, tokens) in read_objects(file_system, folder, prefix, &mut errors) {
+                catalog.read_syndrome_classes(&tokens);
+            }
+        }
+
+        (catalog, errors)
+    }
+
+    fn read_items(&mut self, tokens: &[Token]) {
+        if !object_type(tokens).is_some_and(|o| o.eq("ITEM")) {
+            return;
+        }
+
+        for token in tokens.iter().filter(|t| t.len() >= 2 && t[0].starts_with("ITEM_")) {
+            if self.item(&token[1]).is_none() {
+                self.items.push(ItemDefinition { kind: token[0].clone(), name: token[1].clone() });
+            }
+        }
+    }
+
+    /// Read the creatures and their castes.
+    ///
+    /// SELECT_CREATURE and SELECT_CASTE add to a creature defined elsewhere, such as a vanilla one, so a creature
+    /// that is only selected isn't checked for castes.
+    /// ```
+    /// # use df_texture_helper::logic::catalog::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/objects/creature_mod.txt",
+    ///     "creature_mod\n\n[OBJECT:CREATURE]\n\n[CREATURE:GIANT_ANT]\n\t[CASTE:QUEEN]\n\t[CASTE:DRONE]\n\t[SELECT_CASTE:ALL]\n\n[SELECT_CREATURE:DWARF]\n\t[SELECT_CASTE:FEMALE]\n"
+    /// );
+    ///
+    /// let (catalog, _) = RawCatalog::from_sources(&files, "Mod".as_ref());
+    ///
+    /// assert_eq!(vec!["QUEEN", "DRONE"], catalog.creature("GIANT_ANT").unwrap().castes);
+    /// assert!(catalog.creature("DWARF").unwrap().has_caste("FEMALE"));
+    /// assert!(catalog.creature("DWARF").unwrap().has_caste("MALE"));
+    /// assert!(!catalog.creature("GIANT_ANT").unwrap().has_caste("MALE"));
+    /// assert!(catalog.creature("GIANT_BEE").is_none());
+    /// ```
+    fn read_creatures(&mut self, tokens: &[Token]) {
+        if !object_type(tokens).is_some_and(|o| o.eq("CREATURE")) {
+            return;
+        }
+
+        let mut current: Option<usize> = None;
+        let mut bp_group = None;
+        for token in tokens.iter().filter(|t| !t.is_empty()) {
+            if (token[0].starts_with("APPLY_") && token[0].ends_with("CREATURE_VARIATION")) || token[0].starts_with("CV_") {
+                if let Some(creature) = current.and_then(|i| self.creatures.get_mut(i)) {
+                    creature.varied = true;
+                }
+                continue;
+            }
+            if token.len() < 2 {
+                continue;
+            }
+            match token[0].as_str() {
+                "COPY_TAGS_FROM" => {
+                    if let Some(creature) = current.and_then(|i| self.creatures.get_mut(i)) {
+                        creature.copies_tags = true;
+                    }
+                },
+                "CREATURE" => {
+                    let i_creature = self.creature_index(&token[1]);
+                    self.creatures[i_creature].selected = false;
+                    current = Some(i_creature);
+                    bp_group = None;
+                },
+                "SELECT_CREATURE" => {
+                    let defined = self.creature(&token[1]).is_some();
+                    let i_creature = self.creature_index(&token[1]);
+                    if !defined {
+                        self.creatures[i_creature].selected = true;
+                    }
+                    current = Some(i_creature);
+                    bp_group = None;
+                },
+                "SET_BP_GROUP" | "BP_APPEARANCE_MODIFIER" => {
+                    if let Some(creature) = current.and_then(|i| self.creatures.get_mut(i)) {
+                        read_appearance_modifier(token, &mut bp_group, &mut creature.appearance_modifiers);
+                    }
+                },
+                "CASTE" | "SELECT_CASTE" if !token[1].eq("ALL") => {
+                    if let Some(creature) = current.and_then(|i| self.creatures.get_mut(i)) {
+                        push_unique(&mut creature.castes, &token[1]);
+                    }
+                },
+                "BODY" => {
+                    if let Some(creature) = current.and_then(|i| self.creatures.get_mut(i)) {
+                        for body in &token[1..] {
+                            push_unique(&mut creature.bodies, body);
+                        }
+                    }
+                },
+                "BODY_DETAIL_PLAN" => {
+                    if let Some(creature) = current.and_then(|i| self.creatures.get_mut(i)) {
+                        push_unique(&mut creature.body_detail_plans, &token[1]);
+                    }
+                },
+                "TISSUE" | "USE_TISSUE_TEMPLATE" => {
+                    if let Some(creature) = current.and_then(|i| self.creatures.get_mut(i)) {
+                        push_unique(&mut creature.tissues, &token[1]);
+                    }
+                },
+                "TISSUE_STYLE_UNIT" if token.len() >= 3 => {
+                    push_unique(&mut self.tissue_styles, &token[2]);
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Read the body templates and the categories and types of their parts.
+    ///
+    /// ```
+    /// # use df_texture_helper::logic::catalog::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/objects/body_mod.txt", "body_mod\n\n[OBJECT:BODY]\n\n[BODY:HANDS]\n\t[BP:RH:right hand:STP][GRASP][RIGHT][CATEGORY:HAND]\n");
+    /// files.insert("Mod/objects/creature_mod.txt", "creature_mod\n\n[OBJECT:CREATURE]\n\n[CREATURE:GOBLIN]\n\t[BODY:HANDS]\n\t[TISSUE:HAIR]\n");
+    ///
+    /// let (catalog, _) = RawCatalog::from_sources(&files, "Mod".as_ref());
+    ///
+    /// assert_eq!(vec!["HAND"], catalog.body_part_names("GOBLIN", "BY_CATEGORY"));
+    /// assert_eq!(vec!["GRASP"], catalog.body_part_names("GOBLIN", "BY_TYPE"));
+    /// assert_eq!(vec!["RH"], catalog.body_part_names("GOBLIN", "BY_TOKEN"));
+    /// assert_eq!(vec!["HAIR"], catalog.tissues_of("GOBLIN"));
+    /// ```
+    fn read_bodies(&mut self, tokens: &[Token]) {
+        if !object_type(tokens).is_some_and(|o| o.eq("BODY")) {
+            return;
+        }
+
+        for token in tokens.iter().filter(|t| !t.is_empty()) {
+            match (token[0].as_str(), token.len()) {
+                ("BODY", 2..) => {
+                    self.bodies.push(BodyDefinition { name: token[1].clone(), parts: Vec::new() });
+                },
+                ("BP", 2..) => {
+                    if let Some(body) = self.bodies.last_mut() {
+                        body.parts.push(BodyPartDefinition { token: token[1].clone(), ..Default::default() });
+                    }
+                },
+                ("CATEGORY", 2..) => {
+                    if let Some(part) = self.bodies.last_mut().and_then(|b| b.parts.last_mut()) {
+                        push_unique(&mut part.categories, &token[1]);
+                    }
+                },
+                (flag, 1) if BODY_PART_TYPES.contains(&flag) => {
+                    if let Some(part) = self.bodies.last_mut().and_then(|b| b.parts.last_mut()) {
+                        push_unique(&mut part.types, flag);
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn read_body_detail_plans(&mut self, tokens: &[Token]) {
+        if !object_type(tokens).is_some_and(|o| o.eq("BODY_DETAIL_PLAN")) {
+            return;
+        }
+
+        let mut bp_group = None;
+        for token in tokens.iter().filter(|t| t.len() >= 2) {
+            match token[0].as_str() {
+                "BODY_DETAIL_PLAN" => {
+                    self.body_detail_plans.push(BodyDetailPlan { name: token[1].clone(), ..Default::default() });
+                    bp_group = None;
+                },
+                "SET_BP_GROUP" | "BP_APPEARANCE_MODIFIER" => {
+                    if let Some(plan) = self.body_detail_plans.last_mut() {
+                        plan.has_arguments |= token.iter().any(|arg| arg.starts_with("!ARG"));
+                        read_appearance_modifier(token, &mut bp_group, &mut plan.appearance_modifiers);
+                    }
+                },
+                "ADD_TISSUE" => {
+                    if let Some(plan) = self.body_detail_plans.last_mut() {
+                        push_unique(&mut plan.tissues, &token[1]);
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn read_tissue_templates(&mut self, tokens: &[Token]) {
+        if !object_type(tokens).is_some_and(|o| o.eq("TISSUE_TEMPLATE")) {
+            return;
+        }
+
+        for token in tokens.iter().filter(|t| t.len() >= 2 && t[0].eq("TISSUE_TEMPLATE")) {
+            push_unique(&mut self.tissue_templates, &token[1]);
+        }
+    }
+
+    //index of a creature, added if it isn't known yet
+    fn creature_index(&mut self, name: &str) -> usize {
+        match self.creatures.iter().position(|c| c.name.eq_ignore_ascii_case(name)) {
+            Some(i_creature) => i_creature,
+            None => {
+                self.creatures.push(CreatureDefinition { name: name.to_string(), ..Default::default() });
+                self.creatures.len() - 1
+            },
+        }
+    }
+
+    /// Read the colors and their RGB values.
+    ///
+    /// ```
+    /// # use df_texture_helper::logic::catalog::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/objects/descriptor_color_mod.txt",
+    ///     "descriptor_color_mod\n\n[OBJECT:DESCRIPTOR_COLOR]\n\n[COLOR:AMBER]\n\t[NAME:amber]\n\t[RGB:255:191:0]\n"
+    /// );
+    /// files.insert(
+    ///     "Mod/objects/creature_mod.txt",
+    ///     "creature_mod\n\n[OBJECT:CREATURE]\n\n[CREATURE:GOBLIN]\n\t[TISSUE_STYLE_UNIT:HAIR:STANDARD_HAIR_SHAPINGS]\n"
+    /// );
+    ///
+    /// let (catalog, _) = RawCatalog::from_sources(&files, "Mod".as_ref());
+    ///
+    /// assert_eq!([255, 191, 0], catalog.color("AMBER").unwrap().rgb);
+    /// assert!(catalog.has_shaping("STANDARD_HAIR_SHAPINGS"));
+    /// assert!(catalog.has_shaping("NEATLY_COMBED"));
+    /// assert!(!catalog.has_shaping("NEATLY_COMBD"));
+    /// ```
+    fn read_colors(&mut self, tokens: &[Token]) {
+        if !object_type(tokens).is_some_and(|o| o.eq("DESCRIPTOR_COLOR")) {
+            return;
+        }
+
+        for token in tokens.iter().filter(|t| t.len() >= 2) {
+            match token[0].as_str() {
+                "COLOR" => {
+                    if self.color(&token[1]).is_none() {
+                        self.colors.push(ColorDefinition { name: token[1].clone(), rgb: [0, 0, 0] });
+                    }
+                },
+                "RGB" if token.len() >= 4 => {
+                    if let Some(color) = self.colors.last_mut() {
+                        for (i_channel, channel) in color.rgb.iter_mut().enumerate() {
+                            *channel = token[i_channel + 1].parse().unwrap_or(0);
+                        }
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    /// Read the inorganic materials and their flags.
+    ///
+    /// ```
+    /// # use df_texture_helper::logic::catalog::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/objects/inorganic_mod.txt",
+    ///     "inorganic_mod\n\n[OBJECT:INORGANIC]\n\n[INORGANIC:MITHRIL]\n\t[USE_MATERIAL_TEMPLATE:METAL_TEMPLATE]\n\t[ITEMS_WEAPON]\n\t[SYNDROME]\n\t\t[SYN_CLASS:SHINY]\n\n[INORGANIC:CHALK]\n\t[IS_STONE]\n"
+    /// );
+    /// files.insert(
+    ///     "Mod/objects/interaction_mod.txt",
+    ///     "interaction_mod\n\n[OBJECT:INTERACTION]\n\n[INTERACTION:BITE]\n\t[IE_SYNDROME]\n\t\t[SYN_CLASS:VAMPIRE_BITE]\n"
+    /// );
+    ///
+    /// let (catalog, _) = RawCatalog::from_sources(&files, "Mod".as_ref());
+    ///
+    /// assert!(catalog.inorganic("MITHRIL").unwrap().is_metal());
+    /// assert!(!catalog.inorganic("CHALK").unwrap().is_metal());
+    /// assert_eq!(vec!["SHINY", "VAMPIRE_BITE"], catalog.syndrome_classes);
+    /// ```
+    fn read_inorganics(&mut self, tokens: &[Token]) {
+        if !object_type(tokens).is_some_and(|o| o.eq("INORGANIC")) {
+            return;
+        }
+
+        for token in tokens.iter().filter(|t| !t.is_empty()) {
+            match (token[0].as_str(), token.len()) {
+                ("INORGANIC", 2..) => {
+                    self.inorganics.push(InorganicDefinition { name: token[1].clone(), ..Default::default() });
+                },
+                ("USE_MATERIAL_TEMPLATE", 2..) => {
+                    if let Some(inorganic) = self.inorganics.last_mut() {
+                        inorganic.material_template.clone_from(&token[1]);
+                    }
+                },
+                (flag, 1) => {
+                    if let Some(inorganic) = self.inorganics.last_mut() {
+                        push_unique(&mut inorganic.flags, flag);
+                    }
+                },
+                _ => {},
+            }
+        }
+    }
+
+    fn read_syndrome_classes(&mut self, tokens: &[Token]) {
+        for token in tokens.iter().filter(|t| t.len() >= 2 && t[0].eq("SYN_CLASS")) {
+            push_unique(&mut self.syndrome_classes, &token[1]);
+        }
+    }
+
+    fn read_shapes(&mut self, tokens: &[Token]) {
+        if !object_type(tokens).is_some_and(|o| o.eq("DESCRIPTOR_SHAPE")) {
+            return;
+        }
+
+        for token in tokens.iter().filter(|t| t.len() >= 2 && t[0].eq("SHAPE")) {
+            push_unique(&mut self.shapes, &token[1]);
+        }
+    }
+
+    /// Add the definitions of another catalog that this one doesn't have yet.
+    pub fn merge(&mut self, other: &RawCatalog) {
+        for item in &other.items {
+            if self.item(&item.name).is_none() {
+                self.items.push(item.clone());
+            }
+        }
+        for creature in &other.creatures {
+            let defined = self.creature(&creature.name).is_some();
+            let i_creature = self.creature_index(&creature.name);
+            let own = &mut self.creatures[i_creature];
+            own.varied |= creature.varied;
+            own.copies_tags |= creature.copies_tags;
+            //a creature defined in either catalog is no longer only selected
+            own.selected = if defined { own.selected && creature.selected } else { creature.selected };
+            for modifier in &creature.appearance_modifiers {
+                if !own.appearance_modifiers.contains(modifier) {
+                    own.appearance_modifiers.push(modifier.clone());
+                }
+            }
+            for (own_list, other_list) in [
+                (&mut own.castes, &creature.castes),
+                (&mut own.bodies, &creature.bodies),
+                (&mut own.body_detail_plans, &creature.body_detail_plans),
+                (&mut own.tissues, &creature.tissues),
+            ] {
+                for value in other_list {
+                    push_unique(own_list, value);
+                }
+            }
+        }
+        for body in &other.bodies {
+            if !self.bodies.iter().any(|b| b.name.eq_ignore_ascii_case(&body.name)) {
+                self.bodies.push(body.clone());
+            }
+        }
+        for plan in &other.body_detail_plans {
+            if !self.body_detail_plans.iter().any(|p| p.name.eq_ignore_ascii_case(&plan.name)) {
+                self.body_detail_plans.push(plan.clone());
+            }
+        }
+        for tissue_template in &other.tissue_templates {
+            push_unique(&mut self.tissue_templates, tissue_template);
+        }
+        for color in &other.colors {
+            if self.color(&color.name).is_none() {
+                self.colors.push(color.clone());
+            }
+        }
+        for shape in &other.shapes {
+            push_unique(&mut self.shapes, shape);
+        }
+        for tissue_style in &other.tissue_styles {
+            push_unique(&mut self.tissue_styles, tissue_style);
+        }
+        for syndrome_class in &other.syndrome_classes {
+            push_unique(&mut self.syndrome_classes, syndrome_class);
+        }
+        for inorganic in &other.inorganics {
+            if self.inorganic(&inorganic.name).is_none() {
+                self.inorganics.push(inorganic.clone());
+            }
+        }
+        self.vanilla |= other.vanilla;
+    }
+
+    pub fn item(&self, name: &str) -> Option<&ItemDefinition> {
+        self.items.iter().find(|i| i.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn creature(&self, name: &str) -> Option<&CreatureDefinition> {
+        self.creatures.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Castes of a creature, empty if it isn't known or has no CASTE tokens.
+    pub fn castes_of(&self, creature_name: &str) -> Vec<String> {
+        self.creature(creature_name).map(|c| c.castes.clone()).unwrap_or_default()
+    }
+
+    /// Parts of the bodies a creature is built from.
+    ///
+    /// Every known part when the creature or its bodies aren't known.
+    pub fn body_parts_of(&self, creature_name: &str) -> Vec<&BodyPartDefinition> {
+        let bodies: Vec<&BodyDefinition> = match self.creature(creature_name) {
+            Some(creature) => self.bodies
+                .iter()
+                .filter(|b| creature.bodies.iter().any(|name| name.eq_ignore_ascii_case(&b.name)))
+                .collect(),
+            None => Vec::new(),
+        };
+
+        if bodies.is_empty() {
+            self.bodies.iter().flat_map(|b| b.parts.iter()).collect()
+        } else {
+            bodies.into_iter().flat_map(|b| b.parts.iter()).collect()
+        }
+    }
+
+    /// Sorted names a creature's body parts can be selected by with BY_CATEGORY, BY_TYPE or BY_TOKEN.
+    pub fn body_part_names(&self, creature_name: &str, selection: &str) -> Vec<String> {
+        let mut names: Vec<String> = self.body_parts_of(creature_name)
+            .into_iter()
+            .flat_map(|part| match selection {
+                "BY_CATEGORY" => part.categories.clone(),
+                "BY_TYPE" => part.types.clone(),
+                "BY_TOKEN" => vec![part.token.clone()],
+                _ => Vec::new(),
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Sorted tissues a creature has from its own raws and its body detail plans.
+    ///
+    /// Every known tissue when the creature or its tissues aren't known.
+    pub fn tissues_of(&self, creature_name: &str) -> Vec<String> {
+        let tissues_of = |creature: &CreatureDefinition| {
+            let mut tissues = creature.tissues.clone();
+            for plan in self.body_detail_plans.iter().filter(|p| creature.body_detail_plans.iter().any(|name| name.eq_ignore_ascii_case(&p.name))) {
+                tissues.extend(plan.tissues.iter().cloned());
+            }
+            tissues
+        };
+
+        let mut tissues = self.creature(creature_name).map(tissues_of).unwrap_or_default();
+        if tissues.is_empty() {
+            tissues = self.creatures.iter().flat_map(tissues_of).collect();
+            tissues.extend(self.body_detail_plans.iter().flat_map(|p| p.tissues.iter().cloned()));
+        }
+        tissues.sort();
+        tissues.dedup();
+        tissues
+    }
+
+    /// Whether two body part selections, such as BY_CATEGORY:EYE and BY_TOKEN:REYE, share a part
+    /// of the creature's body.
+    pub fn selects_same_part(&self, creature_name: &str, a: (&str, &str), b: (&str, &str)) -> bool {
+        if a.0.eq_ignore_ascii_case(b.0) && a.1.eq_ignore_ascii_case(b.1) {
+            return true;
+        }
+
+        let selects = |part: &BodyPartDefinition, (selection, name): (&str, &str)| match selection {
+            "BY_CATEGORY" => part.categories.iter().any(|c| c.eq_ignore_ascii_case(name)),
+            "BY_TYPE" => part.types.iter().any(|t| t.eq_ignore_ascii_case(name)),
+            "BY_TOKEN" => part.token.eq_ignore_ascii_case(name),
+            _ => false,
+        };
+        self.body_parts_of(creature_name)
+            .into_iter()
+            .any(|part| selects(part, a) && selects(part, b))
+    }
+
+    /// Appearance modifiers a creature has from its own raws and its body detail plans.
+    ///
+    /// ```
+    /// # use df_texture_helper::logic::catalog::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/objects/creature_mod.txt",
+    ///     "creature_mod\n\n[OBJECT:CREATURE]\n\n[CREATURE:GOBLIN]\n\t[BODY_DETAIL_PLAN:EARS]\n\t[SET_BP_GROUP:BY_CATEGORY:EYE]\n\t\t[BP_APPEARANCE_MODIFIER:CLOSE_SET:0:70:90:100:110:130:200]\n"
+    /// );
+    /// files.insert(
+    ///     "Mod/objects/b_detail_plan_mod.txt",
+    ///     "b_detail_plan_mod\n\n[OBJECT:BODY_DETAIL_PLAN]\n\n[BODY_DETAIL_PLAN:EARS]\n\t[SET_BP_GROUP:BY_CATEGORY:EAR]\n\t\t[BP_APPEARANCE_MODIFIER:SPLAYED_OUT:0:0:0:0:0:0:100]\n"
+    /// );
+    ///
+    /// let (catalog, _) = RawCatalog::from_sources(&files, "Mod".as_ref());
+    /// let modifiers = catalog.appearance_modifiers_of("GOBLIN");
+    ///
+    /// assert_eq!(2, modifiers.len());
+    /// assert_eq!(("BY_CATEGORY".to_string(), "EYE".to_string()), modifiers[0].selection);
+    /// assert_eq!([0, 200], modifiers[0].range);
+    /// assert_eq!("SPLAYED_OUT", modifiers[1].name);
+    /// ```
+    pub fn appearance_modifiers_of(&self, creature_name: &str) -> Vec<&AppearanceModifier> {
+        match self.creature(creature_name) {
+            Some(creature) => creature.appearance_modifiers
+                .iter()
+                .chain(self.body_detail_plans
+                    .iter()
+                    .filter(|p| creature.body_detail_plans.iter().any(|name| name.eq_ignore_ascii_case(&p.name)))
+                    .flat_map(|p| p.appearance_modifiers.iter()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Whether every appearance modifier of a creature can be read from the raws. Creature variations,
+    /// COPY_TAGS_FROM and body detail plans filling in their arguments aren't resolved, so the modifiers of
+    /// creatures using them are only partly known. So are those of creatures only selected here, or using
+    /// a body detail plan that isn't in the raws.
+    ///
+    /// ```
+    /// # use df_texture_helper::logic::catalog::*;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert(
+    ///     "Mod/objects/creature_mod.txt",
+    ///     "creature_mod\n\n[OBJECT:CREATURE]\n\n[CREATURE:GOBLIN]\n\t[BODY_DETAIL_PLAN:EARS]\n\n[CREATURE:ELF]\n\t[APPLY_CREATURE_VARIATION:STANDARD_EARS]\n\n[CREATURE:DWARF]\n\t[BODY_DETAIL_PLAN:HEAD_ARGS:HEAD]\n\n\
+    ///     [CREATURE:ORC]\n\t[BODY_DETAIL_PLAN:EARS]\n\t[BODY_DETAIL_PLAN:VANILLA_PLAN]\n\n\
+    ///     [CREATURE:HALF_ORC]\n\t[COPY_TAGS_FROM:ORC]\n\n[SELECT_CREATURE:TROLL]\n\t[BODY_DETAIL_PLAN:EARS]\n"
+    /// );
+    /// files.insert(
+    ///     "Mod/objects/b_detail_plan_mod.txt",
+    ///     "b_detail_plan_mod\n\n[OBJECT:BODY_DETAIL_PLAN]\n\n[BODY_DETAIL_PLAN:EARS]\n\t[SET_BP_GROUP:BY_CATEGORY:EAR]\n\t\t[BP_APPEARANCE_MODIFIER:SPLAYED_OUT:0:0:0:0:0:0:100]\n\n[BODY_DETAIL_PLAN:HEAD_ARGS]\n\t[SET_BP_GROUP:BY_CATEGORY:!ARG1]\n\t\t[BP_APPEARANCE_MODIFIER:BROADNESS:90:95:98:100:102:105:110]\n"
+    /// );
+    ///
+    /// let (catalog, _) = RawCatalog::from_sources(&files, "Mod".as_ref());
+    ///
+    /// assert!(catalog.appearance_known("GOBLIN"));
+    /// assert!(!catalog.appearance_known("ELF"));
+    /// assert!(!catalog.appearance_known("DWARF"));
+    /// assert!(!catalog.appearance_known("ORC"));
+    /// assert!(!catalog.appearance_known("HALF_ORC"));
+    /// assert!(!catalog.appearance_known("TROLL"));
+    /// ```
+    pub fn appearance_known(&self, creature_name: &str) -> bool {
+        match self.creature(creature_name) {
+            Some(creature) => !creature.varied && !creature.selected && !creature.copies_tags && creature.body_detail_plans
+                .iter()
+                .all(|name| self.body_detail_plans
+                    .iter()
+                    .find(|p| p.name.eq_ignore_ascii_case(name))
+                    .is_some_and(|p| !p.has_arguments)),
+            None => false,
+        }
+    }
+
+    /// Lowest and highest value a creature's appearance modifier can have, over every body part it's
+    /// on or only those overlapping `selection`. None if the creature doesn't have the modifier there.
+    pub fn appearance_range(&self, creature_name: &str, name: &str, selection: Option<(&str, &str)>) -> Option<[u32; 2]> {
+        self.appearance_modifiers_of(creature_name)
+            .into_iter()
+            .filter(|m| m.name.eq_ignore_ascii_case(name))
+            .filter(|m| selection.map_or(true, |s| self.selects_same_part(creature_name, (&m.selection.0, &m.selection.1), s)))
+            .map(|m| m.range)
+            .reduce(|a, b| [a[0].min(b[0]), a[1].max(b[1])])
+    }
+
+    pub fn inorganic(&self, name: &str) -> Option<&InorganicDefinition> {
+        self.inorganics.iter().find(|i| i.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn has_syndrome_class(&self, name: &str) -> bool {
+        self.syndrome_classes.iter().any(|s| s.eq_ignore_ascii_case(name))
+    }
+
+    pub fn color(&self, name: &str) -> Option<&ColorDefinition> {
+        self.colors.iter().find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Sorted shapings a tissue may have: the built in styles, tissue style units and descriptor shapes.
+    pub fn shapings(&self) -> Vec<String> {
+        let mut shapings: Vec<String> = BUILT_IN_SHAPINGS
+            .iter()
+            .map(|s| s.to_string())
+            .chain(self.tissue_styles.iter().cloned())
+            .chain(self.shapes.iter().cloned())
+            .collect();
+        shapings.sort();
+        shapings.dedup();
+        shapings
+    }
+
+    pub fn has_shaping(&self, name: &str) -> bool {
+        self.shapings().iter().any(|s| s.eq_ignore_ascii_case(name))
+    }
+
+    /// Items that can be selected as an equipment type, sorted by name.
+    pub fn items_of(&self, equipment: &EquipmentType) -> Vec<&ItemDefinition> {
+        let mut items: Vec<&ItemDefinition> = self.items
+            .iter()
+            .filter(|i| equipment.eq(&EquipmentType::Any) || i.equipment().eq(equipment))
+            .collect();
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        items
+    }
+}
+
+//SET_BP_GROUP sets the body parts the BP_APPEARANCE_MODIFIER tokens after it apply to
+fn read_appearance_modifier(token: &Token, bp_group: &mut Option<(String, String)>, modifiers: &mut Vec<AppearanceModifier>) {
+    match token[0].as_str() {
+        "SET_BP_GROUP" if token.len() >= 3 => {
+            *bp_group = Some((token[1].clone(), token[2].clone()));
+        },
+        "BP_APPEARANCE_MODIFIER" if token.len() >= 9 => {
+            //body detail plans can leave the values to their arguments, those can't be checked
+            if let (Some(selection), Ok(low), Ok(high)) = (bp_group.as_ref(), token[2].parse(), token[8].parse()) {
+                modifiers.push(AppearanceModifier { selection: selection.clone(), name: token[1].clone(), range: [low, high] });
+            }
+        },
+        _ => {},
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+        list.push(value.to_string());
+    }
+}
+
+//object type of a raw file, from its [OBJECT:...] token
+fn object_type(tokens: &[Token]) -> Option<&String> {
+    tokens.iter().find(|t| t.len() >= 2 && t[0].eq("OBJECT")).map(|t| &t[1])
+}
+
+//tokens of every objects/<prefix>*.txt file in a mod folder, files that can't be read are skipped
+fn read_objects(file_system: &dyn FileSystem, folder: &Path, prefix: &str, errors: &mut Vec<DFGHError>) -> Vec<(PathBuf, Vec<Token>)> {
+    let mut paths: Vec<PathBuf> = file_system
+        .read_dir(&folder.join("objects"))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|path| path.file_name().is_some_and(|f| {
+            let f = f.to_string_lossy().to_lowercase();
+            f.starts_with(prefix) && f.ends_with(".txt")
+        }))
+        .collect();
+    paths.sort();
+
+    let mut files = Vec::new();
+    for path in paths {
+        match file_system.read(&path) {
+            Ok(bytes) => {
+                let raw_lines = Encoding::decode(&bytes).0
+                    .lines()
+                    .map(|l| l.to_string())
+                    .collect::<Vec<String>>();
+                let tokens = Token::tokenize(&raw_lines, &path);
+                files.push((path, tokens));
+            },
+            Err(e) => errors.push(DFGHError::SkippedFileError(path, e.to_string())),
+        }
+    }
+
+    files
+}
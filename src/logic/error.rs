@@ -1,9 +1,9 @@
 use std::path;
-use std::ops::RangeInclusive;
+use std::ops::{Range, RangeInclusive};
 
 use egui::Context;
 
-use super::super::PADDING;
+use super::super::{PADDING, Token};
 use super::app;
 use app::{DFGraphicsHelper, MainWindow};
 
@@ -36,21 +36,96 @@ pub enum DFGHError {
     #[error("Expected {0} fields, found {1}")]
     ImportIndexError(usize, usize),
 
+    #[error("Unable to read a file, it was skipped and the rest of the mod was imported:\n\t{0}\n\n{1}")]
+    SkippedFileError(std::path::PathBuf, String),
+
+    #[error("Unable to read {} files, they were skipped and the rest of the mod was imported:\n{}", .0.len(), skipped_list(.0))]
+    SkippedFiles(Vec<(std::path::PathBuf, String)>),
+
     #[error("File name includes unsupported characters(non UTF-8):\n\t{0}")]
     UnsupportedFileName(std::path::PathBuf),
 
+    #[error("Unable to save a file as {1}, it contains characters that can't be encoded:\n\t{0}\n\n\t{2}")]
+    EncodeError(std::path::PathBuf, String, String),
+
+    #[error("Unable to export, two objects would be saved to the same file:\n\t{0}\n\n{1}\n{2}\n\nRename one of them or export with normalized file names.")]
+    ExportCollision(std::path::PathBuf, String, String),
+
+    #[error("The graphics or the files in the mod folder changed after the export was previewed, nothing was written.\n\nThe preview has been updated, check it and export again.")]
+    ExportPlanChanged,
+
+    #[error("Unable to export {0}, the files written before it were put back as they were.\n\n{1}")]
+    ExportRolledBack(std::path::PathBuf, String),
+
+    #[error("Unable to export {0}, and these files written before it couldn't be put back:\n{2}\nRestore them from the backup if one was made.\n\n{1}")]
+    ExportPartlyWritten(std::path::PathBuf, String, String),
+
+    #[error("Failed to read or save the project file.\n\n{0}")]
+    ProjectError(#[from] serde_json::Error),
+
+    #[error("{1} problems were found while loading the reference library at:\n\t{0}\n\nObjects that couldn't be read may be missing or incomplete.")]
+    LibraryImportError(std::path::PathBuf, usize),
+
+    #[error("Statue tiles should be 1 wide and 2 tall, this one is {0} wide and {1} tall.")]
+    StatueShapeWarning(u32, u32),
+
+    #[error("Nothing was pasted, the clipboard doesn't hold a creature, layer set, layer group, layer or condition.")]
+    UnknownClipboardText,
+
+    #[error("{0} uses palette {1}, which isn't declared with LS_PALETTE in the layer set.")]
+    UndeclaredPalette(String, String),
+
+    #[error("{0} uses row {2} of palette {1}, which only has rows 0 to {3}.")]
+    PaletteRowOutOfRange(String, String, u32, u32),
+
+    #[error("{0} refers to item {1}, which isn't defined in the item raws.")]
+    UnknownItem(String, String),
+
+    #[error("{0} refers to creature {1}, which isn't defined in the creature raws.")]
+    UnknownCreature(String, String),
+
+    #[error("{0} refers to caste {2}, which creature {1} doesn't have.")]
+    UnknownCaste(String, String, String),
+
+    #[error("{0} selects {1}, which creature {2} doesn't have.")]
+    UnknownBodyReference(String, String, String),
+
+    #[error("{0} uses {1}, which isn't defined in the raws.")]
+    UnknownDescriptor(String, String),
+
+    #[error("{0} uses {1} {2}, which isn't defined in the raws.")]
+    UnknownMaterial(String, String, String),
+
+    #[error("{0} checks appearance modifier {1} on {2}, which creature {3} doesn't have there, so the layer never shows.")]
+    MissingAppearanceModifier(String, String, String, String),
+
+    #[error("{0} checks appearance modifier {1} between {2} and {3}, which can never happen as it only ranges from {4} to {5}.")]
+    ImpossibleAppearanceRange(String, String, u32, u32, u32, u32),
+
+    #[error("{0} uses tile {1} of tile page {2}, which only has tiles 0:0 to {3}:{4}.")]
+    TileOutOfBounds(String, String, String, u32, u32),
+
+    #[error("{0} uses tile page {1}, which no TILE_PAGE defines.{2}")]
+    UnknownTilePage(String, String, String),
+
+    #[error("Tile page {0} isn't used by any graphics.")]
+    UnusedTilePage(String),
+
+    #[error("graphics/{0} isn't used by any tile page or palette.")]
+    OrphanImage(std::path::PathBuf),
+
     #[error("No valid directory found at:\n\t\"{0}\"\n\nFormat is \"mod_name (numeric version)/graphics/\"")]
     NoGraphicsDirectory(std::path::PathBuf),
 
     #[error("Should not be displayed. Error String: {3}")]
     ImportBufferError(usize, usize, RangeInclusive<usize>, String),
 
-    #[error("Failed to import line {0} in file:\n\n{1}\n\n{2}\n\n{3}")]
-    ImportError(usize, std::path::PathBuf, String, String),
+    #[error("Failed to import line {0}, column {1} in file:\n\n{2}\n\n{3}\n\n{4}")]
+    ImportError(usize, usize, std::path::PathBuf, String, String),
 }
 
 //todo make fn truncate before and after relevant line
-fn highlight_error(raw_buffer: Vec<String>, i_line: usize, r_error: RangeInclusive<usize>) -> String {
+fn highlight_error(raw_buffer: Vec<String>, i_line: usize, columns: Range<usize>, r_error: RangeInclusive<usize>) -> String {
     let mut highlighted = String::new();
     let display_range = 8;
 
@@ -61,12 +136,13 @@ fn highlight_error(raw_buffer: Vec<String>, i_line: usize, r_error: RangeInclusi
     for (i, raw_line) in raw_buffer.iter().enumerate() {
         if i.abs_diff(i_line) <= display_range && i.ne(&i_line) {
             highlighted.push('\n');
-            highlighted.push_str(&raw_line);
+            highlighted.push_str(raw_line.trim_end_matches(['\r', '\n']));
         } else if i.eq(&i_line) {
+            //only look for breaks inside the token that caused the error
             let breaks = raw_line
                 .chars()
                 .enumerate()
-                .filter(|(_, c)| *c == '[' || *c == ']' || *c == ':')
+                .filter(|(i, c)| columns.contains(i) && (*c == '[' || *c == ']' || *c == ':'))
                 .map(|(i, _)| i)
                 .collect::<Vec<usize>>();
 
@@ -96,7 +172,7 @@ fn highlight_error(raw_buffer: Vec<String>, i_line: usize, r_error: RangeInclusi
                 }
             }
             highlighted.push('\n');
-            highlighted.push_str(&raw_line);
+            highlighted.push_str(raw_line.trim_end_matches(['\r', '\n']));
             highlighted.push('\n');
             highlighted.push_str(&highlight);
         }
@@ -109,37 +185,112 @@ fn highlight_error(raw_buffer: Vec<String>, i_line: usize, r_error: RangeInclusi
     highlighted
 }
 
-pub fn wrap_import_file_error(raw_buffer: Vec<String>, e: &DFGHError, i_line: usize, path: &path::PathBuf) -> DFGHError {
+/// Give an error raised while reading a file the file, line and column it was raised at.
+///
+/// ```
+/// # use df_texture_helper::*;
+/// # use df_texture_helper::logic::error::*;
+/// # use std::path::Path;
+/// let raw_lines = vec!["[STATUE_CREATURE_GRAPHICS:DWARF]\n".to_string(), "\t[DEFAULT:STATUES:0:0]\n".to_string()];
+/// let path = Path::new("graphics_statues.txt");
+/// let tokens = Token::tokenize(&raw_lines, path);
+///
+/// let e = wrap_import_file_error(raw_lines, &tokens, &DFGHError::StatueShapeWarning(1, 1), 1, path);
+///
+/// assert!(matches!(&e, DFGHError::ImportError(2, 2, ..)));
+/// assert!(e.to_string().contains("this one is 1 wide and 1 tall"));
+/// ```
+pub fn wrap_import_file_error(raw_buffer: Vec<String>, buffer: &[Token], e: &DFGHError, i_token: usize, path: &path::Path) -> DFGHError {
     match e {
-        DFGHError::ImportBufferError(i_rel_line, _buffer_len, r_error, error_string) => {
-            let line_index = (i_line).saturating_sub(*i_rel_line);
+        DFGHError::ImportBufferError(i_rel_token, _buffer_len, r_error, error_string) => {
+            let token = buffer
+                .get(i_token.saturating_sub(*i_rel_token))
+                .cloned()
+                .unwrap_or_default();
 
             DFGHError::ImportError(
-                line_index + 1,
+                token.line + 1,
+                token.columns.start + 1,
                 path.to_path_buf(),
-                highlight_error(raw_buffer, line_index, r_error.clone()),
+                highlight_error(raw_buffer, token.line, token.columns, r_error.clone()),
                 error_string.to_string(),
             )
         },
-        _ => DFGHError::ImportUnknownError,
+        //errors raised without a field to point at are shown on the whole token they were raised at
+        _ => {
+            let token = buffer
+                .get(i_token)
+                .or(buffer.last())
+                .cloned()
+                .unwrap_or_default();
+
+            DFGHError::ImportError(
+                token.line + 1,
+                token.columns.start + 1,
+                path.to_path_buf(),
+                highlight_error(raw_buffer, token.line, token.columns, 0..=usize::MAX),
+                e.to_string(),
+            )
+        },
     }
 }
 
+fn skipped_list(files: &[(path::PathBuf, String)]) -> String {
+    files.iter().map(|(path, e)| format!("\t{}\n\t\t{}\n", path.display(), e)).collect()
+}
+
+/// Report every file that was skipped in one list, where the first of them was reported.
+/// ```
+/// # use df_texture_helper::logic::error::*;
+/// let errors = vec![
+///     DFGHError::SkippedFileError("Mod/info.txt".into(), "locked".to_string()),
+///     DFGHError::UnsupportedFileName("Mod/graphics/\u{fffd}.txt".into()),
+///     DFGHError::SkippedFileError("Mod/graphics/graphics_creatures.txt".into(), "locked".to_string()),
+/// ];
+///
+/// let errors = group_skipped_files(errors);
+///
+/// assert_eq!(2, errors.len());
+/// assert!(matches!(&errors[0], DFGHError::SkippedFiles(files) if files.len() == 2));
+/// ```
+pub fn group_skipped_files(errors: Vec<DFGHError>) -> Vec<DFGHError> {
+    let mut grouped = Vec::with_capacity(errors.len());
+    let mut skipped = Vec::new();
+    let mut first_skipped = None;
+
+    for e in errors {
+        match e {
+            DFGHError::SkippedFileError(path, e_string) => skipped.push((path, e_string)),
+            DFGHError::SkippedFiles(mut files) => skipped.append(&mut files),
+            e => {
+                grouped.push(e);
+                continue;
+            },
+        }
+        first_skipped.get_or_insert(grouped.len());
+    }
+
+    if let Some(i_first) = first_skipped {
+        let e = if skipped.len() == 1 {
+            let (path, e_string) = skipped.remove(0);
+            DFGHError::SkippedFileError(path, e_string)
+        } else {
+            DFGHError::SkippedFiles(skipped)
+        };
+        grouped.insert(i_first, e);
+    }
+
+    grouped
+}
+
 pub fn wrap_import_buffer_error(i_rel_line: usize,  buffer_len: usize, r_error: RangeInclusive<usize>, e: &DFGHError) -> DFGHError {
     match e {
-        DFGHError::IoError(_) |
-        DFGHError::ImageError(_) |
-        DFGHError::ImportParseError(_) |
-        DFGHError::ImportMismatchError |
-        DFGHError::UnsupportedFileName(_) => {
-            DFGHError::ImportBufferError(buffer_len.saturating_sub(i_rel_line), buffer_len, r_error, e.to_string())
-        },
         DFGHError::ImportBufferError(i_rel, _b_len, r_e, e_string) => {
             let line_index = (i_rel + buffer_len).saturating_sub(i_rel_line);
             
             DFGHError::ImportBufferError(line_index, buffer_len, r_e.clone(), e_string.clone())
         },
-        _ => DFGHError::ImportUnknownError,
+        _ => DFGHError::ImportBufferError(buffer_len.saturating_sub(i_rel_line), buffer_len, r_error, e.to_string()),
     }
 }
 
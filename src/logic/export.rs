@@ -0,0 +1,333 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::error::{DFGHError, Result};
+use super::vfs::FileSystem;
+use crate::Encoding;
+
+/// A file to be written by an export, relative to the mod folder.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExportFile {
+    pub path: PathBuf,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ExportStatus {
+    #[default]
+    Create,     //file doesn't exist yet
+    Change,     //file exists with different contents
+    Unchanged,  //file exists with the same contents and won't be written
+    Stale,      //raw file in the graphics folder that nothing will be exported to
+}
+impl ExportStatus {
+    pub fn name(&self) -> String {
+        match self {
+            ExportStatus::Create => "Create".to_string(),
+            ExportStatus::Change => "Change".to_string(),
+            ExportStatus::Unchanged => "Unchanged".to_string(),
+            ExportStatus::Stale => "Stale".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlannedFile {
+    pub path: PathBuf,          //relative to the mod folder
+    pub status: ExportStatus,
+    pub bytes: Vec<u8>,         //contents to write, empty for stale files
+    pub diff: String,           //line diff against the file currently on disk
+}
+
+/// Everything an export would do to a mod folder, worked out before anything is written.
+///
+/// ```
+/// # use df_texture_helper::logic::export::*;
+/// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+/// let mut files = MemoryFileSystem::new();
+/// files.insert("Mod/graphics/tile_page_a.txt", "[A]\n[B]\n");
+/// files.insert("Mod/graphics/tile_page_old.txt", "[OLD]\n");
+///
+/// let plan = ExportPlan::new(&files, "Mod".as_ref(), vec![
+///     ExportFile { path: "graphics/tile_page_a.txt".into(), bytes: b"[A]\n[C]\n".to_vec() },
+///     ExportFile { path: "graphics/tile_page_b.txt".into(), bytes: b"[B]\n".to_vec() },
+/// ]);
+///
+/// assert_eq!(ExportStatus::Change, plan.files[0].status);
+/// assert_eq!("@@ line 1 @@\n  [A]\n- [B]\n+ [C]\n", plan.files[0].diff);
+/// assert_eq!(ExportStatus::Create, plan.files[1].status);
+/// assert_eq!(ExportStatus::Stale, plan.files[2].status);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExportPlan {
+    pub folder: PathBuf,
+    pub files: Vec<PlannedFile>,
+}
+impl ExportPlan {
+    pub fn new(file_system: &dyn FileSystem, folder: &Path, export_files: Vec<ExportFile>) -> Self {
+        let mut files: Vec<PlannedFile> = export_files
+            .into_iter()
+            .map(|export_file| {
+                match file_system.read(&folder.join(&export_file.path)) {
+                    Ok(old_bytes) if old_bytes == export_file.bytes => {
+                        PlannedFile { path: export_file.path, status: ExportStatus::Unchanged, bytes: export_file.bytes, diff: String::new() }
+                    },
+                    Ok(old_bytes) => {
+                        let diff = diff(&Encoding::decode(&old_bytes).0, &Encoding::decode(&export_file.bytes).0);
+                        PlannedFile { path: export_file.path, status: ExportStatus::Change, bytes: export_file.bytes, diff }
+                    },
+                    Err(_) => {
+                        let diff = diff("", &Encoding::decode(&export_file.bytes).0);
+                        PlannedFile { path: export_file.path, status: ExportStatus::Create, bytes: export_file.bytes, diff }
+                    },
+                }
+            })
+            .collect();
+
+        //raw files already in the graphics folder that won't be overwritten
+        let mut stale: Vec<PlannedFile> = file_system
+            .read_dir(&folder.join("graphics"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|path| path.strip_prefix(folder).ok().map(|p| p.to_path_buf()))
+            .filter(|path| path.extension().is_some_and(|e| e.eq_ignore_ascii_case("txt")))
+            .filter(|path| !files.iter().any(|f| f.path.eq(path)))
+            .map(|path| PlannedFile { path, status: ExportStatus::Stale, ..Default::default() })
+            .collect();
+        stale.sort_by(|a, b| a.path.cmp(&b.path));
+        files.append(&mut stale);
+
+        ExportPlan { folder: folder.to_path_buf(), files }
+    }
+
+    /// Files that will be written.
+    pub fn changes(&self) -> impl Iterator<Item = &PlannedFile> {
+        self.files.iter().filter(|f| matches!(f.status, ExportStatus::Create | ExportStatus::Change))
+    }
+
+    /// Write every created or changed file, each through a temporary file that is renamed over the original.
+    ///
+    /// If a file can't be written, the files written before it are put back as they were (created ones are
+    /// removed), so a failed export doesn't leave the mod half exported. Putting them back can itself fail if
+    /// the disk does; the backup is there for that.
+    ///
+    /// With `backup` the current graphics folder and info.txt are first copied to a timestamped folder
+    /// in `<mod folder>_backups/` next to the mod, so the backup doesn't ship with it. That folder is returned.
+    pub fn write(&self, backup: bool) -> Result<Option<PathBuf>> {
+        fs::DirBuilder::new()
+            .recursive(true)
+            .create(self.folder.join("graphics").join("images"))?;
+
+        let mut backup_folder = None;
+        if backup && self.changes().next().is_some() {
+            backup_folder = Some(backup_mod(&self.folder)?);
+        }
+
+        //contents of each written file from before the export, None for created files
+        let mut written: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
+        for planned_file in self.changes() {
+            let path = self.folder.join(&planned_file.path);
+            let old_bytes = fs::read(&path).ok();
+            if let Err(e) = write_atomic(&path, &planned_file.bytes) {
+                let mut not_restored = String::new();
+                for (path, old_bytes) in written.into_iter().rev() {
+                    let restored = match old_bytes {
+                        Some(old_bytes) => write_atomic(&path, &old_bytes),
+                        None => fs::remove_file(&path),
+                    };
+                    if restored.is_err() {
+                        not_restored.push_str(&format!("\t{}\n", path.display()));
+                    }
+                }
+
+                return Err(if not_restored.is_empty() {
+                    DFGHError::ExportRolledBack(planned_file.path.clone(), e.to_string())
+                } else {
+                    DFGHError::ExportPartlyWritten(planned_file.path.clone(), e.to_string(), not_restored)
+                });
+            }
+            written.push((path, old_bytes));
+        }
+
+        Ok(backup_folder)
+    }
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = (|| {
+        let mut temp_file = fs::File::create(&temp_path)?;
+        temp_file.write_all(bytes)?;
+        temp_file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn backup_mod(folder: &Path) -> io::Result<PathBuf> {
+    let mut backups_name = folder.file_name().unwrap_or_default().to_os_string();
+    backups_name.push("_backups");
+    let backups = folder.with_file_name(backups_name);
+    fs::create_dir_all(&backups)?;
+
+    //claiming the folder with create_dir keeps two exports in the same millisecond apart
+    let timestamp = timestamp();
+    let mut backup_folder = backups.join(&timestamp);
+    let mut count = 1;
+    loop {
+        match fs::create_dir(&backup_folder) {
+            Ok(()) => break,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                count += 1;
+                backup_folder = backups.join(format!("{}_{}", timestamp, count));
+            },
+            Err(e) => return Err(e),
+        }
+    }
+
+    if folder.join("graphics").is_dir() {
+        copy_dir(&folder.join("graphics"), &backup_folder.join("graphics"))?;
+    }
+    if folder.join("info.txt").is_file() {
+        fs::copy(folder.join("info.txt"), backup_folder.join("info.txt"))?;
+    }
+
+    Ok(backup_folder)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name()))?;
+        }
+    }
+
+    Ok(())
+}
+
+//UTC date and time as YYYY-MM-DD_HH-MM-SS-mmm
+fn timestamp() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = now.as_secs();
+    let (days, secs_of_day) = ((secs / 86400) as i64, secs % 86400);
+
+    //civil from days (Howard Hinnant)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}_{:02}-{:02}-{:02}-{:03}",
+        year, month, day,
+        secs_of_day / 3600, secs_of_day % 3600 / 60, secs_of_day % 60, now.subsec_millis()
+    )
+}
+
+/// Line diff of two texts with a line of context around each change.
+fn diff(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    //skip the common start and end so only the changed middle needs comparing
+    let prefix = old_lines.iter().zip(new_lines.iter()).take_while(|(o, n)| o == n).count();
+    let suffix = old_lines[prefix..].iter().rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(o, n)| o == n)
+        .count();
+    let old_mid = &old_lines[prefix..old_lines.len() - suffix];
+    let new_mid = &new_lines[prefix..new_lines.len() - suffix];
+
+    //(old line, new line) pairs: both for a kept line, only one for a removed or added line
+    let mut edits: Vec<(Option<usize>, Option<usize>)> = Vec::new();
+    edits.extend((0..prefix).map(|i| (Some(i), Some(i))));
+
+    if old_mid.len() * new_mid.len() <= 4_000_000 {
+        //longest common subsequence of the middle
+        let (n, m) = (old_mid.len(), new_mid.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                edits.push((Some(prefix + i), Some(prefix + j)));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                edits.push((Some(prefix + i), None));
+                i += 1;
+            } else {
+                edits.push((None, Some(prefix + j)));
+                j += 1;
+            }
+        }
+    } else {
+        //too large to compare line by line, replace the whole middle
+        edits.extend((0..old_mid.len()).map(|i| (Some(prefix + i), None)));
+        edits.extend((0..new_mid.len()).map(|j| (None, Some(prefix + j))));
+    }
+
+    let old_end = old_lines.len() - suffix;
+    let new_end = new_lines.len() - suffix;
+    edits.extend((0..suffix).map(|k| (Some(old_end + k), Some(new_end + k))));
+
+    //print changed lines with one line of context
+    let context = 1;
+    let changed: Vec<bool> = edits.iter().map(|e| e.0.is_none() || e.1.is_none()).collect();
+    let mut out = String::new();
+    let mut last_printed: Option<usize> = None;
+
+    for (i_edit, edit) in edits.iter().enumerate() {
+        let near_change = (i_edit.saturating_sub(context)..=(i_edit + context).min(edits.len() - 1))
+            .any(|k| changed[k]);
+        if !near_change {
+            continue;
+        }
+
+        if last_printed.map_or(true, |last| last + 1 != i_edit) {
+            let line = edit.1.or(edit.0).unwrap_or_default() + 1;
+            out.push_str(&format!("@@ line {} @@\n", line));
+        }
+        match edit {
+            (Some(o), Some(_)) => out.push_str(&format!("  {}\n", old_lines[*o])),
+            (Some(o), None) => out.push_str(&format!("- {}\n", old_lines[*o])),
+            (None, Some(n)) => out.push_str(&format!("+ {}\n", new_lines[*n])),
+            (None, None) => {},
+        }
+        last_printed = Some(i_edit);
+    }
+
+    out
+}
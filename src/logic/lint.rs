@@ -0,0 +1,481 @@
+use std::fmt;
+use std::path::Path;
+
+use egui::Ui;
+use serde::{Deserialize, Serialize};
+
+use super::error::{DFGHError, group_skipped_files};
+use super::vfs::FileSystem;
+use crate::{Graphics, GraphicsFile, ReferenceLibrary, TilePosition};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Error,      //breaks the graphics in game
+    #[default]
+    Warning,    //likely doesn't do what was meant
+    Info,
+}
+impl Severity {
+    pub fn name(&self) -> String {
+        match self {
+            Severity::Error => "Error".to_string(),
+            Severity::Warning => "Warning".to_string(),
+            Severity::Info => "Info".to_string(),
+        }
+    }
+
+    fn color(&self) -> egui::Color32 {
+        match self {
+            Severity::Error => egui::Color32::RED,
+            Severity::Warning => egui::Color32::YELLOW,
+            Severity::Info => egui::Color32::GRAY,
+        }
+    }
+}
+
+/// A problem found by a lint rule.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diagnostic {
+    pub rule: String,
+    pub severity: Severity,
+    pub location: String,   //e.g. "DWARF, layer set DEFAULT, layer HAIR"
+    pub message: String,
+    pub fix: Option<QuickFix>,
+}
+impl Diagnostic {
+    pub fn new(rule: &dyn LintRule, location: String, message: String) -> Self {
+        Diagnostic { rule: rule.id().to_string(), severity: rule.severity(), location, message, fix: None }
+    }
+
+    //errors of the graphics checks already start with their location
+    fn from_error(rule: &dyn LintRule, error: DFGHError) -> Self {
+        let location = match &error {
+            DFGHError::UndeclaredPalette(location, ..) |
+            DFGHError::PaletteRowOutOfRange(location, ..) |
+            DFGHError::UnknownItem(location, ..) |
+            DFGHError::UnknownCreature(location, ..) |
+            DFGHError::UnknownCaste(location, ..) |
+            DFGHError::UnknownBodyReference(location, ..) |
+            DFGHError::UnknownDescriptor(location, ..) |
+            DFGHError::UnknownMaterial(location, ..) |
+            DFGHError::MissingAppearanceModifier(location, ..) |
+            DFGHError::ImpossibleAppearanceRange(location, ..) |
+            DFGHError::TileOutOfBounds(location, ..) |
+            DFGHError::UnknownTilePage(location, ..) |
+            DFGHError::UnusedTilePage(location) => location.clone(),
+            DFGHError::OrphanImage(path) => format!("graphics/{}", path.display()),
+            _ => String::new(),
+        };
+        Diagnostic::new(rule, location, error.to_string())
+    }
+}
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}] {}", self.severity.name(), self.rule, self.message)
+    }
+}
+
+/// A fix for the one problem a diagnostic is about.
+///
+/// ```
+/// # use df_texture_helper::*;
+/// # use df_texture_helper::logic::lint::*;
+/// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+/// let mut files = MemoryFileSystem::new();
+/// files.insert(
+///     "Mod/graphics/tile_page_dwarf.txt",
+///     "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n\n[TILE_PAGE:DWARF]\n\t[FILE:images/dwarf.png]\n\t[TILE_DIM:32:32]\n\t[PAGE_DIM_PIXELS:128:64]\n"
+/// );
+/// files.insert(
+///     "Mod/graphics/graphics_creatures_dwarf.txt",
+///     "graphics_creatures_dwarf\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[LAYER_SET:DEFAULT]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:BODY:DWARF:5:0]\n\t\t[LAYER:CAPE:Dwarf:LARGE_IMAGE:2:0:4:1]\n"
+/// );
+/// let (mut graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+///
+/// let diagnostics = Linter::new().run(&graphics);
+/// let fixes: Vec<&QuickFix> = diagnostics.iter().filter_map(|d| d.fix.as_ref()).collect();
+/// assert_eq!(2, fixes.len());
+/// assert_eq!("Clamp to 3:0", fixes[0].name());
+/// assert_eq!("Clamp to 1:0 to 3:1", fixes[1].name());
+///
+/// //fixing one tile leaves the other one flagged
+/// fixes[1].apply(&mut graphics);
+/// assert_eq!(1, graphics.tile_bounds_errors().len());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum QuickFix {
+    //graphics file, tile in it as counted by the tile bounds check, its (coords, LARGE_IMAGE extent) now and once clamped
+    ClampTile(usize, usize, TilePosition, TilePosition),
+}
+impl QuickFix {
+    /// Button text, saying what the fix will change.
+    pub fn name(&self) -> String {
+        match self {
+            QuickFix::ClampTile(_, _, _, (coords, large_coords)) => match large_coords {
+                Some([x2, y2]) => format!("Clamp to {}:{} to {}:{}", coords[0], coords[1], coords[0].saturating_add(*x2), coords[1].saturating_add(*y2)),
+                None => format!("Clamp to {}:{}", coords[0], coords[1]),
+            },
+        }
+    }
+
+    /// Apply the fix, unless what it fixes has changed since the diagnostic was made.
+    pub fn apply(&self, graphics: &mut Graphics) {
+        match self {
+            QuickFix::ClampTile(i_file, i_tile, tile, _) => {
+                let unchanged = graphics.graphics_files
+                    .get(*i_file)
+                    .and_then(|gf| gf.clamped_tile(*i_tile, &graphics.shared))
+                    .is_some_and(|(current, _)| current.eq(tile));
+                if unchanged {
+                    graphics.clamp_tile(*i_file, *i_tile);
+                }
+            },
+        }
+    }
+}
+
+/// A check run over loaded graphics. Rules are independent of each other and can be turned off
+/// by id in the [`LintConfig`].
+pub trait LintRule {
+    /// Short kebab case name used in configs and diagnostics, e.g. `palette`.
+    fn id(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Whether the rule checks names that vanilla may define. Until the vanilla objects have been read
+    /// from a reference library, its errors are reported as warnings.
+    fn may_refer_to_vanilla(&self) -> bool {
+        false
+    }
+
+    fn check(&self, graphics: &Graphics) -> Vec<Diagnostic>;
+}
+
+/// What the lint window asks of the app.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LintAction {
+    Rerun,
+    Fix(QuickFix),
+    //graphics file and tile in it, to open in its menu where right clicks on the preview pick the tile
+    PickTile(usize, usize),
+}
+
+/// Which rules run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LintConfig {
+    pub disabled: Vec<String>,  //ids of the rules that don't run
+}
+impl LintConfig {
+    pub fn enabled(&self, id: &str) -> bool {
+        !self.disabled.iter().any(|d| d.eq(id))
+    }
+
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) {
+        self.disabled.retain(|d| d.ne(id));
+        if !enabled {
+            self.disabled.push(id.to_string());
+        }
+    }
+}
+
+/// Runs lint rules over graphics.
+///
+/// ```
+/// # use df_texture_helper::*;
+/// # use df_texture_helper::logic::lint::*;
+/// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+/// let mut files = MemoryFileSystem::new();
+/// files.insert(
+///     "Mod/graphics/graphics_creatures_dwarf.txt",
+///     "graphics_creatures_dwarf\n\n[OBJECT:GRAPHICS]\n\n[CREATURE_GRAPHICS:DWARF]\n\t[LAYER_SET:ANIMATED]\n\t\t[LAYER_GROUP]\n\t\t[LAYER:BODY:DWARF:0:0]\n\t\t\t[USE_PALETTE:SKIN:1]\n"
+/// );
+/// let (graphics, _) = Graphics::from_sources(&files, "Mod".as_ref());
+///
+/// let mut linter = Linter::new();
+/// let diagnostics = linter.run(&graphics);
+///
+/// assert_eq!(3, diagnostics.len());
+/// assert_eq!("palette", diagnostics[0].rule);
+/// assert_eq!(Severity::Error, diagnostics[0].severity);
+/// assert_eq!("DWARF, layer set ANIMATED, layer BODY", diagnostics[0].location);
+/// assert_eq!("layer-set-state", diagnostics[1].rule);
+///
+/// //the mod has no tile page DWARF, but vanilla might
+/// assert_eq!("unknown-tile-page", diagnostics[2].rule);
+/// assert_eq!(Severity::Warning, diagnostics[2].severity);
+///
+/// linter.config.set_enabled("palette", false);
+/// assert_eq!(2, linter.run(&graphics).len());
+/// ```
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+    pub config: LintConfig,
+}
+impl Default for Linter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Linter {
+    /// Linter with every built in rule.
+    pub fn new() -> Self {
+        Linter {
+            rules: vec![
+                Box::new(PaletteRule),
+                Box::new(LayerSetStateRule),
+                Box::new(TileBoundsRule),
+                Box::new(TileNameRule),
+                Box::new(UnusedTilePageRule),
+                Box::new(OrphanImageRule),
+                Box::new(StatueShapeRule),
+                Box::new(ItemRule),
+                Box::new(CreatureRule),
+                Box::new(BodyRule),
+                Box::new(DescriptorRule),
+                Box::new(MaterialRule),
+                Box::new(AppearanceModifierRule),
+            ],
+            config: LintConfig::default(),
+        }
+    }
+
+    pub fn add_rule(&mut self, rule: Box<dyn LintRule>) {
+        self.rules.push(rule);
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn LintRule> {
+        self.rules.iter().map(|r| r.as_ref())
+    }
+
+    /// Run every enabled rule, worst problems first.
+    ///
+    /// Names are checked against what the mod defines itself when no vanilla objects are known, as a
+    /// warning since the name may still be defined by vanilla.
+    pub fn run(&self, graphics: &Graphics) -> Vec<Diagnostic> {
+        let vanilla = graphics.shared.catalog.vanilla;
+        let mut diagnostics: Vec<Diagnostic> = self.rules
+            .iter()
+            .filter(|r| self.config.enabled(r.id()))
+            .flat_map(|r| {
+                let mut diagnostics = r.check(graphics);
+                if r.may_refer_to_vanilla() && !vanilla {
+                    for diagnostic in diagnostics.iter_mut() {
+                        diagnostic.severity = diagnostic.severity.max(Severity::Warning);
+                        diagnostic.message.push_str(" Vanilla isn't loaded, so it may be defined there.");
+                    }
+                }
+                diagnostics
+            })
+            .collect();
+        diagnostics.sort_by_key(|d| d.severity);
+
+        diagnostics
+    }
+
+    /// Lint a mod folder without the GUI, with the vanilla objects of a DF installation if one is given.
+    pub fn run_folder(&self, file_system: &dyn FileSystem, folder: &Path, df_folder: Option<&Path>) -> (Vec<Diagnostic>, Vec<DFGHError>) {
+        let (mut graphics, mut errors) = Graphics::from_sources(file_system, folder);
+        if let Some(df_folder) = df_folder {
+            let (library, library_errors) = ReferenceLibrary::from_sources(file_system, df_folder);
+            graphics.use_library_catalog(&library);
+            errors.extend(library_errors.into_iter().filter(|e| matches!(e, DFGHError::SkippedFileError(..) | DFGHError::SkippedFiles(..))));
+        }
+
+        (self.run(&graphics), group_skipped_files(errors))
+    }
+
+    /// Rule toggles and the diagnostics of the last run, with a button for each that has a quick fix.
+    pub fn menu(&mut self, ui: &mut Ui, diagnostics: &[Diagnostic]) -> Option<LintAction> {
+        let mut action = None;
+
+        egui::CollapsingHeader::new("Rules").show(ui, |ui| {
+            for rule in &self.rules {
+                let mut enabled = self.config.enabled(rule.id());
+                if ui.checkbox(&mut enabled, rule.id()).on_hover_text(rule.description()).changed() {
+                    self.config.set_enabled(rule.id(), enabled);
+                    action = Some(LintAction::Rerun);
+                }
+            }
+        });
+        ui.separator();
+
+        if diagnostics.is_empty() {
+            ui.label("No problems found.");
+        }
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .show(ui, |ui| {
+            for diagnostic in diagnostics {
+                ui.horizontal(|ui| {
+                    ui.colored_label(diagnostic.severity.color(), diagnostic.severity.name());
+                    ui.weak(&diagnostic.rule);
+                    ui.label(&diagnostic.message);
+                    if let Some(fix) = &diagnostic.fix {
+                        if ui.button(fix.name()).clicked() {
+                            action = Some(LintAction::Fix(fix.clone()));
+                        }
+                        match fix {
+                            QuickFix::ClampTile(i_file, i_tile, ..) => if ui.button("Pick Tile").clicked() {
+                                action = Some(LintAction::PickTile(*i_file, *i_tile));
+                            },
+                        }
+                    }
+                });
+            }
+        });
+
+        action
+    }
+}
+
+struct PaletteRule;
+impl LintRule for PaletteRule {
+    fn id(&self) -> &'static str { "palette" }
+
+    fn description(&self) -> &'static str {
+        "USE_PALETTE names a palette the layer set doesn't declare, or a row the palette image doesn't have."
+    }
+
+    fn severity(&self) -> Severity { Severity::Error }
+
+    fn check(&self, graphics: &Graphics) -> Vec<Diagnostic> {
+        graphics.graphics_files
+            .iter()
+            .flat_map(|gf| gf.palette_errors())
+            .map(|e| Diagnostic::from_error(self, e))
+            .collect()
+    }
+}
+
+struct LayerSetStateRule;
+impl LintRule for LayerSetStateRule {
+    fn id(&self) -> &'static str { "layer-set-state" }
+
+    fn description(&self) -> &'static str {
+        "Layer set states other than DEFAULT, PORTRAIT, CORPSE, BABY:DEFAULT and CHILD:DEFAULT aren't known to work (v51.01beta20)."
+    }
+
+    fn check(&self, graphics: &Graphics) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for graphics_file in &graphics.graphics_files {
+            if let GraphicsFile::CreatureFile(_, creatures, _) = graphics_file {
+                for creature in creatures {
+                    for layer_set in creature.layer_sets.iter().filter(|ls| !ls.state_known_to_work()) {
+                        let state = match &layer_set.sub_state {
+                            Some(sub_state) => format!("{}:{}", layer_set.state.name(), sub_state.name()),
+                            None => layer_set.state.name(),
+                        };
+                        let location = format!("{}, layer set {}", creature.name, state);
+                        let message = format!("{} uses state {}, which isn't known to work.", location, state);
+                        diagnostics.push(Diagnostic::new(self, location, message));
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+struct TileBoundsRule;
+impl LintRule for TileBoundsRule {
+    fn id(&self) -> &'static str { "tile-bounds" }
+
+    fn description(&self) -> &'static str {
+        "Tile coordinates or LARGE_IMAGE rectangles reaching past the tile grid of their page."
+    }
+
+    fn severity(&self) -> Severity { Severity::Error }
+
+    fn check(&self, graphics: &Graphics) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (i_file, graphics_file) in graphics.graphics_files.iter().enumerate() {
+            for (i_tile, e) in graphics_file.tile_bounds_errors(&graphics.shared) {
+                let mut diagnostic = Diagnostic::from_error(self, e);
+                diagnostic.fix = graphics_file
+                    .clamped_tile(i_tile, &graphics.shared)
+                    .map(|(tile, clamped)| QuickFix::ClampTile(i_file, i_tile, tile, clamped));
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        diagnostics
+    }
+}
+
+//rules wrapping one of the checks of Graphics
+macro_rules! graphics_rule {
+    ($rule:ident, $id:expr, $severity:expr, $may_refer_to_vanilla:expr, $check:ident, $description:expr) => {
+        struct $rule;
+        impl LintRule for $rule {
+            fn id(&self) -> &'static str { $id }
+
+            fn description(&self) -> &'static str { $description }
+
+            fn severity(&self) -> Severity { $severity }
+
+            fn may_refer_to_vanilla(&self) -> bool { $may_refer_to_vanilla }
+
+            fn check(&self, graphics: &Graphics) -> Vec<Diagnostic> {
+                graphics.$check()
+                    .into_iter()
+                    .map(|e| Diagnostic::from_error(self, e))
+                    .collect()
+            }
+        }
+    };
+}
+graphics_rule!(TileNameRule, "unknown-tile-page", Severity::Error, true, tile_name_errors,
+    "Tile names that no tile page is called, which DF silently draws nothing for.");
+graphics_rule!(UnusedTilePageRule, "unused-tile-page", Severity::Info, false, unused_tile_pages,
+    "Tile pages of the mod that no graphics use.");
+graphics_rule!(OrphanImageRule, "orphan-image", Severity::Info, false, orphan_images,
+    "Images in graphics/images that no tile page or palette uses.");
+
+struct StatueShapeRule;
+impl LintRule for StatueShapeRule {
+    fn id(&self) -> &'static str { "statue-shape" }
+
+    fn description(&self) -> &'static str {
+        "Statue tiles that aren't the 1 wide and 2 tall shape DF draws statues with."
+    }
+
+    fn check(&self, graphics: &Graphics) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for graphics_file in &graphics.graphics_files {
+            if let GraphicsFile::StatueCreatureFile(_, statues, _) = graphics_file {
+                for statue in statues {
+                    for tile in &statue.tiles {
+                        if let Some(e) = tile.shape_warning() {
+                            let location = format!("Statue {}, {}", statue.creature_name, tile.state.name());
+                            let message = format!("{}: {}", location, e);
+                            diagnostics.push(Diagnostic::new(self, location, message));
+                        }
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+//references into the raws, only checked once the mod or vanilla defines something of the kind
+graphics_rule!(ItemRule, "unknown-item", Severity::Error, true, item_errors,
+    "Item conditions naming items the item raws don't define.");
+graphics_rule!(CreatureRule, "unknown-creature", Severity::Error, true, creature_errors,
+    "Graphics and caste conditions for creatures or castes the creature raws don't define.");
+graphics_rule!(BodyRule, "unknown-body-part", Severity::Error, true, body_errors,
+    "Conditions selecting body parts or tissues the creature's body raws don't give it.");
+graphics_rule!(DescriptorRule, "unknown-descriptor", Severity::Warning, true, descriptor_errors,
+    "Colors and shapings the descriptor and creature raws don't define.");
+graphics_rule!(MaterialRule, "unknown-material", Severity::Warning, true, material_errors,
+    "Syndrome classes and materials the raws don't define.");
+graphics_rule!(AppearanceModifierRule, "appearance-modifier", Severity::Warning, true, appearance_errors,
+    "BP_APPEARANCE_MODIFIER_RANGE conditions the creature's appearance modifiers make dead or impossible.");
@@ -1,3 +1,7 @@
 pub mod app;
+pub mod catalog;
 pub mod error;
-pub mod tests;
\ No newline at end of file
+pub mod export;
+pub mod lint;
+pub mod tests;
+pub mod vfs;
\ No newline at end of file
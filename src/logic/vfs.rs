@@ -0,0 +1,158 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::{fs, io::Cursor};
+
+/// Source of the files in a mod folder.
+///
+/// Lets graphics be parsed from a mod on disk or from buffers held in memory (tests, archives, tooling).
+pub trait FileSystem {
+    /// Paths of the files and folders directly inside a folder, with the errors of the entries that
+    /// couldn't be read.
+    fn read_dir_entries(&self, dir: &Path) -> io::Result<(Vec<PathBuf>, Vec<io::Error>)>;
+
+    /// Paths of the files and folders directly inside a folder, leaving out entries that couldn't be
+    /// read. Use [`FileSystem::read_dir_entries`] where those should be reported.
+    fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(self.read_dir_entries(dir)?.0)
+    }
+
+    /// Full contents of a file.
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+    fn is_file(&self, path: &Path) -> bool;
+
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// Width and height of an image without decoding the whole thing.
+    fn image_dimensions(&self, path: &Path) -> image::ImageResult<(u32, u32)> {
+        let bytes = self.read(path)?;
+        image::io::Reader::new(Cursor::new(bytes))
+            .with_guessed_format()?
+            .into_dimensions()
+    }
+
+    fn open_image(&self, path: &Path) -> image::ImageResult<image::DynamicImage> {
+        let bytes = self.read(path)?;
+        image::io::Reader::new(Cursor::new(bytes))
+            .with_guessed_format()?
+            .decode()
+    }
+}
+
+/// Files read straight from the disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DiskFileSystem;
+impl FileSystem for DiskFileSystem {
+    fn read_dir_entries(&self, dir: &Path) -> io::Result<(Vec<PathBuf>, Vec<io::Error>)> {
+        //an entry that can't be read doesn't fail the whole folder
+        let mut paths = Vec::new();
+        let mut errors = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            match entry {
+                Ok(entry) => paths.push(entry.path()),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        Ok((paths, errors))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+}
+
+/// Named buffers standing in for the files of a mod folder.
+///
+/// ```
+/// # use df_texture_helper::logic::vfs::*;
+/// # use std::path::Path;
+/// let mut files = MemoryFileSystem::new();
+/// files.insert("graphics/tile_page_dwarf.txt", "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n");
+/// files.insert("graphics/images/dwarf.png", Vec::new());
+///
+/// assert!(files.is_dir(Path::new("graphics/images")));
+/// assert_eq!(2, files.read_dir(Path::new("graphics")).unwrap().len());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MemoryFileSystem {
+    files: BTreeMap<PathBuf, Vec<u8>>,
+    unreadable: BTreeSet<PathBuf>,  //listed in their folder, but reading them fails
+}
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a file. Folders are implied by the paths of the files inside them.
+    pub fn insert(&mut self, path: impl Into<PathBuf>, contents: impl Into<Vec<u8>>) {
+        self.files.insert(path.into(), contents.into());
+    }
+
+    /// Add a file that shows up in its folder but can't be read, like one without read permission.
+    ///
+    /// ```
+    /// # use df_texture_helper::*;
+    /// # use df_texture_helper::logic::error::DFGHError;
+    /// # use df_texture_helper::logic::vfs::MemoryFileSystem;
+    /// let mut files = MemoryFileSystem::new();
+    /// files.insert("Mod/graphics/tile_page_dwarf.txt", "tile_page_dwarf\n\n[OBJECT:TILE_PAGE]\n");
+    /// files.insert_unreadable("Mod/graphics/graphics_creatures_dwarf.txt");
+    ///
+    /// let (graphics, errors) = Graphics::from_sources(&files, "Mod".as_ref());
+    ///
+    /// //the unreadable file is skipped and reported, the rest of the mod is still read
+    /// assert_eq!(1, graphics.tile_page_files.len());
+    /// assert!(graphics.graphics_files.is_empty());
+    /// assert!(matches!(&errors[..], [DFGHError::SkippedFileError(path, _)] if path.ends_with("graphics_creatures_dwarf.txt")));
+    /// ```
+    pub fn insert_unreadable(&mut self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        self.files.insert(path.clone(), Vec::new());
+        self.unreadable.insert(path);
+    }
+}
+impl FileSystem for MemoryFileSystem {
+    fn read_dir_entries(&self, dir: &Path) -> io::Result<(Vec<PathBuf>, Vec<io::Error>)> {
+        if !self.is_dir(dir) {
+            return Err(io::Error::new(io::ErrorKind::NotFound, format!("{} not found", dir.display())));
+        }
+
+        let mut entries: Vec<PathBuf> = self.files
+            .keys()
+            .filter_map(|path| path.strip_prefix(dir).ok())
+            .filter_map(|relative| relative.components().next())
+            .map(|child| dir.join(child))
+            .collect();
+        entries.dedup();
+
+        Ok((entries, Vec::new()))
+    }
+
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        if self.unreadable.contains(path) {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, format!("{} can't be read", path.display())));
+        }
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or(io::Error::new(io::ErrorKind::NotFound, format!("{} not found", path.display())))
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.contains_key(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.files.keys().any(|file| file.starts_with(path) && file.ne(path))
+    }
+}
@@ -4,6 +4,8 @@ use egui::ViewportBuilder;
 use eframe::{NativeOptions, run_native, Result};
 
 use df_texture_helper::logic::app::DFGraphicsHelper;
+use df_texture_helper::logic::lint::{Linter, Severity};
+use df_texture_helper::logic::vfs::DiskFileSystem;
 
 fn load_icon() -> egui::IconData {
     let icon_bytes = include_bytes!("../icons/DFGH_icon.png");
@@ -18,7 +20,71 @@ fn load_icon() -> egui::IconData {
     }
 }
 
+//release builds on Windows have no console of their own, so --lint writes to the one it was started from
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    //fails harmlessly if there is no parent console or one is already attached
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+//df_texture_helper --lint <mod folder> [--df <df folder>] [--disable <rule id>]..
+fn lint(args: &[String]) -> i32 {
+    let mut linter = Linter::new();
+    let mut folder = None;
+    let mut df_folder = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--df" => df_folder = args.next().map(std::path::PathBuf::from),
+            "--disable" => {
+                if let Some(id) = args.next() {
+                    linter.config.set_enabled(id, false);
+                }
+            },
+            _ => folder = Some(std::path::PathBuf::from(arg)),
+        }
+    }
+
+    let Some(folder) = folder else {
+        eprintln!("Usage: df_texture_helper --lint <mod folder> [--df <df folder>] [--disable <rule id>]..");
+        eprintln!("Rules:");
+        for rule in linter.rules() {
+            eprintln!("\t{}\t{}", rule.id(), rule.description());
+        }
+        return 2;
+    };
+
+    let (diagnostics, errors) = linter.run_folder(&DiskFileSystem, &folder, df_folder.as_deref());
+    for error in &errors {
+        eprintln!("{}\n", error);
+    }
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic);
+    }
+
+    if diagnostics.iter().any(|d| d.severity == Severity::Error) { 1 } else { 0 }
+}
+
 fn main() -> Result<()> { //eframe::Result
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|a| a.eq("--lint")) {
+        attach_console();
+        std::process::exit(lint(&args[1..]));
+    }
+
     let native_options = NativeOptions {
         viewport: ViewportBuilder::default()
             .with_maximized(true)